@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+use bevy::reflect::{EnumInfo, TypeInfo, VariantInfo};
+
+/// Collation of a text column, used for comparisons and sorting. Sqlite applies the collation
+/// of a column to every comparison, `ORDER BY` and unique constraint involving it.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Player {
+///     #[reflect(@Collate::NoCase)]
+///     name: String,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collate {
+    /// Compare the bytes of the text (sqlite default).
+    #[default]
+    Binary,
+    /// Ignore the case of ASCII characters.
+    NoCase,
+    /// Ignore trailing spaces.
+    RTrim,
+}
+
+impl Collate {
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            Collate::Binary => "BINARY",
+            Collate::NoCase => "NOCASE",
+            Collate::RTrim => "RTRIM",
+        }
+    }
+}
+
+/// Store a `bool` field as TEXT `'true'`/`'false'` instead of INTEGER 0/1. This is meant for
+/// mapping onto existing databases, which were not created by this crate.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Player {
+///     #[reflect(@BoolAsText)]
+///     active: bool,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoolAsText;
+
+/// Store an `Option<Entity>` relation as the `Name` of the related entity instead of its bits.
+/// Names are resolved using the `EntityNames` of the database
+/// (see `SqliteDatabase::set_entity_names`).
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Room {
+///     #[reflect(@RelateByName)]
+///     building: Option<Entity>,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RelateByName;
+
+/// Marks an `i64` field storing the checksum of all other columns of the row. The checksum is
+/// computed on insert and can be checked using `SqliteDatabase::verify_row`.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct SaveGame {
+///     gold: i32,
+///     #[reflect(@Checksum)]
+///     checksum: i64,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checksum;
+
+/// Store a `Vec2`, `Vec3` or `Vec4` field in one REAL column per component instead of a blob.
+/// The columns are named after the field and the component, e.g. `pos_x`, `pos_y`, `pos_z`,
+/// which makes them readable and usable in queries (`WHERE pos_x > 100`).
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Marker {
+///     #[reflect(@Flatten)]
+///     pos: Vec3,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Flatten;
+
+/// Store a `Quat` field as its Euler angles (XYZ order, in radians) in three REAL columns named
+/// after the field, e.g. `rotation_x`, `rotation_y`, `rotation_z`, instead of a blob.
+///
+/// The angles are readable and can be edited by hand, but the conversion is lossy: the read
+/// rotation is only equal to the written one within floating point precision, and near gimbal
+/// lock (a Y angle of ±90°) different angles describe the same rotation, so the stored angles
+/// may differ from the ones used to create the rotation. Use the default blob form to store
+/// rotations exactly.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Marker {
+///     #[reflect(@EulerAngles)]
+///     rotation: Quat,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EulerAngles;
+
+/// Store the fields of a sub-struct directly in the table of the parent, using their own names
+/// without a prefix. Fields whose names collide with other columns of the table are rejected.
+/// Only primitive fields and `String` can be inlined.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Player {
+///     name: String,
+///     #[reflect(@Inline)]
+///     stats: Stats,
+/// }
+///
+/// #[derive(Reflect)]
+/// struct Stats {
+///     strength: i32,
+///     agility: i32,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Inline;
+
+/// Store a fieldless enum as the INTEGER discriminant of its variants instead of its name.
+///
+/// Reflection does not expose the discriminants declared in Rust, so the index of a variant is
+/// stored, unless the variant declares its discriminant using the `Discriminant` attribute.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// #[reflect(@IntegerEnum)]
+/// #[repr(u8)]
+/// enum Class {
+///     Warrior,
+///     #[reflect(@Discriminant(10))]
+///     Mage = 10,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntegerEnum;
+
+/// The discriminant stored for a variant of an `IntegerEnum`.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Discriminant(pub i64);
+
+/// Store an enum whose variants all hold a single value of the same type in two columns: the
+/// name of the variant as TEXT and the value in a column with the suffix `_value`. Only
+/// variants holding exactly one primitive value or `String` are supported.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// #[reflect(@TaggedEnum)]
+/// enum Damage {
+///     Physical(i32),
+///     Magical(i32),
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaggedEnum;
+
+/// Pack a `bool` field into a single bit of an INTEGER column shared with other flags, given
+/// by the name of the column and the position of the bit (0 to 63). Positions are explicit, so
+/// reordering or adding fields does not change the meaning of stored values. The table has to
+/// be created with `TableSqlOptions::for_type`.
+///
+/// ```ignore
+/// #[derive(Reflect)]
+/// struct Door {
+///     #[reflect(@PackedBit("flags", 0))]
+///     locked: bool,
+///     #[reflect(@PackedBit("flags", 1))]
+///     hidden: bool,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PackedBit(pub &'static str, pub u8);
+
+/// Get the attribute of type `A` of the struct field with the given name.
+pub(crate) fn get_field_attribute<'a, A: Reflect>(
+    type_info: &'a TypeInfo,
+    field: &str,
+) -> Option<&'a A> {
+    match type_info {
+        TypeInfo::Struct(info) => info.field(field)?.get_attribute::<A>(),
+        _ => None,
+    }
+}
+
+/// The info of the given type, if it is a fieldless enum marked as `IntegerEnum`.
+pub(crate) fn get_integer_enum_info(type_info: &TypeInfo) -> Option<&EnumInfo> {
+    let TypeInfo::Enum(info) = type_info else {
+        return None;
+    };
+
+    if !info.has_attribute::<IntegerEnum>()
+        || !info.iter().all(|v| matches!(v, VariantInfo::Unit(_)))
+    {
+        return None;
+    }
+
+    Some(info)
+}
+
+/// The discriminant stored for the variant with the given index.
+pub(crate) fn get_discriminant(info: &EnumInfo, index: usize) -> Option<i64> {
+    let variant = info.variant_at(index)?;
+    match variant.get_attribute::<Discriminant>() {
+        Some(discriminant) => Some(discriminant.0),
+        None => Some(index as i64),
+    }
+}
+
+/// The name of the variant storing the given discriminant.
+pub(crate) fn get_variant_name(info: &EnumInfo, discriminant: i64) -> Option<&'static str> {
+    (0..info.variant_len())
+        .find(|index| get_discriminant(info, *index) == Some(discriminant))
+        .and_then(|index| info.variant_at(index))
+        .map(|variant| variant.name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_discriminant, get_field_attribute, get_integer_enum_info, get_variant_name, Collate,
+        Discriminant, IntegerEnum,
+    };
+    use bevy::prelude::*;
+    use bevy::reflect::Typed;
+
+    #[derive(Default, Reflect)]
+    struct Player {
+        #[reflect(@Collate::NoCase)]
+        name: String,
+        email: String,
+    }
+
+    #[test]
+    fn test_get_field_attribute() {
+        let info = Player::type_info();
+        assert_eq!(
+            get_field_attribute::<Collate>(info, "name"),
+            Some(&Collate::NoCase)
+        );
+        assert_eq!(get_field_attribute::<Collate>(info, "email"), None);
+        assert_eq!(get_field_attribute::<Collate>(info, "level"), None);
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(@IntegerEnum)]
+    #[repr(u8)]
+    enum Class {
+        #[default]
+        Warrior,
+        #[reflect(@Discriminant(10))]
+        Mage = 10,
+    }
+
+    #[derive(Default, Reflect)]
+    enum Stance {
+        #[default]
+        Idle,
+    }
+
+    #[test]
+    fn test_integer_enum() {
+        let info = get_integer_enum_info(Class::type_info()).unwrap();
+        assert_eq!(get_discriminant(info, 0), Some(0));
+        assert_eq!(get_discriminant(info, 1), Some(10));
+        assert_eq!(get_discriminant(info, 2), None);
+        assert_eq!(get_variant_name(info, 10), Some("Mage"));
+        assert_eq!(get_variant_name(info, 1), None);
+
+        assert!(get_integer_enum_info(Stance::type_info()).is_none());
+        assert!(get_integer_enum_info(Player::type_info()).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! Rows of types with a field marked as `Checksum` carry a hash of their values. Rows edited
+//! outside of the game can be detected by comparing the stored checksum with a recomputed one.
+//! This is an obfuscation against casual editing of save files, not real security: anybody
+//! knowing the algorithm can compute a valid checksum for an edited row.
+
+use crate::prelude::{SqliteDatabase, SqliteDbError, ValueWrapper};
+use bevy::prelude::*;
+use bevy_erm::prelude::TableDefinition;
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use rusqlite::ToSql;
+
+/// FNV-1a is used instead of the std hasher, whose output may change between Rust releases.
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub fn new() -> Self {
+        Fnv1a(0xcbf29ce484222325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Compute the checksum of all values except the checksum column itself. Columns are hashed
+/// ordered by name, including their names, so the result does not depend on the order of the
+/// table definition.
+pub(crate) fn compute_checksum(
+    names: &[String],
+    wrapped: &[ValueWrapper],
+) -> Result<u64, SqliteDbError> {
+    let mut columns: Vec<usize> = (0..names.len())
+        .filter(|x| !wrapped[*x].is_checksum())
+        .collect();
+    columns.sort_by_key(|x| &names[*x]);
+
+    let mut hasher = Fnv1a::new();
+    for x in columns {
+        hasher.write(names[x].as_bytes());
+
+        let output = wrapped[x].to_sql().map_err(SqliteDbError::from_execute)?;
+        let value = match &output {
+            ToSqlOutput::Borrowed(v) => *v,
+            ToSqlOutput::Owned(v) => ValueRef::from(v),
+            _ => return Err(format!("Cannot compute the checksum of column {}.", names[x]).into()),
+        };
+
+        match value {
+            ValueRef::Null => hasher.write(&[0]),
+            ValueRef::Integer(v) => {
+                hasher.write(&[1]);
+                hasher.write(&v.to_le_bytes());
+            }
+            ValueRef::Real(v) => {
+                hasher.write(&[2]);
+                hasher.write(&v.to_bits().to_le_bytes());
+            }
+            ValueRef::Text(v) => {
+                hasher.write(&[3]);
+                hasher.write(&(v.len() as u64).to_le_bytes());
+                hasher.write(v);
+            }
+            ValueRef::Blob(v) => {
+                hasher.write(&[4]);
+                hasher.write(&(v.len() as u64).to_le_bytes());
+                hasher.write(v);
+            }
+        }
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Store the checksum of all other values in the checksum column, if the type has one.
+pub(crate) fn apply_checksum(
+    names: &[String],
+    wrapped: &mut [ValueWrapper],
+) -> Result<(), SqliteDbError> {
+    let Some(x) = wrapped.iter().position(|x| x.is_checksum()) else {
+        return Ok(());
+    };
+
+    let checksum = compute_checksum(names, wrapped)?;
+    wrapped[x].set_value(rusqlite::types::Value::Integer(checksum as i64));
+
+    Ok(())
+}
+
+impl SqliteDatabase {
+    /// Recompute the checksum of a value read from the database and compare it with the
+    /// checksum it was stored with. The key is not part of the checksum.
+    pub fn verify_row<T: Reflect + TypePath + Struct>(
+        &self,
+        def: &TableDefinition,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<bool, SqliteDbError> {
+        let (names, mut wrapped) = Self::get_insert_values(def, value, registry);
+        for x in wrapped.iter_mut() {
+            x.resolve_name(self.get_entity_names())?;
+            x.convert(self.get_converters());
+        }
+
+        let Some(stored) = wrapped.iter().find(|x| x.is_checksum()) else {
+            return Err(SqliteDbError::NoChecksumColumn(def.sql_name.clone()));
+        };
+
+        let stored = match stored.to_sql().map_err(SqliteDbError::from_execute)? {
+            ToSqlOutput::Owned(rusqlite::types::Value::Integer(v)) => v as u64,
+            _ => return Err("The checksum has to be stored in an i64 field.".into()),
+        };
+
+        Ok(stored == compute_checksum(&names, &wrapped)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fnv1a;
+
+    #[test]
+    fn test_fnv1a() {
+        let mut hasher = Fnv1a::new();
+        assert_eq!(hasher.finish(), 0xcbf29ce484222325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+    }
+}
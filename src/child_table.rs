@@ -0,0 +1,923 @@
+//! Lists of structs (e.g. `Vec<InventorySlot>`) can be stored normalized in a child table named
+//! `<Parent>_<field>`. Every element is stored as one row, referencing the parent by its key and
+//! keeping its position within the list. The list field itself is not part of the parent table.
+
+use crate::checksum::apply_checksum;
+use crate::junction_table::is_many_to_many;
+use crate::migration::get_column_name;
+use crate::plugin::{is_zero_sized, sort_columns};
+use crate::prelude::{SqliteDatabase, SqliteDbError, TableSqlOptions};
+use crate::spawn::get_key_value;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy_erm::prelude::{ColumnDefinition, SqlType, TableDefinition};
+use rusqlite::types::FromSql;
+use rusqlite::ToSql;
+
+/// The column of a child table referencing the key of the parent row.
+pub const CHILD_PARENT_COLUMN: &str = "parent_id";
+
+/// The column of a child table storing the index of an element within the parent's list.
+pub const CHILD_POSITION_COLUMN: &str = "position";
+
+/// The column of an entity list table storing the bits of each entity.
+pub const CHILD_ENTITY_COLUMN: &str = "entity";
+
+/// The column of an entity map table storing the value of each entity.
+pub const CHILD_VALUE_COLUMN: &str = "value";
+
+/// List fields (`Vec<T>`), entity maps (`EntityHashMap<V>`) and many to many relations cannot
+/// be stored in a column of the parent table. They are skipped when creating, inserting and
+/// reading the parent table and stored in a child or junction table instead.
+pub(crate) fn is_stored_in_child_table(def: &ColumnDefinition) -> bool {
+    let is_list = matches!(def.sql_type, SqlType::None)
+        && (def.ty.path().starts_with("alloc::vec::Vec<") || is_entity_map(def));
+
+    is_list || is_many_to_many(def)
+}
+
+fn is_entity_map(def: &ColumnDefinition) -> bool {
+    let path = def.ty.path();
+    path.contains("HashMap<bevy_ecs::entity::Entity, ")
+        && path.ends_with(", bevy_ecs::entity::hash::EntityHash>")
+}
+
+impl SqliteDatabase {
+    /// The name of the child table storing the list `field` of the parent table.
+    pub fn get_child_table_name(parent: &TableDefinition, field: &str) -> String {
+        format!("{}_{}", parent.sql_name, field)
+    }
+
+    /// Generate the SQL to create the child table storing the list `field` of the parent table.
+    /// The key column of the child definition (if any) is omitted, because elements are
+    /// identified by the parent key and their position. The options apply to the child type,
+    /// e.g. `TableSqlOptions::for_type::<InventorySlot>()`.
+    pub fn get_child_table_sql(
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+        options: &TableSqlOptions,
+    ) -> Result<String, SqliteDbError> {
+        let columns = Self::get_table_column_sqls(Self::get_child_columns(child), options)?;
+        Self::get_list_table_sql(parent, field, columns)
+    }
+
+    /// Generate the SQL for a table storing the list `field` of the parent table. Besides the
+    /// given element columns it contains the parent key and the position of each element.
+    fn get_list_table_sql(
+        parent: &TableDefinition,
+        field: &str,
+        element_columns: Vec<String>,
+    ) -> Result<String, SqliteDbError> {
+        let Some(key) = parent.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(parent.sql_name.clone()));
+        };
+
+        let mut columns: Vec<String> = vec![
+            format!(
+                "{CHILD_PARENT_COLUMN} INTEGER NOT NULL REFERENCES '{}'({})",
+                parent.sql_name, key.sql_name
+            ),
+            format!("{CHILD_POSITION_COLUMN} INTEGER NOT NULL"),
+        ];
+        columns.extend(element_columns);
+        columns.push(format!(
+            "PRIMARY KEY({CHILD_PARENT_COLUMN}, {CHILD_POSITION_COLUMN})"
+        ));
+
+        let table_name = Self::get_child_table_name(parent, field);
+        let column_defs = columns.join(",\n");
+        Ok(format!("CREATE TABLE '{table_name}'({column_defs});"))
+    }
+
+    /// Create the child table for the list `field` of the parent table, if it does not exist.
+    pub fn create_child_table(
+        &mut self,
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+    ) -> Result<(), SqliteDbError> {
+        self.create_child_table_with_options(parent, child, field, &TableSqlOptions::default())
+    }
+
+    /// Same as create_child_table, but generates the child table using the given options.
+    pub fn create_child_table_with_options(
+        &mut self,
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+        options: &TableSqlOptions,
+    ) -> Result<(), SqliteDbError> {
+        let options = self.get_sql_options(options);
+        let table_sql = Self::get_child_table_sql(parent, child, field, &options)?;
+        self.create_list_table(parent, field, &table_sql)
+    }
+
+    /// Create the child table storing the entity list `field` (e.g. `Vec<Entity>`) of the
+    /// parent table, if it does not exist. Entities are stored by their bits.
+    pub fn create_entity_table(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+    ) -> Result<(), SqliteDbError> {
+        let columns = vec![format!("{CHILD_ENTITY_COLUMN} INTEGER NOT NULL")];
+        let table_sql = Self::get_list_table_sql(parent, field, columns)?;
+        self.create_list_table(parent, field, &table_sql)
+    }
+
+    fn create_list_table(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+        table_sql: &str,
+    ) -> Result<(), SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+        if self.table_exists(&table_name)? {
+            info!("A table with the name {table_name} already exists");
+            return Ok(());
+        }
+
+        self.execute(table_sql, &[]).map(|_| ())
+    }
+
+    /// Replace all elements stored for the parent with the given key by `children`.
+    /// Returns the number of inserted rows. Elements are written like rows of their own table
+    /// (entity names, converters and checksums apply). Either all elements are replaced or
+    /// none of them.
+    pub fn insert_children<T: Reflect + TypePath + Struct>(
+        &mut self,
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+        parent_key: &dyn ToSql,
+        children: &[T],
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+        self.savepoint("insert_children", |database| {
+            database.delete_children(&table_name, parent_key)?;
+
+            let mut inserted = 0;
+            for (position, value) in children.iter().enumerate() {
+                let (names_vec, mut wrapped_values) =
+                    Self::get_insert_values(child, value, registry);
+                database.resolve_entity_names(&mut wrapped_values)?;
+                apply_checksum(&names_vec, &mut wrapped_values)?;
+                let position = position as i64;
+
+                let mut column_names = vec![CHILD_PARENT_COLUMN, CHILD_POSITION_COLUMN];
+                column_names.extend(names_vec.iter().map(|x| x.as_str()));
+                let parameter: Vec<&str> = column_names.iter().map(|_| "?").collect();
+
+                let query = format!(
+                    "INSERT INTO '{}' ({}) VALUES ({});",
+                    table_name,
+                    column_names.join(", "),
+                    parameter.join(", ")
+                );
+
+                let mut params: Vec<&dyn ToSql> = vec![parent_key, &position];
+                params.extend(wrapped_values.iter().map(|x| x as &dyn ToSql));
+
+                inserted += database.execute(&query, &params)?;
+            }
+
+            Ok(inserted)
+        })
+    }
+
+    /// Insert the value together with the elements of its list `field` (a `Vec<C>`). Returns
+    /// the key of the new parent row. Either the parent and all elements are inserted or
+    /// nothing is.
+    pub fn insert_with_children<
+        T: Reflect + Default + TypePath + Struct,
+        C: Reflect + TypePath + Struct,
+    >(
+        &mut self,
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<i64, SqliteDbError> {
+        let Some(children) = value
+            .field(field)
+            .and_then(|x| x.try_downcast_ref::<Vec<C>>())
+        else {
+            return Err(format!(
+                "Field {field} of {} is not a list of {}.",
+                parent.sql_name,
+                C::short_type_path()
+            )
+            .into());
+        };
+
+        self.savepoint("insert_with_children", |database| {
+            database.insert(parent, value, registry)?;
+            let key = database
+                .query_scalar::<i64>("SELECT last_insert_rowid();", &[])?
+                .unwrap_or(0);
+            database.insert_children(parent, child, field, &key, children, registry)?;
+
+            Ok(key)
+        })
+    }
+
+    /// Same as query, but fills the list `field` (a `Vec<C>`) of every parent with the elements
+    /// stored for it.
+    pub fn query_with_children<T: Default + Reflect + Struct, C: Default + Reflect + TypePath>(
+        &mut self,
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let Some(key) = parent.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(parent.sql_name.clone()));
+        };
+
+        let mut values: Vec<T> = self.query(parent, query, parameter)?;
+        for value in values.iter_mut() {
+            let Some(parent_key) = get_key_value(value, &key.rust_name) else {
+                return Err(format!("The key of {} is not an integer.", parent.sql_name).into());
+            };
+
+            let children: Vec<C> = self.query_children(parent, child, field, &parent_key)?;
+            let Some(list) = value
+                .field_mut(field)
+                .and_then(|x| x.try_downcast_mut::<Vec<C>>())
+            else {
+                return Err(format!(
+                    "Field {field} of {} is not a list of {}.",
+                    parent.sql_name,
+                    C::short_type_path()
+                )
+                .into());
+            };
+            *list = children;
+        }
+
+        Ok(values)
+    }
+
+    /// Read all elements stored for the parent with the given key, in their original order.
+    pub fn query_children<T: Default + Reflect>(
+        &mut self,
+        parent: &TableDefinition,
+        child: &TableDefinition,
+        field: &str,
+        parent_key: &dyn ToSql,
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+
+        // Fields may be stored in several columns (e.g. flattened vectors), so the names are
+        // taken from the generated columns.
+        let mut options = TableSqlOptions::default();
+        options.set_type_info(T::default().get_represented_type_info());
+        let options = self.get_sql_options(&options);
+        let columns = Self::get_table_column_sqls(Self::get_child_columns(child), &options)?;
+        let column_names: Vec<&str> = columns
+            .iter()
+            .flat_map(|x| x.split(",\n"))
+            .map(get_column_name)
+            .collect();
+
+        let query = format!(
+            "SELECT {} FROM '{}' WHERE {} = ? ORDER BY {};",
+            column_names.join(", "),
+            table_name,
+            CHILD_PARENT_COLUMN,
+            CHILD_POSITION_COLUMN
+        );
+
+        self.query(child, &query, &[parent_key])
+    }
+
+    /// Replace all entities stored for the parent with the given key by `entities`.
+    /// Returns the number of inserted rows. Either all entities are replaced or none of them.
+    ///
+    /// Entity ids are only valid within the running app. After loading, the stored entities
+    /// have to be mapped to the entities spawned for them (e.g. via an `EntityHashMap`),
+    /// otherwise the relationship points to unrelated or dead entities.
+    pub fn insert_entities(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+        parent_key: &dyn ToSql,
+        entities: &[Entity],
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+        let query = format!(
+            "INSERT INTO '{table_name}' ({CHILD_PARENT_COLUMN}, {CHILD_POSITION_COLUMN}, {CHILD_ENTITY_COLUMN}) VALUES (?, ?, ?);"
+        );
+
+        self.savepoint("insert_entities", |database| {
+            database.delete_children(&table_name, parent_key)?;
+
+            let mut inserted = 0;
+            for (position, entity) in entities.iter().enumerate() {
+                let position = position as i64;
+                let bits = entity.to_bits() as i64;
+                inserted += database.execute(&query, &[parent_key, &position, &bits])?;
+            }
+
+            Ok(inserted)
+        })
+    }
+
+    /// Read all entities stored for the parent with the given key, in their original order.
+    /// See `insert_entities` regarding the stability of entity ids.
+    pub fn query_entities(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+        parent_key: &dyn ToSql,
+    ) -> Result<Vec<Entity>, SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+        let query = format!(
+            "SELECT {CHILD_ENTITY_COLUMN} FROM '{table_name}' WHERE {CHILD_PARENT_COLUMN} = ? ORDER BY {CHILD_POSITION_COLUMN};"
+        );
+
+        let bits: Vec<i64> = self.query_column(&query, &[parent_key])?;
+        bits.into_iter()
+            .map(|x| {
+                Entity::try_from_bits(x as u64)
+                    .map_err(|e| format!("Invalid entity {x} in table {table_name}: {e}").into())
+            })
+            .collect()
+    }
+
+    /// Create the child table storing the entity map `field` (e.g. `EntityHashMap<i32>`) of the
+    /// parent table, if it does not exist. Every entry is stored as one row of the parent key,
+    /// the entity bits and the value.
+    pub fn create_entity_map_table(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+    ) -> Result<(), SqliteDbError> {
+        let Some(key) = parent.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(parent.sql_name.clone()));
+        };
+
+        let table_name = Self::get_child_table_name(parent, field);
+        let columns = [
+            format!(
+                "{CHILD_PARENT_COLUMN} INTEGER NOT NULL REFERENCES '{}'({})",
+                parent.sql_name, key.sql_name
+            ),
+            format!("{CHILD_ENTITY_COLUMN} INTEGER NOT NULL"),
+            format!("{CHILD_VALUE_COLUMN} NOT NULL"),
+            format!("PRIMARY KEY({CHILD_PARENT_COLUMN}, {CHILD_ENTITY_COLUMN})"),
+        ];
+        let table_sql = format!("CREATE TABLE '{table_name}'({});", columns.join(",\n"));
+        self.create_list_table(parent, field, &table_sql)
+    }
+
+    /// Replace all entries stored for the parent with the given key by the entries of `map`.
+    /// Returns the number of inserted rows. Either all entries are replaced or none of them.
+    ///
+    /// Values have to be stored in a single column, so `V` is restricted to the types sqlite
+    /// can store directly (numbers, text and blobs). Store maps of structs as a list of structs
+    /// referencing the entity instead (see `insert_children`).
+    pub fn insert_entity_map<V: ToSql>(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+        parent_key: &dyn ToSql,
+        map: &EntityHashMap<V>,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+        let query = format!(
+            "INSERT INTO '{table_name}' ({CHILD_PARENT_COLUMN}, {CHILD_ENTITY_COLUMN}, {CHILD_VALUE_COLUMN}) VALUES (?, ?, ?);"
+        );
+
+        self.savepoint("insert_entity_map", |database| {
+            database.delete_children(&table_name, parent_key)?;
+
+            let mut inserted = 0;
+            for (entity, value) in map.iter() {
+                let bits = entity.to_bits() as i64;
+                inserted += database.execute(&query, &[parent_key, &bits, value])?;
+            }
+
+            Ok(inserted)
+        })
+    }
+
+    /// Read all entries stored for the parent with the given key. Entity ids are only valid
+    /// within the running app (see `insert_entities`), so the stored entities can be remapped
+    /// to the entities spawned for them while loading. With a mapping every stored entity has
+    /// to be part of it.
+    pub fn query_entity_map<V: FromSql>(
+        &mut self,
+        parent: &TableDefinition,
+        field: &str,
+        parent_key: &dyn ToSql,
+        mapping: Option<&EntityHashMap<Entity>>,
+    ) -> Result<EntityHashMap<V>, SqliteDbError> {
+        let table_name = Self::get_child_table_name(parent, field);
+        let query = format!(
+            "SELECT {CHILD_ENTITY_COLUMN}, {CHILD_VALUE_COLUMN} FROM '{table_name}' WHERE {CHILD_PARENT_COLUMN} = ?;"
+        );
+
+        let mut map: EntityHashMap<V> = EntityHashMap::default();
+        for (bits, value) in self.query_column_pairs::<i64, V>(&query, &[parent_key])? {
+            let entity = Entity::try_from_bits(bits as u64)
+                .map_err(|e| format!("Invalid entity {bits} in table {table_name}: {e}"))?;
+            let entity = match mapping {
+                Some(mapping) => match mapping.get(&entity) {
+                    Some(mapped) => *mapped,
+                    None => {
+                        return Err(format!(
+                            "Entity {entity} of table {table_name} is not part of the mapping."
+                        )
+                        .into())
+                    }
+                },
+                None => entity,
+            };
+            map.insert(entity, value);
+        }
+
+        Ok(map)
+    }
+
+    fn delete_children(
+        &mut self,
+        table_name: &str,
+        parent_key: &dyn ToSql,
+    ) -> Result<usize, SqliteDbError> {
+        self.execute(
+            &format!("DELETE FROM '{table_name}' WHERE {CHILD_PARENT_COLUMN} = ?;"),
+            &[parent_key],
+        )
+    }
+
+    /// All non-key columns of the child definition, sorted by their order.
+    fn get_child_columns(child: &TableDefinition) -> Vec<&ColumnDefinition> {
+        sort_columns(child)
+            .into_iter()
+            .filter(|x| !x.is_key() && !is_stored_in_child_table(x) && !is_zero_sized(None, x))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{
+        Checksum, Flatten, SqliteConnectionSettings, SqliteDatabase, TableSqlOptions,
+    };
+    use bevy::ecs::entity::EntityHashMap;
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Player {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        inventory: Vec<InventorySlot>,
+    }
+
+    #[derive(Default, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Default)]
+    struct InventorySlot {
+        item: String,
+        amount: i32,
+        #[reflect(@Checksum)]
+        checksum: i64,
+    }
+
+    fn setup() -> App {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Player>();
+        app.register_type::<InventorySlot>();
+
+        app
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_child_table.sqlite");
+        registry.register_type::<Player>(&app_registry);
+        registry.register_type::<InventorySlot>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let player_table = erm_registry.get_table_definition("Player").unwrap();
+        let slot_table = erm_registry.get_table_definition("InventorySlot").unwrap();
+        database.create_table(player_table).unwrap();
+        database
+            .create_child_table(player_table, slot_table, "inventory")
+            .unwrap();
+        assert!(database.table_exists("Player_inventory").unwrap());
+
+        // The list is not stored in the parent table.
+        let columns = database.get_columns("Player").unwrap();
+        assert!(!columns.iter().any(|x| x.name == "inventory"));
+
+        let player = Player {
+            name: "Anne Straße".to_string(),
+            inventory: vec![
+                InventorySlot {
+                    item: "Sword".to_string(),
+                    amount: 1,
+                    ..Default::default()
+                },
+                InventorySlot {
+                    item: "Arrow".to_string(),
+                    amount: 20,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let id = database
+            .insert_with_children::<Player, InventorySlot>(
+                player_table,
+                slot_table,
+                "inventory",
+                &player,
+                &registry,
+            )
+            .unwrap();
+
+        let test: Vec<Player> = database
+            .query_with_children::<Player, InventorySlot>(
+                player_table,
+                slot_table,
+                "inventory",
+                "SELECT * FROM Player;",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].id as i64, id);
+        assert_eq!(test[0].name, player.name);
+        let inventory = &test[0].inventory;
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(inventory[0].item, "Sword");
+        assert_eq!(inventory[1].amount, 20);
+
+        // Elements carry their own checksum.
+        for slot in inventory {
+            assert!(database.verify_row(slot_table, slot, &registry).unwrap());
+        }
+
+        // Inserting again replaces the stored list.
+        let inserted = database
+            .insert_children(
+                player_table,
+                slot_table,
+                "inventory",
+                &id,
+                &player.inventory[1..],
+                &registry,
+            )
+            .unwrap();
+        assert_eq!(inserted, 1);
+        let inventory: Vec<InventorySlot> = database
+            .query_children(player_table, slot_table, "inventory", &id)
+            .unwrap();
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].item, "Arrow");
+
+        // A failing element leaves the stored list untouched.
+        database
+            .execute(
+                "CREATE TRIGGER no_bows BEFORE INSERT ON Player_inventory \
+                 WHEN NEW.item = 'Bow' BEGIN SELECT RAISE(ABORT, 'No bows'); END;",
+                &[],
+            )
+            .unwrap();
+        let bows = vec![
+            InventorySlot {
+                item: "Shield".to_string(),
+                amount: 1,
+                ..Default::default()
+            },
+            InventorySlot {
+                item: "Bow".to_string(),
+                amount: 1,
+                ..Default::default()
+            },
+        ];
+        assert!(database
+            .insert_children(player_table, slot_table, "inventory", &id, &bows, &registry)
+            .is_err());
+        let inventory: Vec<InventorySlot> = database
+            .query_children(player_table, slot_table, "inventory", &id)
+            .unwrap();
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].item, "Arrow");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_child_table_round_trip() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Node {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        children: Vec<Entity>,
+    }
+
+    fn update_database_path_entities(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_child_table_entities.sqlite");
+        registry.register_type::<Node>(&app_registry);
+    }
+
+    fn run_test_entities(
+        mut commands: Commands,
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let node_table = erm_registry.get_table_definition("Node").unwrap();
+        database.create_table(node_table).unwrap();
+        database
+            .create_entity_table(node_table, "children")
+            .unwrap();
+
+        let node = Node {
+            name: "Root".to_string(),
+            children: vec![commands.spawn_empty().id(), commands.spawn_empty().id()],
+            ..Default::default()
+        };
+        database.insert(node_table, &node, &registry).unwrap();
+        let id = database
+            .query_scalar::<i64>("SELECT last_insert_rowid();", &[])
+            .unwrap()
+            .unwrap();
+
+        let inserted = database
+            .insert_entities(node_table, "children", &id, &node.children)
+            .unwrap();
+        assert_eq!(inserted, 2);
+
+        let children = database
+            .query_entities(node_table, "children", &id)
+            .unwrap();
+        assert_eq!(children, node.children);
+
+        // A failing entity leaves the stored list untouched.
+        database
+            .execute(
+                "CREATE TRIGGER one_child BEFORE INSERT ON Node_children \
+                 WHEN NEW.position > 0 BEGIN SELECT RAISE(ABORT, 'One child only'); END;",
+                &[],
+            )
+            .unwrap();
+        assert!(database
+            .insert_entities(node_table, "children", &id, &node.children)
+            .is_err());
+        let children = database
+            .query_entities(node_table, "children", &id)
+            .unwrap();
+        assert_eq!(children, node.children);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_entity_list_round_trip() {
+        let mut app = setup();
+        app.register_type::<Node>();
+        app.add_systems(PreStartup, update_database_path_entities);
+        app.add_systems(Startup, run_test_entities);
+
+        app.update();
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Guild {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        standing: EntityHashMap<i32>,
+    }
+
+    fn update_database_path_entity_map(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_child_table_entity_map.sqlite");
+        registry.register_type::<Guild>(&app_registry);
+    }
+
+    fn run_test_entity_map(
+        mut commands: Commands,
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let guild_table = erm_registry.get_table_definition("Guild").unwrap();
+        database.create_table(guild_table).unwrap();
+        database
+            .create_entity_map_table(guild_table, "standing")
+            .unwrap();
+        assert!(database.table_exists("Guild_standing").unwrap());
+
+        let friend = commands.spawn_empty().id();
+        let enemy = commands.spawn_empty().id();
+        let mut guild = Guild {
+            name: "Schwarze Hand".to_string(),
+            ..Default::default()
+        };
+        guild.standing.insert(friend, 100);
+        guild.standing.insert(enemy, -50);
+        database.insert(guild_table, &guild, &registry).unwrap();
+        let id = database
+            .query_scalar::<i64>("SELECT last_insert_rowid();", &[])
+            .unwrap()
+            .unwrap();
+
+        let inserted = database
+            .insert_entity_map(guild_table, "standing", &id, &guild.standing)
+            .unwrap();
+        assert_eq!(inserted, 2);
+
+        let standing: EntityHashMap<i32> = database
+            .query_entity_map(guild_table, "standing", &id, None)
+            .unwrap();
+        assert_eq!(standing, guild.standing);
+
+        // A failing entry leaves the stored map untouched.
+        database
+            .execute(
+                "CREATE TRIGGER no_enemies BEFORE INSERT ON Guild_standing \
+                 WHEN NEW.value < 0 BEGIN SELECT RAISE(ABORT, 'No enemies'); END;",
+                &[],
+            )
+            .unwrap();
+        assert!(database
+            .insert_entity_map(guild_table, "standing", &id, &guild.standing)
+            .is_err());
+        database.execute("DROP TRIGGER no_enemies;", &[]).unwrap();
+        let standing: EntityHashMap<i32> = database
+            .query_entity_map(guild_table, "standing", &id, None)
+            .unwrap();
+        assert_eq!(standing, guild.standing);
+
+        // The stored entities are replaced by the entities spawned for them.
+        let loaded_friend = commands.spawn_empty().id();
+        let loaded_enemy = commands.spawn_empty().id();
+        let mut mapping: EntityHashMap<Entity> = EntityHashMap::default();
+        mapping.insert(friend, loaded_friend);
+        mapping.insert(enemy, loaded_enemy);
+        let standing: EntityHashMap<i32> = database
+            .query_entity_map(guild_table, "standing", &id, Some(&mapping))
+            .unwrap();
+        assert_eq!(standing.get(&loaded_friend), Some(&100));
+        assert_eq!(standing.get(&loaded_enemy), Some(&-50));
+
+        mapping.remove(&enemy);
+        assert!(database
+            .query_entity_map::<i32>(guild_table, "standing", &id, Some(&mapping))
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_entity_map_round_trip() {
+        let mut app = setup();
+        app.register_type::<Guild>();
+        app.add_systems(PreStartup, update_database_path_entity_map);
+        app.add_systems(Startup, run_test_entity_map);
+
+        app.update();
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Patrol {
+        #[reflect(@Key)]
+        id: i32,
+        route: Vec<RoutePoint>,
+    }
+
+    #[derive(Default, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Default)]
+    struct RoutePoint {
+        #[reflect(@Flatten)]
+        pos: Vec3,
+        wait: f32,
+    }
+
+    fn update_database_path_flatten(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_child_table_flatten.sqlite");
+        registry.register_type::<Patrol>(&app_registry);
+        registry.register_type::<RoutePoint>(&app_registry);
+    }
+
+    fn run_test_flatten(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        // The attributes of the child type apply to the columns of the child table.
+        let patrol_table = erm_registry.get_table_definition("Patrol").unwrap();
+        let point_table = erm_registry.get_table_definition("RoutePoint").unwrap();
+        let options = TableSqlOptions::for_type::<RoutePoint>();
+        let sql = SqliteDatabase::get_child_table_sql(patrol_table, point_table, "route", &options)
+            .unwrap();
+        assert!(sql.contains("pos_x REAL NOT NULL,\npos_y REAL NOT NULL,\npos_z REAL NOT NULL"));
+
+        database.create_table(patrol_table).unwrap();
+        database
+            .create_child_table_with_options(patrol_table, point_table, "route", &options)
+            .unwrap();
+
+        let route = vec![
+            RoutePoint {
+                pos: Vec3::new(1.0, 2.0, 3.0),
+                wait: 0.5,
+            },
+            RoutePoint {
+                pos: Vec3::new(-4.0, 0.0, 8.0),
+                wait: 2.0,
+            },
+        ];
+        let id = 7;
+        database
+            .execute("INSERT INTO Patrol (id) VALUES (?1);", &[&id])
+            .unwrap();
+        database
+            .insert_children(patrol_table, point_table, "route", &id, &route, &registry)
+            .unwrap();
+
+        let test: Vec<RoutePoint> = database
+            .query_children(patrol_table, point_table, "route", &id)
+            .unwrap();
+        assert_eq!(test, route);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_child_table_flatten() {
+        let mut app = setup();
+        app.register_type::<Patrol>();
+        app.register_type::<RoutePoint>();
+        app.add_systems(PreStartup, update_database_path_flatten);
+        app.add_systems(Startup, run_test_flatten);
+
+        app.update();
+    }
+}
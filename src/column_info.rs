@@ -0,0 +1,87 @@
+use crate::prelude::{SqliteDatabase, SqliteDbError};
+
+/// A column of an existing table, as reported by `PRAGMA table_info`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// The declared type, e.g. `INTEGER`. Empty, if the column was declared without a type.
+    pub declared_type: String,
+    pub not_null: bool,
+    pub primary_key: bool,
+    /// The default value as SQL text, e.g. `'none'` or `0`.
+    pub default_value: Option<String>,
+}
+
+impl SqliteDatabase {
+    /// Read the columns of the table from the database, ordered like the columns of the table.
+    /// Unlike `describe`, this reflects the actual schema of the database instead of the table
+    /// definition. Returns no columns, if the table does not exist.
+    pub fn get_columns(&mut self, table: &str) -> Result<Vec<ColumnInfo>, SqliteDbError> {
+        self.query_mapped(
+            "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1);",
+            &[&table],
+            |row| {
+                Ok(ColumnInfo {
+                    name: row.get(0)?,
+                    declared_type: row.get(1)?,
+                    not_null: row.get(2)?,
+                    default_value: row.get(3)?,
+                    primary_key: row.get::<usize, i64>(4)? > 0,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase};
+    use bevy::prelude::*;
+
+    fn update_database_path(mut settings: ResMut<SqliteConnectionSettings>) {
+        settings.set_data_source("test_column_info.sqlite");
+    }
+
+    fn run_test(mut database: ResMut<SqliteDatabase>, settings: Res<SqliteConnectionSettings>) {
+        database.open(&settings).unwrap();
+
+        database
+            .execute(
+                "CREATE TABLE Item (id INTEGER PRIMARY KEY, name TEXT NOT NULL, \
+                 rarity TEXT DEFAULT 'common', note);",
+                &[],
+            )
+            .unwrap();
+
+        let columns = database.get_columns("Item").unwrap();
+        let names: Vec<&str> = columns.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "rarity", "note"]);
+
+        assert!(columns[0].primary_key);
+        assert_eq!(columns[0].declared_type, "INTEGER");
+        assert!(columns[1].not_null);
+        assert!(!columns[1].primary_key);
+        assert_eq!(columns[2].default_value.as_deref(), Some("'common'"));
+        assert!(!columns[2].not_null);
+        assert_eq!(columns[3].declared_type, "");
+        assert_eq!(columns[3].default_value, None);
+
+        assert!(database.get_columns("Missing").unwrap().is_empty());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_get_columns() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,110 @@
+//! The content version marks changes of the game data (not the schema) which invalidate old
+//! saves, e.g. a balance overhaul. Games keep the current version in a constant, store it with
+//! every save and compare it on startup.
+
+use crate::prelude::{SqliteDatabase, SqliteDbError};
+use bevy::prelude::*;
+use rusqlite::ToSql;
+
+/// The table storing metadata of the database, e.g. the content version.
+pub const METADATA_TABLE: &str = "_erm_metadata";
+
+const CONTENT_VERSION_KEY: &str = "content_version";
+
+impl SqliteDatabase {
+    /// Store the content version of the data in the database.
+    pub fn set_content_version(&mut self, version: u32) -> Result<(), SqliteDbError> {
+        self.create_metadata_table()?;
+
+        let query = format!(
+            "INSERT INTO {METADATA_TABLE} (key, value) VALUES (?1, ?2) \
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value;"
+        );
+        self.execute(&query, &[&CONTENT_VERSION_KEY as &dyn ToSql, &version])?;
+
+        Ok(())
+    }
+
+    /// Get the stored content version, if there is one.
+    pub fn get_content_version(&mut self) -> Result<Option<u32>, SqliteDbError> {
+        if !self.table_exists(METADATA_TABLE)? {
+            return Ok(None);
+        }
+
+        let query = format!("SELECT value FROM {METADATA_TABLE} WHERE key = ?1;");
+        let versions: Vec<u32> = self.query_column(&query, &[&CONTENT_VERSION_KEY])?;
+
+        Ok(versions.first().copied())
+    }
+
+    /// Compare the stored content version with the current version of the game. Returns true,
+    /// if they are equal. Otherwise a warning is logged and false is returned, also for
+    /// databases without a stored version. Call this on startup after opening a save.
+    pub fn check_content_version(&mut self, current: u32) -> Result<bool, SqliteDbError> {
+        match self.get_content_version()? {
+            Some(stored) if stored == current => Ok(true),
+            Some(stored) => {
+                warn!("The save has content version {stored}, the game expects {current}.");
+                Ok(false)
+            }
+            None => {
+                warn!("The save has no content version, the game expects {current}.");
+                Ok(false)
+            }
+        }
+    }
+
+    fn create_metadata_table(&mut self) -> Result<(), SqliteDbError> {
+        self.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {METADATA_TABLE} \
+                 (key TEXT PRIMARY KEY, value NOT NULL);"
+            ),
+            &[],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase};
+    use bevy::prelude::*;
+
+    fn update_database_path(mut settings: ResMut<SqliteConnectionSettings>) {
+        settings.set_data_source("test_content_version.sqlite");
+    }
+
+    fn run_test(mut database: ResMut<SqliteDatabase>, settings: Res<SqliteConnectionSettings>) {
+        database.open(&settings).unwrap();
+
+        assert_eq!(database.get_content_version().unwrap(), None);
+        assert!(!database.check_content_version(1).unwrap());
+
+        database.set_content_version(1).unwrap();
+        assert_eq!(database.get_content_version().unwrap(), Some(1));
+        assert!(database.check_content_version(1).unwrap());
+
+        // A newer game detects the old save.
+        assert!(!database.check_content_version(2).unwrap());
+        database.set_content_version(2).unwrap();
+        assert!(database.check_content_version(2).unwrap());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_content_version() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use bevy::reflect::ReflectMut;
+use bevy::utils::HashMap;
+use bevy_erm::prelude::TableDefinition;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::Row;
+use std::any::TypeId;
+
+type ToSqlFn = Box<dyn Fn(&dyn Reflect) -> Option<Value> + Send + Sync>;
+type FromSqlFn = Box<dyn Fn(ValueRef) -> Result<Box<dyn Reflect>, String> + Send + Sync>;
+
+struct Converter {
+    to_sql: ToSqlFn,
+    from_sql: FromSqlFn,
+}
+
+/// Custom conversions between field types and sqlite values, registered with
+/// `SqliteDatabase::register_converter`. A converter is consulted before the built-in mappings,
+/// so it can store types this crate does not know or store known types differently.
+///
+/// The converters are part of the `SqliteDatabase` resource, which is shared between systems
+/// running on different threads. This is why the functions have to be `Send + Sync`.
+/// Optional fields (e.g. `Option<T>`) are not converted, register a converter for the option
+/// itself instead.
+#[derive(Default)]
+pub struct Converters {
+    converters: HashMap<TypeId, Converter>,
+}
+
+impl Converters {
+    pub fn new() -> Self {
+        Converters::default()
+    }
+
+    /// Add a converter for type `T`. A converter registered before for the same type is replaced.
+    pub fn register<T: Reflect>(
+        &mut self,
+        to_sql: impl Fn(&T) -> Value + Send + Sync + 'static,
+        from_sql: impl Fn(ValueRef) -> Result<T, String> + Send + Sync + 'static,
+    ) {
+        let converter = Converter {
+            to_sql: Box::new(move |value| value.downcast_ref::<T>().map(&to_sql)),
+            from_sql: Box::new(move |value| {
+                from_sql(value).map(|x| Box::new(x) as Box<dyn Reflect>)
+            }),
+        };
+        self.converters.insert(TypeId::of::<T>(), converter);
+    }
+
+    /// True, if a converter is registered for the type with the given id.
+    pub fn contains(&self, type_id: TypeId) -> bool {
+        self.converters.contains_key(&type_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.converters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.converters.is_empty()
+    }
+
+    /// The ids of all types with a converter.
+    pub(crate) fn type_ids(&self) -> Vec<TypeId> {
+        self.converters.keys().copied().collect()
+    }
+
+    /// Convert the value using the converter of its type, if there is one.
+    pub(crate) fn to_sql(&self, value: &dyn Reflect) -> Option<Value> {
+        let converter = self.converters.get(&value.as_any().type_id())?;
+        (converter.to_sql)(value)
+    }
+}
+
+/// Read the columns of all fields with a converter and assign the converted values.
+pub(crate) fn read_converted_columns(
+    table_def: &TableDefinition,
+    names: &[String],
+    row: &Row,
+    converters: &Converters,
+    value: &mut dyn PartialReflect,
+) -> rusqlite::Result<()> {
+    if converters.is_empty() {
+        return Ok(());
+    }
+
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return Ok(());
+    };
+
+    for (x, name) in names.iter().enumerate() {
+        let Some(col) = table_def.get(name) else {
+            continue;
+        };
+
+        let Some(converter) = converters.converters.get(&col.ty.id()) else {
+            continue;
+        };
+
+        let Some(field) = target
+            .field_mut(&col.rust_name)
+            .and_then(|f| f.try_as_reflect_mut())
+        else {
+            continue;
+        };
+
+        let stored = row.get_ref(x)?;
+        let converted = (converter.from_sql)(stored).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(x, stored.data_type(), e.into())
+        })?;
+        if field.set(converted).is_err() {
+            return Err(rusqlite::Error::FromSqlConversionFailure(
+                x,
+                stored.data_type(),
+                format!("The converter of {name} returned a value of the wrong type.").into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, TableSqlOptions};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+    use rusqlite::types::{Value, ValueRef};
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+    struct Money {
+        cents: i64,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Account {
+        #[reflect(@Key)]
+        id: i32,
+        owner: String,
+        balance: Money,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_converters.sqlite");
+        registry.register_type::<Account>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+        let table = erm_registry.get_table_definition("Account").unwrap();
+
+        // Money is unknown without a converter.
+        assert!(database.create_table(table).is_err());
+
+        database.register_converter::<Money>(
+            |money| Value::Integer(money.cents),
+            |value| match value {
+                ValueRef::Integer(cents) => Ok(Money { cents }),
+                _ => Err("Money is stored as cents.".to_string()),
+            },
+        );
+        assert!(database
+            .get_converters()
+            .contains(std::any::TypeId::of::<Money>()));
+        database
+            .create_table_with_options(table, &TableSqlOptions::for_type::<Account>())
+            .unwrap();
+
+        let account = Account {
+            owner: "Dagobert".to_string(),
+            balance: Money { cents: 1250 },
+            ..Default::default()
+        };
+        database.insert(table, &account, &registry).unwrap();
+
+        let cents = database
+            .query_scalar::<i64>("SELECT balance FROM Account;", &[])
+            .unwrap();
+        assert_eq!(cents, Some(1250));
+
+        let read: Vec<Account> = database
+            .query(table, "SELECT * FROM Account;", &[])
+            .unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].owner, "Dagobert");
+        assert_eq!(read[0].balance, Money { cents: 1250 });
+
+        // Errors of the converter fail the query.
+        database
+            .execute("UPDATE Account SET balance = 'a lot';", &[])
+            .unwrap();
+        let result = database.query::<Account>(table, "SELECT * FROM Account;", &[]);
+        let Err(e) = result else {
+            panic!("The converter should have failed.");
+        };
+        assert!(e.to_string().contains("Money is stored as cents."));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_register_converter() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Account>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,165 @@
+use crate::prelude::{SqliteDatabase, SqliteDbError};
+use bevy_erm::prelude::{ErmTypesRegistry, SqlType, TableDefinition};
+
+impl SqliteDatabase {
+    /// Create the tables of the given types only, e.g. the content tables before the gameplay
+    /// tables. Tables referenced by a one to one relation are created before the tables
+    /// referencing them, as long as both are listed. Existing tables are skipped.
+    pub fn create_tables(
+        &mut self,
+        registry: &ErmTypesRegistry,
+        type_names: &[&str],
+    ) -> Result<(), SqliteDbError> {
+        let mut tables: Vec<&TableDefinition> = Vec::new();
+        for name in type_names {
+            let Some(def) = registry.get_table_definition(name) else {
+                return Err(SqliteDbError::UnknownType(name.to_string()));
+            };
+            tables.push(def);
+        }
+
+        for def in sort_by_dependencies(&tables)? {
+            self.create_table(def)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Order the tables, so every table comes after the tables it references. References to tables
+/// which are not part of the list are ignored. Otherwise the order of the list is kept.
+pub(crate) fn sort_by_dependencies<'a>(
+    tables: &[&'a TableDefinition],
+) -> Result<Vec<&'a TableDefinition>, SqliteDbError> {
+    let mut pending: Vec<&TableDefinition> = tables.to_vec();
+    let mut sorted: Vec<&TableDefinition> = Vec::new();
+    while !pending.is_empty() {
+        let next = pending.iter().position(|def| {
+            get_references(def)
+                .iter()
+                .all(|name| !pending.iter().any(|x| x.sql_name == *name))
+        });
+
+        let Some(x) = next else {
+            let names: Vec<String> = pending.iter().map(|x| x.sql_name.clone()).collect();
+            return Err(SqliteDbError::CyclicReference(names));
+        };
+        sorted.push(pending.remove(x));
+    }
+
+    Ok(sorted)
+}
+
+/// The names of the tables referenced by one to one relations, except the table itself.
+fn get_references(def: &TableDefinition) -> Vec<&str> {
+    def.fields
+        .values()
+        .filter(|x| matches!(x.sql_type, SqlType::One2One(..)))
+        .map(|x| x.ty.short_path())
+        .filter(|x| *x != def.sql_name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sort_by_dependencies;
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, SqliteDbError};
+    use bevy::prelude::*;
+    use bevy::reflect::Type;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key, SqlType, TableDefinition};
+    use std::any::TypeId;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Region {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Town {
+        #[reflect(@Key)]
+        id: i32,
+        region: Region,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Citizen {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_create_tables.sqlite");
+        registry.register_type::<Region>(&app_registry);
+        registry.register_type::<Town>(&app_registry);
+        registry.register_type::<Citizen>(&app_registry);
+    }
+
+    fn run_test(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        database
+            .create_tables(&erm_registry, &["Town", "Region"])
+            .unwrap();
+        assert!(database.table_exists("Region").unwrap());
+        assert!(database.table_exists("Town").unwrap());
+        assert!(!database.table_exists("Citizen").unwrap());
+
+        database.create_tables(&erm_registry, &["Citizen"]).unwrap();
+        assert!(database.table_exists("Citizen").unwrap());
+
+        assert!(matches!(
+            database.create_tables(&erm_registry, &["Castle"]),
+            Err(SqliteDbError::UnknownType(name)) if name == "Castle"
+        ));
+
+        // Towns reference their region, so the region has to be created first.
+        let region = erm_registry.get_table_definition("Region").unwrap();
+        let town = erm_registry.get_table_definition("Town").unwrap();
+        let sorted = sort_by_dependencies(&[town, region]).unwrap();
+        let names: Vec<&str> = sorted.iter().map(|x| x.sql_name.as_str()).collect();
+        assert_eq!(names, vec!["Region", "Town"]);
+
+        // Structs cannot contain each other, so the cycle is built by hand.
+        let mut region: TableDefinition = region.clone();
+        let column = region.fields.get_mut("name").unwrap();
+        column.sql_type = SqlType::One2One(TypeId::of::<Town>(), true);
+        column.ty = Type::of::<Town>();
+        assert!(matches!(
+            sort_by_dependencies(&[town, &region]),
+            Err(SqliteDbError::CyclicReference(names)) if names == ["Town", "Region"]
+        ));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_create_tables() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Region>();
+        app.register_type::<Town>();
+        app.register_type::<Citizen>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
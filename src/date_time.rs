@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use bevy_erm::prelude::ColumnDefinition;
+use rusqlite::types::Value;
+use rusqlite::Row;
+use std::any::Any;
+
+#[cfg(feature = "chrono")]
+use crate::plugin::assign_parsed;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+/// The number of seconds of a day. Times of day are stored as seconds since midnight.
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Timestamps (`chrono::DateTime<Utc>` and `chrono::DateTime<FixedOffset>`) are stored as
+/// RFC 3339 text including their offset, e.g. `2024-05-01T18:30:00+02:00`. Reading parses the
+/// text back into the type of the field, so the offset of a `DateTime<FixedOffset>` survives
+/// the round trip instead of being normalized to UTC. Dates (`chrono::NaiveDate`) are stored as
+/// ISO 8601 text, e.g. `2024-05-01`. Requires the `chrono` feature.
+#[cfg(feature = "chrono")]
+pub(crate) fn is_date_time_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<NaiveDate>()
+        || col.ty.is::<Option<NaiveDate>>()
+        || col.ty.is::<DateTime<Utc>>()
+        || col.ty.is::<Option<DateTime<Utc>>>()
+        || col.ty.is::<DateTime<FixedOffset>>()
+        || col.ty.is::<Option<DateTime<FixedOffset>>>()
+}
+
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn is_date_time_column(_col: &ColumnDefinition) -> bool {
+    false
+}
+
+/// The RFC 3339 text of a timestamp, NULL for an empty optional timestamp.
+#[cfg(feature = "chrono")]
+pub(crate) fn get_date_time_value(value: &dyn Reflect) -> Option<Value> {
+    let value = value.as_any();
+    if let Some(v) = value.downcast_ref::<DateTime<Utc>>() {
+        return Some(Value::Text(v.to_rfc3339()));
+    }
+
+    if let Some(v) = value.downcast_ref::<DateTime<FixedOffset>>() {
+        return Some(Value::Text(v.to_rfc3339()));
+    }
+
+    if let Some(v) = value.downcast_ref::<Option<DateTime<Utc>>>() {
+        return Some(v.map_or(Value::Null, |v| Value::Text(v.to_rfc3339())));
+    }
+
+    if let Some(v) = value.downcast_ref::<Option<DateTime<FixedOffset>>>() {
+        return Some(v.map_or(Value::Null, |v| Value::Text(v.to_rfc3339())));
+    }
+
+    if let Some(v) = value.downcast_ref::<NaiveDate>() {
+        return Some(Value::Text(v.to_string()));
+    }
+
+    if let Some(v) = value.downcast_ref::<Option<NaiveDate>>() {
+        return Some(v.map_or(Value::Null, |v| Value::Text(v.to_string())));
+    }
+
+    None
+}
+
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn get_date_time_value(_value: &dyn Reflect) -> Option<Value> {
+    None
+}
+
+/// Parse the timestamp stored in the given column and assign it to the field.
+#[cfg(feature = "chrono")]
+pub(crate) fn assign_date_time(field: &mut dyn Any, row: &Row, x: usize) -> rusqlite::Result<()> {
+    if field.is::<NaiveDate>() || field.is::<Option<NaiveDate>>() {
+        assign_parsed::<NaiveDate>(field, row, x)
+    } else if field.is::<DateTime<Utc>>() || field.is::<Option<DateTime<Utc>>>() {
+        assign_parsed::<DateTime<Utc>>(field, row, x)
+    } else {
+        assign_parsed::<DateTime<FixedOffset>>(field, row, x)
+    }
+}
+
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn assign_date_time(
+    _field: &mut dyn Any,
+    _row: &Row,
+    _x: usize,
+) -> rusqlite::Result<()> {
+    Ok(())
+}
+
+/// Read the ISO 8601 date (`YYYY-MM-DD`) stored in a date column. NULL is read as `None`,
+/// empty or malformed text is an error.
+pub(crate) fn read_date_text(row: &Row, x: usize) -> rusqlite::Result<Option<String>> {
+    let Some(text) = row.get::<usize, Option<String>>(x)? else {
+        return Ok(None);
+    };
+
+    if !is_iso_date(&text) {
+        return Err(rusqlite::Error::FromSqlConversionFailure(
+            x,
+            rusqlite::types::Type::Text,
+            format!("'{text}' is not a date (YYYY-MM-DD)").into(),
+        ));
+    }
+
+    Ok(Some(text))
+}
+
+/// Read a time of day stored as seconds since midnight. NULL is read as `None`, values outside
+/// of a day are an error.
+pub(crate) fn read_time_seconds(row: &Row, x: usize) -> rusqlite::Result<Option<f64>> {
+    let Some(seconds) = row.get::<usize, Option<f64>>(x)? else {
+        return Ok(None);
+    };
+
+    if !(0.0..SECONDS_PER_DAY).contains(&seconds) {
+        return Err(rusqlite::Error::FromSqlConversionFailure(
+            x,
+            rusqlite::types::Type::Real,
+            format!("{seconds} is not a time of day in seconds").into(),
+        ));
+    }
+
+    Ok(Some(seconds))
+}
+
+/// Read the ISO 8601 date and time (e.g. `YYYY-MM-DDTHH:MM:SS`) stored in a date time column.
+/// The date may be separated by a space instead of `T`, as done by the date functions of
+/// sqlite. NULL is read as `None`, empty or malformed text is an error.
+pub(crate) fn read_date_time_text(row: &Row, x: usize) -> rusqlite::Result<Option<String>> {
+    let Some(text) = row.get::<usize, Option<String>>(x)? else {
+        return Ok(None);
+    };
+
+    let is_date_time = text
+        .split_once(['T', ' '])
+        .is_some_and(|(date, time)| is_iso_date(date) && is_iso_time(time));
+    if !is_date_time {
+        return Err(rusqlite::Error::FromSqlConversionFailure(
+            x,
+            rusqlite::types::Type::Text,
+            format!("'{text}' is not a date time (YYYY-MM-DDTHH:MM:SS)").into(),
+        ));
+    }
+
+    Ok(Some(text))
+}
+
+fn is_iso_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return false;
+    };
+
+    let is_number =
+        |part: &str, len: usize| part.len() == len && part.chars().all(|c| c.is_ascii_digit());
+    if !is_number(year, 4) || !is_number(month, 2) || !is_number(day, 2) {
+        return false;
+    }
+
+    matches!(month.parse::<u8>(), Ok(1..=12)) && matches!(day.parse::<u8>(), Ok(1..=31))
+}
+
+/// True, if the text starts with a time of day (`HH:MM` or `HH:MM:SS`). Fractions of seconds and
+/// offsets may follow.
+fn is_iso_time(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let is_pair = |i: usize| {
+        bytes
+            .get(i..i + 2)
+            .is_some_and(|p| p.iter().all(u8::is_ascii_digit))
+    };
+    if !is_pair(0) || bytes.get(2) != Some(&b':') || !is_pair(3) {
+        return false;
+    }
+
+    let hours = text[0..2].parse::<u8>().unwrap_or(u8::MAX);
+    let minutes = text[3..5].parse::<u8>().unwrap_or(u8::MAX);
+    if hours > 23 || minutes > 59 {
+        return false;
+    }
+
+    match bytes.get(5) {
+        Some(b':') => is_pair(6) && text[6..8].parse::<u8>().is_ok_and(|s| s < 61),
+        _ => true,
+    }
+}
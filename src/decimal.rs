@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use bevy_erm::prelude::ColumnDefinition;
+use rusqlite::types::Value;
+use rusqlite::Row;
+use std::any::Any;
+
+#[cfg(feature = "rust_decimal")]
+use crate::plugin::assign_parsed;
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+
+/// Decimals (`rust_decimal::Decimal`) are stored as text in their canonical form, e.g. `12.50`,
+/// because a REAL column would silently round them. Reading parses the text back, so values
+/// survive the round trip exactly, including their scale. Note that sqlite compares text
+/// character by character, so `ORDER BY` or `<` on such a column do not follow the numeric
+/// order. Sort in Rust or store a scaled integer (e.g. cents as `i64`) for columns which are
+/// sorted or compared in queries. Requires the `rust_decimal` feature.
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn is_decimal_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<Decimal>() || col.ty.is::<Option<Decimal>>()
+}
+
+#[cfg(not(feature = "rust_decimal"))]
+pub(crate) fn is_decimal_column(_col: &ColumnDefinition) -> bool {
+    false
+}
+
+/// The canonical text of a decimal, NULL for an empty optional decimal.
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn get_decimal_value(value: &dyn Reflect) -> Option<Value> {
+    let value = value.as_any();
+    if let Some(v) = value.downcast_ref::<Decimal>() {
+        return Some(Value::Text(v.to_string()));
+    }
+
+    if let Some(v) = value.downcast_ref::<Option<Decimal>>() {
+        return Some(v.map_or(Value::Null, |v| Value::Text(v.to_string())));
+    }
+
+    None
+}
+
+#[cfg(not(feature = "rust_decimal"))]
+pub(crate) fn get_decimal_value(_value: &dyn Reflect) -> Option<Value> {
+    None
+}
+
+/// Parse the decimal stored in the given column and assign it to the field.
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn assign_decimal(field: &mut dyn Any, row: &Row, x: usize) -> rusqlite::Result<()> {
+    assign_parsed::<Decimal>(field, row, x)
+}
+
+#[cfg(not(feature = "rust_decimal"))]
+pub(crate) fn assign_decimal(_field: &mut dyn Any, _row: &Row, _x: usize) -> rusqlite::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, feature = "rust_decimal"))]
+mod tests {
+    use super::assign_decimal;
+    use rusqlite::Connection;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_assign_decimal() {
+        let connection = Connection::open_in_memory().unwrap();
+        let (value, empty) = connection
+            .query_row("SELECT '0.10', NULL;", [], |row| {
+                let mut value = Decimal::ZERO;
+                assign_decimal(&mut value, row, 0)?;
+                let mut empty = Some(Decimal::ONE);
+                assign_decimal(&mut empty, row, 1)?;
+                Ok((value, empty))
+            })
+            .unwrap();
+
+        // The scale is kept, so the text is the same after the round trip.
+        assert_eq!(value, Decimal::from_str("0.1").unwrap());
+        assert_eq!(value.to_string(), "0.10");
+        assert_eq!(empty, None);
+
+        let mut value = Decimal::ZERO;
+        let result = connection.query_row("SELECT 'ten';", [], |row| {
+            assign_decimal(&mut value, row, 0)
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::plugin::sort_columns;
+use crate::prelude::SqliteDatabase;
+use bevy_erm::prelude::{SqlType, TableDefinition};
+
+/// How a column of a table maps to the field of its struct.
+#[derive(Clone, Debug)]
+pub struct ColumnMapping {
+    pub sql_name: String,
+    pub sql_type: SqlType,
+    pub rust_name: String,
+    /// The full type path of the field, e.g. `alloc::string::String`.
+    pub rust_type: &'static str,
+    pub is_key: bool,
+}
+
+impl SqliteDatabase {
+    /// Describe how the columns of the table map to the fields of its struct, ordered like the
+    /// columns of the table. Only the table definition is used, so no connection is required.
+    pub fn describe(def: &TableDefinition) -> Vec<ColumnMapping> {
+        sort_columns(def)
+            .into_iter()
+            .map(|col| ColumnMapping {
+                sql_name: col.sql_name.clone(),
+                sql_type: col.sql_type,
+                rust_name: col.rust_name.clone(),
+                rust_type: col.ty.path(),
+                is_key: col.is_key(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::SqliteDatabase;
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key, SqlType};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Player {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        level: u8,
+    }
+
+    fn run_test(app_registry: Res<AppTypeRegistry>, mut registry: ResMut<ErmTypesRegistry>) {
+        registry.register_type::<Player>(&app_registry);
+        let table = registry.get_table_definition("Player").unwrap();
+
+        let mapping = SqliteDatabase::describe(table);
+        let names: Vec<&str> = mapping.iter().map(|x| x.rust_name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "level"]);
+
+        assert!(mapping[0].is_key);
+        assert_eq!(mapping[1].sql_name, "name");
+        assert!(matches!(mapping[1].sql_type, SqlType::Text(_)));
+        assert_eq!(mapping[1].rust_type, "alloc::string::String");
+        assert!(!mapping[2].is_key);
+        assert_eq!(mapping[2].rust_type, "u8");
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Player>();
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// A unique `Name` for every known entity. Relations marked as `RelateByName` are stored as
+/// the name of the related entity instead of its bits, which change between sessions. When
+/// reading, the entity with the stored name is looked up again.
+///
+/// Relations can only be resolved for entities which are part of the lookup, so parents have
+/// to be spawned and added before their children are loaded.
+#[derive(Clone, Debug, Default)]
+pub struct EntityNames {
+    names: HashMap<Entity, String>,
+    entities: HashMap<String, Entity>,
+}
+
+impl EntityNames {
+    pub fn new() -> Self {
+        EntityNames::default()
+    }
+
+    /// Add an entity. Fails, if the name is used by another entity already.
+    pub fn insert(&mut self, entity: Entity, name: &Name) -> Result<(), String> {
+        let name = name.as_str();
+        if let Some(other) = self.entities.get(name) {
+            if *other != entity {
+                return Err(format!(
+                    "The name {name} is used by the entities {other} and {entity}."
+                ));
+            }
+        }
+
+        if let Some(previous) = self.names.insert(entity, name.to_string()) {
+            self.entities.remove(&previous);
+        }
+        self.entities.insert(name.to_string(), entity);
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(name) = self.names.remove(&entity) {
+            self.entities.remove(&name);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.names.clear();
+        self.entities.clear();
+    }
+
+    pub fn get_name(&self, entity: Entity) -> Option<&str> {
+        self.names.get(&entity).map(|x| x.as_str())
+    }
+
+    pub fn get_entity(&self, name: &str) -> Option<Entity> {
+        self.entities.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntityNames;
+    use bevy::prelude::*;
+
+    #[test]
+    fn test_insert_and_lookup() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+
+        let mut names = EntityNames::new();
+        assert!(names.is_empty());
+        names.insert(a, &Name::new("Castle")).unwrap();
+        names.insert(b, &Name::new("Tower")).unwrap();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.get_name(a), Some("Castle"));
+        assert_eq!(names.get_entity("Tower"), Some(b));
+
+        // Names have to be unique.
+        assert!(names.insert(b, &Name::new("Castle")).is_err());
+
+        // Renaming an entity releases its old name.
+        names.insert(b, &Name::new("Keep")).unwrap();
+        assert_eq!(names.get_entity("Tower"), None);
+        assert_eq!(names.get_entity("Keep"), Some(b));
+
+        names.remove(a);
+        assert_eq!(names.get_entity("Castle"), None);
+        assert_eq!(names.len(), 1);
+    }
+}
@@ -0,0 +1,168 @@
+use std::any::TypeId;
+use std::fmt;
+use std::time::Duration;
+
+/// The errors returned by the database. Their messages are sentences starting with a capital
+/// letter. Errors of sqlite are appended after a colon.
+#[derive(Debug)]
+pub enum SqliteDbError {
+    /// The database was used before opening it or after closing it.
+    NotOpen,
+    /// A thread panicked while holding the connection.
+    MutexPoisoned,
+    /// The database file could not be opened.
+    Open(rusqlite::Error),
+    /// The statement could not be compiled.
+    Prepare(rusqlite::Error),
+    /// The statement failed while running or its result could not be read.
+    Execute(rusqlite::Error),
+    /// The row with the given index of a query result could not be mapped to a value.
+    MapRow(usize, rusqlite::Error),
+    /// The operation needs a key column, but the table with the given name has none.
+    NoKeyColumn(String),
+    /// The operation needs a checksum column, but the table with the given name has none.
+    NoChecksumColumn(String),
+    /// The table has no column with the given name.
+    UnknownColumn { table: String, column: String },
+    /// The table with the given name does not exist in the database.
+    UnknownTable(String),
+    /// No type with the given name is registered.
+    UnknownType(String),
+    /// The tables with the given names reference each other, so they cannot be created in order.
+    CyclicReference(Vec<String>),
+    /// Values of the type cannot be stored.
+    UnsupportedType(TypeId),
+    /// A query expected to return at most one row returned the given number of rows.
+    TooManyRows(usize),
+    /// The operation was interrupted, because it took longer than the given time budget.
+    Timeout(Duration),
+    /// The query returned more rows than the limit set with `set_max_rows`.
+    RowLimitExceeded(usize),
+    /// Any other error, described by its message.
+    Other(String),
+}
+
+impl fmt::Display for SqliteDbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqliteDbError::NotOpen => write!(f, "The database connection is not open."),
+            SqliteDbError::MutexPoisoned => write!(f, "The connection mutex was poisoned."),
+            SqliteDbError::Open(e) => write!(f, "Could not open database connection: {e}"),
+            SqliteDbError::Prepare(e) => write!(f, "Could not compile query: {e}"),
+            SqliteDbError::Execute(e) => write!(f, "Could not execute query: {e}"),
+            SqliteDbError::MapRow(index, e) => write!(f, "Could not map row {index}: {e}"),
+            SqliteDbError::NoKeyColumn(table) => write!(f, "Table {table} has no key column."),
+            SqliteDbError::NoChecksumColumn(table) => {
+                write!(f, "Table {table} has no checksum column.")
+            }
+            SqliteDbError::UnknownColumn { table, column } => {
+                write!(f, "Table {table} has no column {column}.")
+            }
+            SqliteDbError::UnknownTable(table) => write!(f, "Table {table} does not exist."),
+            SqliteDbError::UnknownType(name) => write!(f, "Type {name} is not registered."),
+            SqliteDbError::CyclicReference(tables) => {
+                write!(f, "The tables {} reference each other.", tables.join(", "))
+            }
+            SqliteDbError::UnsupportedType(ty) => write!(f, "The type {ty:?} cannot be stored."),
+            SqliteDbError::TooManyRows(rows) => write!(
+                f,
+                "Expected at most one row, but the query returned {rows}."
+            ),
+            SqliteDbError::Timeout(timeout) => write!(
+                f,
+                "Operation exceeded the timeout of {} ms.",
+                timeout.as_millis()
+            ),
+            SqliteDbError::RowLimitExceeded(max_rows) => {
+                write!(f, "Query returned more than the limit of {max_rows} rows.")
+            }
+            SqliteDbError::Other(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl SqliteDbError {
+    /// Wrap an error of a running statement. Values that cannot be stored are reported as
+    /// `UnsupportedType`.
+    pub(crate) fn from_execute(error: rusqlite::Error) -> Self {
+        if let rusqlite::Error::ToSqlConversionFailure(inner) = &error {
+            if let Some(UnsupportedValue(ty)) = inner.downcast_ref::<UnsupportedValue>() {
+                return SqliteDbError::UnsupportedType(*ty);
+            }
+        }
+
+        SqliteDbError::Execute(error)
+    }
+}
+
+/// The conversion error of a value, whose type cannot be stored.
+#[derive(Debug)]
+pub(crate) struct UnsupportedValue(pub TypeId);
+
+impl fmt::Display for UnsupportedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The type {:?} cannot be stored.", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedValue {}
+
+impl std::error::Error for SqliteDbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SqliteDbError::Open(e)
+            | SqliteDbError::Prepare(e)
+            | SqliteDbError::Execute(e)
+            | SqliteDbError::MapRow(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for SqliteDbError {
+    fn from(message: String) -> Self {
+        SqliteDbError::Other(message)
+    }
+}
+
+impl From<&str> for SqliteDbError {
+    fn from(message: &str) -> Self {
+        SqliteDbError::Other(message.to_string())
+    }
+}
+
+impl From<SqliteDbError> for String {
+    fn from(error: SqliteDbError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteDbError;
+    use std::time::Duration;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            SqliteDbError::NotOpen.to_string(),
+            "The database connection is not open."
+        );
+        assert_eq!(
+            SqliteDbError::CyclicReference(vec!["Town".to_string(), "Region".to_string()])
+                .to_string(),
+            "The tables Town, Region reference each other."
+        );
+        assert_eq!(
+            SqliteDbError::NoKeyColumn("Player".to_string()).to_string(),
+            "Table Player has no key column."
+        );
+        assert_eq!(
+            SqliteDbError::Timeout(Duration::from_millis(50)).to_string(),
+            "Operation exceeded the timeout of 50 ms."
+        );
+
+        let message: String = SqliteDbError::from("Failed.").into();
+        assert_eq!(message, "Failed.");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::attributes::{get_field_attribute, EulerAngles, Flatten};
+use bevy::prelude::*;
+use bevy::reflect::{ReflectMut, TypeInfo};
+use bevy_erm::prelude::{ColumnDefinition, TableDefinition};
+use rusqlite::Row;
+
+/// The order of the Euler angles of a rotation marked as `EulerAngles`.
+pub(crate) const EULER_ROT: EulerRot = EulerRot::XYZ;
+
+/// The components of the vector stored in the given column, if it is a glam vector. Rotations
+/// have one component per Euler angle.
+pub(crate) fn get_components(col: &ColumnDefinition) -> Option<&'static [&'static str]> {
+    if col.ty.is::<Vec2>() {
+        Some(&["x", "y"])
+    } else if col.ty.is::<Vec3>() {
+        Some(&["x", "y", "z"])
+    } else if col.ty.is::<Vec4>() {
+        Some(&["x", "y", "z", "w"])
+    } else if col.ty.is::<Quat>() {
+        Some(&["x", "y", "z"])
+    } else {
+        None
+    }
+}
+
+/// True, if the vector stored in the given column is flattened into one column per component,
+/// or the rotation stored in it is split into its Euler angles.
+pub(crate) fn is_flattened(type_info: Option<&TypeInfo>, col: &ColumnDefinition) -> bool {
+    let Some(type_info) = type_info else {
+        return false;
+    };
+
+    if col.ty.is::<Quat>() {
+        return get_field_attribute::<EulerAngles>(type_info, &col.rust_name).is_some();
+    }
+
+    get_components(col).is_some()
+        && get_field_attribute::<Flatten>(type_info, &col.rust_name).is_some()
+}
+
+/// The names of the columns storing the components of a flattened vector, e.g. `pos_x`.
+pub(crate) fn get_component_names(col: &ColumnDefinition) -> Vec<String> {
+    get_components(col)
+        .unwrap_or_default()
+        .iter()
+        .map(|c| format!("{}_{}", col.sql_name, c))
+        .collect()
+}
+
+/// Read the components of all flattened vectors and assign them to their fields. Vectors with
+/// missing component columns keep their value.
+pub(crate) fn read_flattened_columns(
+    table_def: &TableDefinition,
+    names: &[String],
+    row: &Row,
+    value: &mut dyn PartialReflect,
+) -> rusqlite::Result<()> {
+    let type_info = value.get_represented_type_info();
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return Ok(());
+    };
+
+    for col in table_def.fields.values() {
+        if !is_flattened(type_info, col) {
+            continue;
+        }
+
+        let mut components: Vec<f32> = Vec::new();
+        for name in get_component_names(col) {
+            let Some(x) = names.iter().position(|n| *n == name) else {
+                break;
+            };
+            components.push(row.get::<usize, f32>(x)?);
+        }
+
+        let Some(field) = target
+            .field_mut(&col.rust_name)
+            .and_then(|f| f.try_as_reflect_mut())
+        else {
+            continue;
+        };
+
+        let field = field.as_any_mut();
+        if let (Some(f), [x, y]) = (field.downcast_mut::<Vec2>(), components.as_slice()) {
+            *f = Vec2::new(*x, *y);
+        } else if let (Some(f), [x, y, z]) = (field.downcast_mut::<Vec3>(), components.as_slice()) {
+            *f = Vec3::new(*x, *y, *z);
+        } else if let (Some(f), [x, y, z, w]) =
+            (field.downcast_mut::<Vec4>(), components.as_slice())
+        {
+            *f = Vec4::new(*x, *y, *z, *w);
+        } else if let (Some(f), [x, y, z]) = (field.downcast_mut::<Quat>(), components.as_slice()) {
+            *f = Quat::from_euler(EULER_ROT, *x, *y, *z);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_component_names, is_flattened};
+    use crate::prelude::{EulerAngles, Flatten};
+    use bevy::prelude::*;
+    use bevy::reflect::Typed;
+    use bevy_erm::prelude::ErmTypesRegistry;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Marker {
+        #[reflect(@Flatten)]
+        pos: Vec3,
+        #[reflect(@Flatten)]
+        name: String,
+        size: Vec2,
+        #[reflect(@Flatten)]
+        rotation: Quat,
+        #[reflect(@EulerAngles)]
+        heading: Quat,
+    }
+
+    fn run_test(app_registry: Res<AppTypeRegistry>, mut registry: ResMut<ErmTypesRegistry>) {
+        registry.register_type::<Marker>(&app_registry);
+        let table = registry.get_table_definition("Marker").unwrap();
+        let info = Some(Marker::type_info());
+
+        let pos = table.get("pos").unwrap();
+        assert!(is_flattened(info, pos));
+        assert_eq!(get_component_names(pos), vec!["pos_x", "pos_y", "pos_z"]);
+
+        // Only vectors with the attribute are flattened.
+        assert!(!is_flattened(info, table.get("name").unwrap()));
+        assert!(!is_flattened(info, table.get("size").unwrap()));
+        assert!(!is_flattened(None, pos));
+
+        // Rotations are split into their Euler angles.
+        let heading = table.get("heading").unwrap();
+        assert!(is_flattened(info, heading));
+        assert_eq!(
+            get_component_names(heading),
+            vec!["heading_x", "heading_y", "heading_z"]
+        );
+        assert!(!is_flattened(info, table.get("rotation").unwrap()));
+    }
+
+    #[test]
+    fn test_is_flattened() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(crate::prelude::SqliteDatabase::default());
+        app.register_type::<Marker>();
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
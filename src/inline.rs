@@ -0,0 +1,153 @@
+use crate::attributes::Inline;
+use crate::plugin::sort_columns;
+use crate::prelude::SqliteDatabase;
+use bevy::prelude::*;
+use bevy::reflect::{DynamicStruct, ReflectMut, Type, TypeInfo};
+use bevy_erm::prelude::{ColumnDefinition, TableDefinition};
+use rusqlite::Row;
+
+/// The type info of the struct stored in the given column, if the field is marked as `Inline`.
+pub(crate) fn get_inline_type(
+    type_info: Option<&TypeInfo>,
+    col: &ColumnDefinition,
+) -> Option<&'static TypeInfo> {
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return None;
+    };
+
+    info.field(&col.rust_name)?.get_attribute::<Inline>()?;
+    match info.field(&col.rust_name)?.type_info()? {
+        inner @ TypeInfo::Struct(_) => Some(inner),
+        _ => None,
+    }
+}
+
+/// The names of the fields of an inlined struct, which are also the names of their columns.
+pub(crate) fn get_inline_names(inner: &TypeInfo) -> Vec<&'static str> {
+    match inner {
+        TypeInfo::Struct(info) => info.field_names().to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+/// Make sure that the columns of inlined structs neither collide with the columns of the table
+/// nor with each other.
+pub(crate) fn check_inline_names(
+    table: &TableDefinition,
+    type_info: Option<&TypeInfo>,
+) -> Result<(), String> {
+    let mut names: Vec<&str> = table
+        .fields
+        .values()
+        .filter(|x| get_inline_type(type_info, x).is_none())
+        .map(|x| x.sql_name.as_str())
+        .collect();
+
+    for col in sort_columns(table) {
+        let Some(inner) = get_inline_type(type_info, col) else {
+            continue;
+        };
+
+        for name in get_inline_names(inner) {
+            if names.contains(&name) {
+                return Err(format!(
+                    "Field {name} of the inlined field {} collides with another column of table {}.",
+                    col.rust_name, table.sql_name
+                ));
+            }
+            names.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+/// The column definitions (name, type and constraints) of the fields of an inlined struct.
+pub(crate) fn get_inline_column_sql(inner: &TypeInfo) -> Result<String, String> {
+    let TypeInfo::Struct(info) = inner else {
+        return Err(format!("{} is not a struct.", inner.type_path()));
+    };
+
+    let mut columns: Vec<String> = Vec::new();
+    for field in info.iter() {
+        let Some(sql_type) = get_inline_sql_type(field.ty()) else {
+            return Err(format!(
+                "Field {} of {} cannot be inlined.",
+                field.name(),
+                info.type_path()
+            ));
+        };
+
+        columns.push(format!("{} {}", field.name(), sql_type));
+    }
+
+    Ok(columns.join(",\n"))
+}
+
+fn get_inline_sql_type(ty: &Type) -> Option<&'static str> {
+    macro_rules! sql_type {
+        ($($t:ty => $sql:literal),*) => {
+            $(
+                if ty.is::<$t>() {
+                    return Some(concat!($sql, " NOT NULL"));
+                }
+
+                if ty.is::<Option<$t>>() {
+                    return Some($sql);
+                }
+            )*
+        };
+    }
+
+    sql_type!(
+        i8 => "INTEGER",
+        i16 => "INTEGER",
+        i32 => "INTEGER",
+        i64 => "INTEGER",
+        u8 => "INTEGER",
+        u16 => "INTEGER",
+        u32 => "INTEGER",
+        u64 => "INTEGER",
+        f32 => "REAL",
+        f64 => "REAL",
+        bool => "INTEGER",
+        String => "TEXT"
+    );
+
+    None
+}
+
+/// Read the columns of all inlined structs and assign them to their fields. Fields of inlined
+/// structs without a column keep their value.
+pub(crate) fn read_inline_columns(
+    table_def: &TableDefinition,
+    names: &[String],
+    row: &Row,
+    value: &mut dyn PartialReflect,
+) -> rusqlite::Result<()> {
+    let type_info = value.get_represented_type_info();
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return Ok(());
+    };
+
+    for col in table_def.fields.values() {
+        let Some(inner) = get_inline_type(type_info, col) else {
+            continue;
+        };
+
+        let mut dyn_type = DynamicStruct::default();
+        for name in get_inline_names(inner) {
+            let Some(x) = names.iter().position(|n| n == name) else {
+                continue;
+            };
+
+            SqliteDatabase::read_unmapped_column(Some(inner), row, x, name, &mut dyn_type)?;
+        }
+
+        if let Some(field) = target.field_mut(&col.rust_name) {
+            field.apply(&dyn_type);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use crate::child_table::is_stored_in_child_table;
+use crate::converters::Converters;
+use crate::entity_names::EntityNames;
+use crate::flatten::{get_component_names, is_flattened};
+use crate::plugin::{is_zero_sized, sort_columns};
+use crate::prelude::SqliteDatabase;
+use crate::range::{get_range_column_names, get_range_sql_type};
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+use bevy_erm::prelude::{ColumnDefinition, TableDefinition};
+use rusqlite::Row;
+
+/// The separator between the alias of a table and the name of a column in a joined result,
+/// e.g. `p.name`.
+pub const JOIN_ALIAS_SEPARATOR: &str = ".";
+
+/// Rows of a join can be mapped into tuples of values, one per joined table. Column names are
+/// ambiguous within a join, so the columns of every table have to be selected with the alias of
+/// the table as prefix, e.g. `SELECT p.name AS "p.name", h.value AS "h.value" ...`.
+/// `SqliteDatabase::get_join_columns` generates these select lists.
+pub trait FromJoinedRow: Sized {
+    /// The number of values of the tuple, which equals the number of joined tables.
+    const COUNT: usize;
+
+    /// Read the values of all tables from the row. Every table is given with its alias.
+    fn from_joined_row(
+        tables: &[(&str, &TableDefinition)],
+        names: &[String],
+        row: &Row,
+        entity_names: &EntityNames,
+        converters: &Converters,
+    ) -> rusqlite::Result<Self>;
+}
+
+/// The column names of the given alias without the prefix. The columns of all other aliases
+/// are masked with an empty name, so they are skipped when reading the row.
+fn get_alias_names(alias: &str, names: &[String]) -> Vec<String> {
+    let prefix = format!("{alias}{JOIN_ALIAS_SEPARATOR}");
+
+    names
+        .iter()
+        .map(|x| x.strip_prefix(&prefix).unwrap_or_default().to_string())
+        .collect()
+}
+
+macro_rules! impl_from_joined_row {
+    ($count:literal, $($t:ident => $x:tt),*) => {
+        impl<$($t: Default + Reflect),*> FromJoinedRow for ($($t,)*) {
+            const COUNT: usize = $count;
+
+            fn from_joined_row(
+                tables: &[(&str, &TableDefinition)],
+                names: &[String],
+                row: &Row,
+                entity_names: &EntityNames,
+                converters: &Converters,
+            ) -> rusqlite::Result<Self> {
+                Ok(($(
+                    SqliteDatabase::read_value::<$t>(
+                        tables[$x].1,
+                        &get_alias_names(tables[$x].0, names),
+                        row,
+                        entity_names,
+                        converters,
+                    )?,
+                )*))
+            }
+        }
+    };
+}
+
+impl_from_joined_row!(2, A => 0, B => 1);
+impl_from_joined_row!(3, A => 0, B => 1, C => 2);
+impl_from_joined_row!(4, A => 0, B => 1, C => 2, D => 3);
+impl_from_joined_row!(5, A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_from_joined_row!(6, A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+
+impl SqliteDatabase {
+    /// The select list of all columns of the table of type `T`, prefixed with the given alias,
+    /// e.g. `p.id AS "p.id", p.name AS "p.name"`.
+    pub fn get_join_columns<T: Typed>(alias: &str, def: &TableDefinition) -> String {
+        let type_info = Some(T::type_info());
+        let sorted: Vec<&ColumnDefinition> = sort_columns(def)
+            .into_iter()
+            .filter(|x| !is_stored_in_child_table(x) && !is_zero_sized(type_info, x))
+            .collect();
+
+        let mut columns: Vec<String> = Vec::new();
+        for col in sorted {
+            if is_flattened(type_info, col) {
+                columns.extend(get_component_names(col));
+            } else if get_range_sql_type(col).is_some() {
+                columns.extend(get_range_column_names(col));
+            } else {
+                columns.push(col.sql_name.clone());
+            }
+        }
+
+        columns
+            .iter()
+            .map(|x| format!("{alias}.{x} AS \"{alias}{JOIN_ALIAS_SEPARATOR}{x}\""))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, SqliteDbError};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Component, Default, Reflect)]
+    #[reflect(Default)]
+    struct Hero {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        guild_id: i32,
+    }
+
+    #[derive(Component, Default, Reflect)]
+    #[reflect(Default)]
+    struct Guild {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_joined_row.sqlite");
+        registry.register_type::<Hero>(&app_registry);
+        registry.register_type::<Guild>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let hero = erm_registry.get_table_definition("Hero").unwrap();
+        let guild = erm_registry.get_table_definition("Guild").unwrap();
+        database.create_table(hero).unwrap();
+        database.create_table(guild).unwrap();
+
+        for name in ["Mages", "Thieves"] {
+            let value = Guild {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            database.insert(guild, &value, &registry).unwrap();
+        }
+
+        for (name, guild_id) in [("Rincewind", 1), ("Nobby", 2), ("Ridcully", 1)] {
+            let value = Hero {
+                name: name.to_string(),
+                guild_id,
+                ..Default::default()
+            };
+            database.insert(hero, &value, &registry).unwrap();
+        }
+
+        let columns = format!(
+            "{}, {}",
+            SqliteDatabase::get_join_columns::<Hero>("h", hero),
+            SqliteDatabase::get_join_columns::<Guild>("g", guild)
+        );
+        assert!(columns.contains("h.name AS \"h.name\""));
+        assert!(columns.contains("g.name AS \"g.name\""));
+
+        let query = format!(
+            "SELECT {columns} FROM Hero h JOIN Guild g ON h.guild_id = g.id \
+             WHERE g.name = ?1 ORDER BY h.id;"
+        );
+        let test: Vec<(Hero, Guild)> = database
+            .query_bundle(&[("h", hero), ("g", guild)], &query, &[&"Mages"])
+            .unwrap();
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].0.name, "Rincewind");
+        assert_eq!(test[1].0.name, "Ridcully");
+        assert_eq!(test[1].1.name, "Mages");
+        assert_eq!(test[1].1.id, 1);
+
+        // The number of tables has to match the tuple.
+        let result: Result<Vec<(Hero, Guild)>, SqliteDbError> =
+            database.query_bundle(&[("h", hero)], &query, &[&"Mages"]);
+        assert!(result.is_err());
+
+        // The values can be spawned as a bundle.
+        let mut world = World::new();
+        for bundle in test {
+            world.spawn(bundle);
+        }
+        assert_eq!(world.query::<(&Hero, &Guild)>().iter(&world).count(), 2);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_bundle() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Hero>();
+        app.register_type::<Guild>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,197 @@
+//! Many to many relations (`SqlType::Many2Many`) are stored in a junction table named
+//! `<Table>_<Related>`, with one row per pair of related keys. The relation field itself is not
+//! part of the table. `create_table` creates the junction tables of a table together with the
+//! table. The related table is looked up by the type id of the relation in the type registry
+//! of the options (see `TableSqlOptions::set_type_registry`).
+
+use crate::prelude::{SqliteDatabase, SqliteDbError, TableSqlOptions};
+use crate::relation::get_related_table;
+use bevy::prelude::*;
+use bevy_erm::prelude::{ColumnDefinition, SqlType, TableDefinition};
+
+impl SqliteDatabase {
+    /// The name of the junction table relating the rows of the table to the related table.
+    pub fn get_junction_table_name(def: &TableDefinition, related: &str) -> String {
+        format!("{}_{}", def.sql_name, related)
+    }
+
+    /// The names of the columns of the junction table referencing the keys of both tables.
+    /// A table related to itself references the related rows with `other_<table>_id`.
+    pub fn get_junction_column_names(def: &TableDefinition, related: &str) -> [String; 2] {
+        let column = format!("{}_id", def.sql_name.to_lowercase());
+        let related_column = format!("{}_id", related.to_lowercase());
+        if column == related_column {
+            return [column, format!("other_{related_column}")];
+        }
+
+        [column, related_column]
+    }
+
+    /// Generate the SQL to create the junction table of the many to many relation stored in
+    /// the given column.
+    pub fn get_junction_table_sql(
+        def: &TableDefinition,
+        col: &ColumnDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<String, SqliteDbError> {
+        let SqlType::Many2Many(type_id, _) = col.sql_type else {
+            return Err(format!("Column {} is not a many to many relation.", col.sql_name).into());
+        };
+        let Some(key) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(def.sql_name.clone()));
+        };
+        let (related, related_key) = get_related_table(options, col, type_id)?;
+
+        let [column, related_column] = Self::get_junction_column_names(def, &related);
+        let table_name = Self::get_junction_table_name(def, &related);
+        Ok(format!(
+            "CREATE TABLE '{table_name}'(\
+             {column} INTEGER NOT NULL REFERENCES '{}'({}),\n\
+             {related_column} INTEGER NOT NULL REFERENCES '{related}'({related_key}),\n\
+             PRIMARY KEY({column}, {related_column}));",
+            def.sql_name, key.sql_name
+        ))
+    }
+
+    /// Create the junction tables of all many to many relations of the table, unless they
+    /// exist.
+    pub(crate) fn create_junction_tables(
+        &mut self,
+        def: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<(), SqliteDbError> {
+        for col in def.fields.values() {
+            let SqlType::Many2Many(type_id, _) = col.sql_type else {
+                continue;
+            };
+
+            let (related, _) = get_related_table(options, col, type_id)?;
+            let table_name = Self::get_junction_table_name(def, &related);
+            if self.table_exists(&table_name)? {
+                info!("A table with the name {table_name} already exists");
+                continue;
+            }
+
+            let table_sql = Self::get_junction_table_sql(def, col, options)?;
+            self.execute(&table_sql, &[])?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn is_many_to_many(def: &ColumnDefinition) -> bool {
+    matches!(def.sql_type, SqlType::Many2Many(..))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, TableSqlOptions};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key, SqlType};
+    use std::any::TypeId;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Student {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        courses: Vec<Course>,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Course {
+        #[reflect(@Key)]
+        number: i32,
+        title: String,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_junction_table.sqlite");
+        registry.register_type::<Student>(&app_registry);
+        registry.register_type::<Course>(&app_registry);
+    }
+
+    fn run_test(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let student = erm_registry.get_table_definition("Student").unwrap();
+        let course = erm_registry.get_table_definition("Course").unwrap();
+        let courses = student.get("courses").unwrap();
+        assert!(matches!(
+            courses.sql_type,
+            SqlType::Many2Many(type_id, _) if type_id == TypeId::of::<Course>()
+        ));
+
+        // The relation is not a column of the table.
+        let sql = SqliteDatabase::get_table_sql(student).unwrap();
+        assert!(!sql.contains("courses"));
+
+        // The related table is looked up in the type registry.
+        let options = TableSqlOptions::default();
+        assert!(SqliteDatabase::get_junction_table_sql(student, courses, &options).is_err());
+        let mut options = TableSqlOptions::default();
+        options.set_type_registry(database.get_type_registry());
+        let sql = SqliteDatabase::get_junction_table_sql(student, courses, &options).unwrap();
+        assert!(sql.contains("course_id INTEGER NOT NULL REFERENCES 'Course'(number)"));
+
+        // Creating the tables creates their junction tables.
+        database.create_table(student).unwrap();
+        database.create_table(course).unwrap();
+        assert!(database.table_exists("Student_Course").unwrap());
+
+        database
+            .execute("INSERT INTO Student (name) VALUES ('Runna vom Sofa');", &[])
+            .unwrap();
+        database
+            .execute("INSERT INTO Course (title) VALUES ('Databases');", &[])
+            .unwrap();
+        database
+            .execute(
+                "INSERT INTO Student_Course (student_id, course_id) VALUES (1, 1);",
+                &[],
+            )
+            .unwrap();
+        assert!(database
+            .execute(
+                "INSERT INTO Student_Course (student_id, course_id) VALUES (1, 1);",
+                &[],
+            )
+            .is_err());
+
+        // Creating the table again skips the existing junction table.
+        database.create_table_if_not_exists(student).unwrap();
+        assert_eq!(
+            SqliteDatabase::get_junction_column_names(course, "Course"),
+            ["course_id", "other_course_id"]
+        );
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_junction_table() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Student>();
+        app.register_type::<Course>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
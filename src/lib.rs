@@ -1,10 +1,71 @@
+mod attributes;
+mod checksum;
+mod child_table;
+mod column_info;
+mod content_version;
+mod converters;
+mod create_tables;
+mod date_time;
+mod decimal;
+mod describe;
+mod entity_names;
+mod error;
+mod flatten;
+mod inline;
+mod joined_row;
+mod junction_table;
+mod mapping_error_policy;
+mod migration;
+mod normalize;
+mod packed_bits;
+mod persist;
 mod plugin;
+mod range;
+mod relation;
+mod retry_policy;
+mod roundtrip;
+mod schema_dot;
+mod schema_hash;
+mod shared;
+mod small_string;
+mod spawn;
 mod sqlite_connection_settings;
+mod table_sql_options;
+mod tagged_enum;
+mod transaction;
+mod unsupported_fields;
 mod value_to_sql_wrapper;
 
 pub mod prelude {
+    pub use crate::attributes::{
+        BoolAsText, Checksum, Collate, Discriminant, EulerAngles, Flatten, Inline, IntegerEnum,
+        PackedBit, RelateByName, TaggedEnum,
+    };
+    pub use crate::child_table::{
+        CHILD_ENTITY_COLUMN, CHILD_PARENT_COLUMN, CHILD_POSITION_COLUMN, CHILD_VALUE_COLUMN,
+    };
+    pub use crate::column_info::ColumnInfo;
+    pub use crate::content_version::METADATA_TABLE;
+    pub use crate::converters::Converters;
+    pub use crate::describe::ColumnMapping;
+    pub use crate::entity_names::EntityNames;
+    pub use crate::error::SqliteDbError;
+    pub use crate::joined_row::{FromJoinedRow, JOIN_ALIAS_SEPARATOR};
+    pub use crate::mapping_error_policy::{MappedRows, MappingErrorPolicy, RowMappingError};
+    pub use crate::migration::{MigrationStep, SchemaMismatch};
+    #[cfg(feature = "unicode-normalization")]
+    pub use crate::normalize::{Normalize, NormalizeOnRead};
+    pub use crate::persist::TableWrite;
     pub use crate::plugin::SqliteDatabase;
+    pub use crate::range::{RANGE_MAX_SUFFIX, RANGE_MIN_SUFFIX};
+    pub use crate::retry_policy::RetryPolicy;
+    pub use crate::schema_hash::SCHEMA_HASH_TABLE;
+    #[cfg(feature = "compact_str")]
+    pub use crate::small_string::CompactText;
     pub use crate::sqlite_connection_settings::SqliteConnectionSettings;
+    pub use crate::table_sql_options::TableSqlOptions;
+    pub use crate::tagged_enum::TAGGED_VALUE_SUFFIX;
+    pub use crate::transaction::TransactionCtx;
     pub use crate::value_to_sql_wrapper::ValueWrapper;
 }
 
@@ -0,0 +1,35 @@
+/// Decides what happens if a single row of a query result cannot be mapped to the target type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MappingErrorPolicy {
+    /// The whole query fails with the first row that cannot be mapped.
+    #[default]
+    FailFast,
+    /// Rows that cannot be mapped are skipped. Their errors are collected and returned
+    /// alongside the successfully mapped rows.
+    SkipAndCollect,
+}
+
+/// Describes a row of a query result that could not be mapped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowMappingError {
+    /// The zero based index of the row within the result set.
+    pub row: usize,
+    pub message: String,
+}
+
+/// The result of a query using `MappingErrorPolicy::SkipAndCollect`.
+#[derive(Debug)]
+pub struct MappedRows<T> {
+    pub rows: Vec<T>,
+    pub errors: Vec<RowMappingError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MappingErrorPolicy;
+
+    #[test]
+    fn test_default_policy() {
+        assert_eq!(MappingErrorPolicy::default(), MappingErrorPolicy::FailFast);
+    }
+}
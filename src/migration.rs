@@ -0,0 +1,535 @@
+use crate::child_table::is_stored_in_child_table;
+use crate::plugin::{is_zero_sized, sort_columns};
+use crate::prelude::{SqliteDatabase, SqliteDbError, TableSqlOptions};
+use bevy_erm::prelude::{ErmTypesRegistry, TableDefinition};
+
+/// A single statement of a migration plan (see `SqliteDatabase::plan_migration`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum MigrationStep {
+    /// The table does not exist yet.
+    CreateTable { table: String, sql: String },
+    /// The table exists, but the column is missing.
+    AddColumn {
+        table: String,
+        column: String,
+        sql: String,
+    },
+}
+
+impl MigrationStep {
+    /// The statement executed by this step.
+    pub fn sql(&self) -> &str {
+        match self {
+            MigrationStep::CreateTable { sql, .. } | MigrationStep::AddColumn { sql, .. } => sql,
+        }
+    }
+}
+
+/// A difference between a table in the database and its definition
+/// (see `SqliteDatabase::validate_schema`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaMismatch {
+    /// The table does not exist.
+    MissingTable { table: String },
+    /// The definition contains the column, but the table does not.
+    MissingColumn { column: String },
+    /// The table contains the column, but the definition does not.
+    ExtraColumn { column: String },
+    /// The declared type of the column differs from the type generated for the definition.
+    TypeMismatch {
+        column: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl SqliteDatabase {
+    /// Compute the statements needed to bring the tables of the given types in line with their
+    /// registered definitions, without executing them. Missing tables are created and missing
+    /// columns are added. Columns which are no longer part of a definition are kept and changed
+    /// column types are not detected. Log or confirm the plan before passing it to
+    /// `apply_migration`.
+    ///
+    /// Added `NOT NULL` columns get the default value of their type (0, empty text or an empty
+    /// blob), because sqlite cannot add them to a table with rows otherwise.
+    pub fn plan_migration(
+        &mut self,
+        registry: &ErmTypesRegistry,
+        type_names: &[&str],
+    ) -> Result<Vec<MigrationStep>, SqliteDbError> {
+        let options = self.get_sql_options(&TableSqlOptions::default());
+
+        let mut steps: Vec<MigrationStep> = Vec::new();
+        for name in type_names {
+            let Some(def) = registry.get_table_definition(name) else {
+                return Err(SqliteDbError::UnknownType(name.to_string()));
+            };
+
+            let table = def.sql_name.clone();
+            if !self.table_exists(&table)? {
+                let sql = Self::get_table_sql_with_options(def, &options)?;
+                steps.push(MigrationStep::CreateTable { table, sql });
+                continue;
+            }
+
+            steps.extend(self.plan_missing_columns(def, &options)?);
+        }
+
+        Ok(steps)
+    }
+
+    /// Add the columns of the definition which are missing in the existing table, e.g. after
+    /// a field was added to the struct. Returns the number of added columns. This covers
+    /// additive changes only, use `plan_migration` to create missing tables as well.
+    pub fn add_missing_columns(&mut self, def: &TableDefinition) -> Result<usize, SqliteDbError> {
+        if !self.table_exists(&def.sql_name)? {
+            return Err(SqliteDbError::UnknownTable(def.sql_name.clone()));
+        }
+
+        let options = self.get_sql_options(&TableSqlOptions::default());
+        let steps = self.plan_missing_columns(def, &options)?;
+        self.apply_migration(&steps)?;
+
+        Ok(steps.len())
+    }
+
+    /// The steps adding the columns of the definition which are missing in the existing table.
+    fn plan_missing_columns(
+        &mut self,
+        def: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<Vec<MigrationStep>, SqliteDbError> {
+        let table = def.sql_name.clone();
+        let existing: Vec<String> = self
+            .get_columns(&table)?
+            .into_iter()
+            .map(|x| x.name)
+            .collect();
+
+        let mut steps: Vec<MigrationStep> = Vec::new();
+        for column in get_column_sqls(def, options)? {
+            let column_name = get_column_name(&column);
+            if existing.iter().any(|x| x == column_name) {
+                continue;
+            }
+
+            steps.push(MigrationStep::AddColumn {
+                table: table.clone(),
+                column: column_name.to_string(),
+                sql: format!(
+                    "ALTER TABLE '{table}' ADD COLUMN {};",
+                    with_default_value(&column)
+                ),
+            });
+        }
+
+        Ok(steps)
+    }
+
+    /// Compare the columns of the table in the database with the columns generated for its
+    /// definition. Reports missing and extra columns as well as columns whose declared type
+    /// differs. Constraints and defaults are not compared. Returns no mismatches, if the table
+    /// matches its definition.
+    pub fn validate_schema(
+        &mut self,
+        def: &TableDefinition,
+    ) -> Result<Vec<SchemaMismatch>, SqliteDbError> {
+        if !self.table_exists(&def.sql_name)? {
+            return Ok(vec![SchemaMismatch::MissingTable {
+                table: def.sql_name.clone(),
+            }]);
+        }
+
+        let options = self.get_sql_options(&TableSqlOptions::default());
+        let expected = get_column_sqls(def, &options)?;
+        let existing = self.get_columns(&def.sql_name)?;
+
+        let mut mismatches: Vec<SchemaMismatch> = Vec::new();
+        for column in expected.iter() {
+            let column_name = get_column_name(column);
+            let Some(info) = existing.iter().find(|x| x.name == column_name) else {
+                mismatches.push(SchemaMismatch::MissingColumn {
+                    column: column_name.to_string(),
+                });
+                continue;
+            };
+
+            let expected_type = get_column_type(column);
+            if !info.declared_type.eq_ignore_ascii_case(expected_type) {
+                mismatches.push(SchemaMismatch::TypeMismatch {
+                    column: column_name.to_string(),
+                    expected: expected_type.to_string(),
+                    actual: info.declared_type.clone(),
+                });
+            }
+        }
+
+        for info in existing {
+            if !expected.iter().any(|x| get_column_name(x) == info.name) {
+                mismatches.push(SchemaMismatch::ExtraColumn { column: info.name });
+            }
+        }
+
+        Ok(mismatches)
+    }
+
+    /// The schema version stored in the database (`PRAGMA user_version`), which is 0 for a new
+    /// database.
+    pub fn get_user_version(&mut self) -> Result<i32, SqliteDbError> {
+        Ok(self
+            .query_scalar::<i32>("PRAGMA user_version;", &[])?
+            .unwrap_or_default())
+    }
+
+    /// Run every migration with a version greater than the `user_version` of the database, in
+    /// the order of their versions. Each migration may consist of several statements and runs
+    /// in its own transaction, which also sets `user_version` to its version. If a migration
+    /// fails, it is rolled back and the remaining migrations are not run, while the migrations
+    /// before it stay applied.
+    pub fn run_migrations(&mut self, migrations: &[(i32, &str)]) -> Result<(), SqliteDbError> {
+        let current = self.get_user_version()?;
+
+        let mut pending: Vec<&(i32, &str)> =
+            migrations.iter().filter(|(v, _)| *v > current).collect();
+        pending.sort_by_key(|(v, _)| *v);
+        for (version, sql) in pending {
+            self.savepoint(&format!("migration_{version}"), |database| {
+                database.execute_batch(sql)?;
+                database.execute(&format!("PRAGMA user_version = {version};"), &[])?;
+                Ok(())
+            })
+            .map_err(|e| SqliteDbError::Other(format!("Migration {version} failed: {e}")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute the steps of a migration plan. Either all steps are applied or none of them.
+    pub fn apply_migration(&mut self, steps: &[MigrationStep]) -> Result<(), SqliteDbError> {
+        self.savepoint("apply_migration", |database| {
+            for step in steps {
+                database.execute(step.sql(), &[])?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// The definitions of all columns of the table, one per column.
+fn get_column_sqls(
+    def: &TableDefinition,
+    options: &TableSqlOptions,
+) -> Result<Vec<String>, SqliteDbError> {
+    let mut columns: Vec<String> = Vec::new();
+    for col in sort_columns(def) {
+        if is_stored_in_child_table(col) || is_zero_sized(None, col) {
+            continue;
+        }
+
+        let column_sql = SqliteDatabase::get_column_sql(col, options)?;
+        columns.extend(column_sql.split(",\n").map(|x| x.to_string()));
+    }
+
+    Ok(columns)
+}
+
+pub(crate) fn get_column_name(column: &str) -> &str {
+    column.split_whitespace().next().unwrap_or_default()
+}
+
+/// Keywords starting the constraints of a column definition.
+const CONSTRAINTS: [&str; 8] = [
+    "NOT",
+    "PRIMARY",
+    "UNIQUE",
+    "CHECK",
+    "DEFAULT",
+    "COLLATE",
+    "REFERENCES",
+    "GENERATED",
+];
+
+/// The declared type of a column definition including its size, e.g. `VARCHAR(20)`, which is
+/// empty for columns without a type.
+fn get_column_type(column: &str) -> &str {
+    column
+        .split_whitespace()
+        .nth(1)
+        .filter(|x| {
+            let word = x.split('(').next().unwrap_or_default();
+            !CONSTRAINTS.iter().any(|c| c.eq_ignore_ascii_case(word))
+        })
+        .unwrap_or_default()
+}
+
+/// Append the default value of the column type to a `NOT NULL` column without a default.
+fn with_default_value(column: &str) -> String {
+    if !column.contains(" NOT NULL") || column.contains(" DEFAULT ") {
+        return column.to_string();
+    }
+
+    let column_type = get_column_type(column);
+    let default = if column_type == "TEXT" || column_type.starts_with("VARCHAR") {
+        "''"
+    } else if column_type == "BLOB" {
+        "X''"
+    } else {
+        "0"
+    };
+
+    format!("{column} DEFAULT {default}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MigrationStep, SchemaMismatch};
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, SqliteDbError};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key, MaxLength};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Hero {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        level: u8,
+        title: Option<String>,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Quest {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Guild {
+        #[reflect(@Key)]
+        id: i32,
+        #[reflect(@MaxLength(20))]
+        name: String,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_migration.sqlite");
+        registry.register_type::<Hero>(&app_registry);
+        registry.register_type::<Quest>(&app_registry);
+        registry.register_type::<Guild>(&app_registry);
+    }
+
+    fn run_test(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        // An older version of the hero without level and title.
+        database
+            .execute(
+                "CREATE TABLE 'Hero'(id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL);",
+                &[],
+            )
+            .unwrap();
+        database
+            .execute("INSERT INTO Hero (name) VALUES ('Conan');", &[])
+            .unwrap();
+
+        let plan = database
+            .plan_migration(&erm_registry, &["Hero", "Quest"])
+            .unwrap();
+        assert_eq!(plan.len(), 3);
+        assert!(matches!(
+            &plan[0],
+            MigrationStep::AddColumn { column, .. } if column == "level"
+        ));
+        assert_eq!(
+            plan[0].sql(),
+            "ALTER TABLE 'Hero' ADD COLUMN level INTEGER NOT NULL CHECK(level >= 0) DEFAULT 0;"
+        );
+        assert_eq!(plan[1].sql(), "ALTER TABLE 'Hero' ADD COLUMN title TEXT;");
+        assert!(matches!(
+            &plan[2],
+            MigrationStep::CreateTable { table, .. } if table == "Quest"
+        ));
+
+        // Planning does not change the database.
+        assert!(!database.table_exists("Quest").unwrap());
+        let hero = erm_registry.get_table_definition("Hero").unwrap();
+        let quest = erm_registry.get_table_definition("Quest").unwrap();
+        assert_eq!(
+            database.validate_schema(hero).unwrap(),
+            vec![
+                SchemaMismatch::MissingColumn {
+                    column: "level".to_string()
+                },
+                SchemaMismatch::MissingColumn {
+                    column: "title".to_string()
+                },
+            ]
+        );
+
+        database.apply_migration(&plan).unwrap();
+        assert!(database.table_exists("Quest").unwrap());
+        let level = database
+            .query_scalar::<i32>("SELECT level FROM Hero WHERE name = 'Conan';", &[])
+            .unwrap();
+        assert_eq!(level, Some(0));
+
+        let plan = database
+            .plan_migration(&erm_registry, &["Hero", "Quest"])
+            .unwrap();
+        assert!(plan.is_empty());
+        assert_eq!(database.add_missing_columns(hero).unwrap(), 0);
+        assert!(database.validate_schema(hero).unwrap().is_empty());
+        assert!(database.validate_schema(quest).unwrap().is_empty());
+
+        database
+            .execute("ALTER TABLE Hero ADD COLUMN mood TEXT;", &[])
+            .unwrap();
+        assert_eq!(
+            database.validate_schema(hero).unwrap(),
+            vec![SchemaMismatch::ExtraColumn {
+                column: "mood".to_string()
+            }]
+        );
+
+        database.drop_table(quest).unwrap();
+        assert_eq!(
+            database.validate_schema(quest).unwrap(),
+            vec![SchemaMismatch::MissingTable {
+                table: "Quest".to_string()
+            }]
+        );
+        database
+            .execute(
+                "CREATE TABLE Quest (id INTEGER PRIMARY KEY, name BLOB);",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            database.validate_schema(quest).unwrap(),
+            vec![SchemaMismatch::TypeMismatch {
+                column: "name".to_string(),
+                expected: "TEXT".to_string(),
+                actual: "BLOB".to_string(),
+            }]
+        );
+
+        // A quest from before the name was added.
+        database.drop_table(quest).unwrap();
+        database
+            .execute(
+                "CREATE TABLE Quest (id INTEGER PRIMARY KEY AUTOINCREMENT);",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(database.add_missing_columns(quest).unwrap(), 1);
+        assert!(database.validate_schema(quest).unwrap().is_empty());
+
+        assert!(matches!(
+            database.plan_migration(&erm_registry, &["Castle"]),
+            Err(SqliteDbError::UnknownType(_))
+        ));
+
+        // Text with a maximum length is declared as VARCHAR.
+        let guild = erm_registry.get_table_definition("Guild").unwrap();
+        database.create_table(guild).unwrap();
+        let columns = database.get_columns("Guild").unwrap();
+        assert_eq!(columns[1].declared_type, "VARCHAR(20)");
+        assert!(database.validate_schema(guild).unwrap().is_empty());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_migration() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Hero>();
+        app.register_type::<Quest>();
+        app.register_type::<Guild>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+
+    fn update_database_path_user_version(mut settings: ResMut<SqliteConnectionSettings>) {
+        settings.set_data_source("test_run_migrations.sqlite");
+    }
+
+    fn run_test_user_version(
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+        assert_eq!(database.get_user_version().unwrap(), 0);
+
+        let migrations = [
+            (
+                2,
+                "ALTER TABLE Hero ADD COLUMN level INTEGER NOT NULL DEFAULT 1;",
+            ),
+            (
+                1,
+                "CREATE TABLE Hero (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+                 INSERT INTO Hero (name) VALUES ('Conan');",
+            ),
+        ];
+        database.run_migrations(&migrations).unwrap();
+        assert_eq!(database.get_user_version().unwrap(), 2);
+        let level = database
+            .query_scalar::<i32>("SELECT level FROM Hero;", &[])
+            .unwrap();
+        assert_eq!(level, Some(1));
+
+        // Applied migrations are not run again.
+        database.run_migrations(&migrations).unwrap();
+        let count = database
+            .query_scalar::<i32>("SELECT COUNT(*) FROM Hero;", &[])
+            .unwrap();
+        assert_eq!(count, Some(1));
+
+        // A failing migration is rolled back, including its version.
+        let failing = [(
+            3,
+            "UPDATE Hero SET level = 2; INSERT INTO Missing VALUES (1);",
+        )];
+        assert!(database.run_migrations(&failing).is_err());
+        assert_eq!(database.get_user_version().unwrap(), 2);
+        let level = database
+            .query_scalar::<i32>("SELECT level FROM Hero;", &[])
+            .unwrap();
+        assert_eq!(level, Some(1));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_run_migrations() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.add_systems(PreStartup, update_database_path_user_version);
+        app.add_systems(Startup, run_test_user_version);
+
+        app.update();
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeInfo;
+
+#[cfg(feature = "unicode-normalization")]
+use crate::attributes::get_field_attribute;
+#[cfg(feature = "unicode-normalization")]
+use bevy::reflect::ReflectMut;
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+/// Normalize the text of a `String` field to the given Unicode normalization form before it is
+/// stored, so strings which look the same are stored byte for byte the same, e.g. file names
+/// created on different platforms. Requires the `unicode-normalization` feature.
+///
+/// ```ignore
+/// #[derive(Reflect, Default)]
+/// struct SaveGame {
+///     #[reflect(@Key)]
+///     id: i32,
+///     #[reflect(@Normalize::Nfc)]
+///     file_name: String,
+/// }
+/// ```
+#[cfg(feature = "unicode-normalization")]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Normalize {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+/// Normalize the text of a field marked as `Normalize` when it is read as well, e.g. for
+/// columns written by other applications. Requires the `unicode-normalization` feature.
+#[cfg(feature = "unicode-normalization")]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NormalizeOnRead;
+
+#[cfg(feature = "unicode-normalization")]
+impl Normalize {
+    /// The text in this normalization form.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Normalize::Nfc => text.nfc().collect(),
+            Normalize::Nfd => text.nfd().collect(),
+            Normalize::Nfkc => text.nfkc().collect(),
+            Normalize::Nfkd => text.nfkd().collect(),
+        }
+    }
+}
+
+/// The normalization of the given field, if it is marked as `Normalize`.
+#[cfg(feature = "unicode-normalization")]
+pub(crate) fn get_normalize(type_info: &TypeInfo, field: &str) -> Option<fn(&str) -> String> {
+    match get_field_attribute::<Normalize>(type_info, field)? {
+        Normalize::Nfc => Some(|x| Normalize::Nfc.apply(x)),
+        Normalize::Nfd => Some(|x| Normalize::Nfd.apply(x)),
+        Normalize::Nfkc => Some(|x| Normalize::Nfkc.apply(x)),
+        Normalize::Nfkd => Some(|x| Normalize::Nfkd.apply(x)),
+    }
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+pub(crate) fn get_normalize(_type_info: &TypeInfo, _field: &str) -> Option<fn(&str) -> String> {
+    None
+}
+
+/// Normalize the text of all fields marked as `Normalize` and `NormalizeOnRead`.
+#[cfg(feature = "unicode-normalization")]
+pub(crate) fn normalize_read_fields(value: &mut dyn PartialReflect) {
+    let Some(TypeInfo::Struct(info)) = value.get_represented_type_info() else {
+        return;
+    };
+
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return;
+    };
+
+    for field in info.iter() {
+        let Some(normalize) = field.get_attribute::<Normalize>() else {
+            continue;
+        };
+        if !field.has_attribute::<NormalizeOnRead>() {
+            continue;
+        }
+
+        let Some(value) = target.field_mut(field.name()) else {
+            continue;
+        };
+        if let Some(text) = value.try_downcast_mut::<String>() {
+            *text = normalize.apply(text);
+        } else if let Some(Some(text)) = value.try_downcast_mut::<Option<String>>() {
+            *text = normalize.apply(text);
+        }
+    }
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+pub(crate) fn normalize_read_fields(_value: &mut dyn PartialReflect) {}
+
+#[cfg(all(test, feature = "unicode-normalization"))]
+mod tests {
+    use crate::prelude::{Normalize, NormalizeOnRead, SqliteConnectionSettings, SqliteDatabase};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    // "é" as a single code point (NFC) and as "e" followed by a combining accent (NFD).
+    const COMPOSED: &str = "caf\u{e9}.sav";
+    const DECOMPOSED: &str = "cafe\u{301}.sav";
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct SaveGame {
+        #[reflect(@Key)]
+        id: i32,
+        #[reflect(@Normalize::Nfc)]
+        file_name: String,
+        #[reflect(@Normalize::Nfc, @NormalizeOnRead)]
+        title: Option<String>,
+        note: String,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_normalize.sqlite");
+        registry.register_type::<SaveGame>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("SaveGame").unwrap();
+        database.create_table(table).unwrap();
+        let save = SaveGame {
+            file_name: DECOMPOSED.to_string(),
+            title: Some(DECOMPOSED.to_string()),
+            note: DECOMPOSED.to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &save, &registry).unwrap();
+
+        let stored: Vec<(String, String)> = database
+            .query_column_pairs("SELECT file_name, note FROM SaveGame;", &[])
+            .unwrap();
+        assert_eq!(stored[0].0, COMPOSED);
+        assert_eq!(stored[0].1, DECOMPOSED);
+
+        // Text written by other applications is normalized on read, if requested.
+        database
+            .execute(
+                "UPDATE SaveGame SET file_name = ?1, title = ?1;",
+                &[&DECOMPOSED],
+            )
+            .unwrap();
+        let test: Vec<SaveGame> = database
+            .query(table, "SELECT * FROM SaveGame;", &[])
+            .unwrap();
+        assert_eq!(test[0].file_name, DECOMPOSED);
+        assert_eq!(test[0].title.as_deref(), Some(COMPOSED));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<SaveGame>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,212 @@
+use crate::attributes::{get_field_attribute, PackedBit};
+use bevy::prelude::*;
+use bevy::reflect::{ReflectMut, TypeInfo};
+use bevy_erm::prelude::ColumnDefinition;
+use rusqlite::Row;
+
+/// The `PackedBit` attribute of the field stored in the given column, if it has one.
+pub(crate) fn get_packed_bit(
+    type_info: Option<&TypeInfo>,
+    col: &ColumnDefinition,
+) -> Option<PackedBit> {
+    get_field_attribute::<PackedBit>(type_info?, &col.rust_name).copied()
+}
+
+/// The packed fields of the type as (field name, attribute), in declaration order.
+fn get_packed_fields(type_info: Option<&TypeInfo>) -> Vec<(&'static str, PackedBit)> {
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return Vec::new();
+    };
+
+    info.iter()
+        .filter_map(|field| {
+            let bit = field.get_attribute::<PackedBit>()?;
+            Some((field.name(), *bit))
+        })
+        .collect()
+}
+
+/// True, if the column stores the packed flags of the type.
+pub(crate) fn is_packed_column(type_info: Option<&TypeInfo>, name: &str) -> bool {
+    get_packed_fields(type_info)
+        .iter()
+        .any(|(_, bit)| bit.0 == name)
+}
+
+/// Check that only `bool` fields are packed and that every bit of a column is used once.
+pub(crate) fn check_packed_bits(type_info: Option<&TypeInfo>) -> Result<(), String> {
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return Ok(());
+    };
+
+    let packed = get_packed_fields(type_info);
+    for (x, (name, bit)) in packed.iter().enumerate() {
+        if !info.field(name).is_some_and(|f| f.is::<bool>()) {
+            return Err(format!(
+                "Only bool fields can be packed, but {name} is not a bool."
+            ));
+        }
+
+        if bit.1 >= 64 {
+            return Err(format!(
+                "Field {name} uses bit {} of column {}, but there are only 64 bits.",
+                bit.1, bit.0
+            ));
+        }
+
+        if let Some((other, _)) = packed[..x].iter().find(|(_, b)| b == bit) {
+            return Err(format!(
+                "Fields {other} and {name} share bit {} of column {}.",
+                bit.1, bit.0
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The bit mask of all flags of the value packed into the given column.
+pub(crate) fn get_packed_value(value: &dyn Struct, column: &str) -> i64 {
+    get_packed_fields(value.get_represented_type_info())
+        .iter()
+        .filter(|(name, bit)| {
+            bit.0 == column
+                && value
+                    .field(name)
+                    .and_then(|f| f.try_downcast_ref::<bool>())
+                    .is_some_and(|f| *f)
+        })
+        .fold(0, |mask, (_, bit)| mask | (1i64 << bit.1))
+}
+
+/// Unpack the flags of all packed columns of the result into their fields.
+pub(crate) fn read_packed_columns(
+    names: &[String],
+    row: &Row,
+    value: &mut dyn PartialReflect,
+) -> rusqlite::Result<()> {
+    let packed = get_packed_fields(value.get_represented_type_info());
+    if packed.is_empty() {
+        return Ok(());
+    }
+
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return Ok(());
+    };
+
+    for (x, column) in names.iter().enumerate() {
+        if !packed.iter().any(|(_, bit)| bit.0 == column) {
+            continue;
+        }
+
+        let mask = row.get::<usize, i64>(x)?;
+        for (name, bit) in packed.iter().filter(|(_, bit)| bit.0 == column) {
+            if let Some(field) = target
+                .field_mut(name)
+                .and_then(|f| f.try_downcast_mut::<bool>())
+            {
+                *field = mask & (1i64 << bit.1) != 0;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{PackedBit, SqliteConnectionSettings, SqliteDatabase, TableSqlOptions};
+    use bevy::prelude::*;
+    use bevy::reflect::Typed;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    use super::check_packed_bits;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Door {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        #[reflect(@PackedBit("flags", 0))]
+        locked: bool,
+        #[reflect(@PackedBit("flags", 1))]
+        hidden: bool,
+        #[reflect(@PackedBit("flags", 5))]
+        trapped: bool,
+        open: bool,
+    }
+
+    #[derive(Default, Reflect)]
+    struct BrokenDoor {
+        #[reflect(@PackedBit("flags", 0))]
+        locked: bool,
+        #[reflect(@PackedBit("flags", 0))]
+        hidden: bool,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_packed_bits.sqlite");
+        registry.register_type::<Door>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Door").unwrap();
+        let options = TableSqlOptions::for_type::<Door>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("flags INTEGER NOT NULL DEFAULT 0"));
+        assert!(!sql.contains("locked"));
+        assert!(sql.contains("open INTEGER NOT NULL"));
+
+        database.create_table_with_options(table, &options).unwrap();
+        let door = Door {
+            name: "Back door".to_string(),
+            locked: true,
+            trapped: true,
+            open: true,
+            ..Default::default()
+        };
+        database.insert(table, &door, &registry).unwrap();
+
+        let flags = database
+            .query_scalar::<i64>("SELECT flags FROM Door;", &[])
+            .unwrap();
+        assert_eq!(flags, Some(0b100001));
+
+        let test: Vec<Door> = database.query(table, "SELECT * FROM Door;", &[]).unwrap();
+        assert!(test[0].locked);
+        assert!(!test[0].hidden);
+        assert!(test[0].trapped);
+        assert!(test[0].open);
+
+        assert!(check_packed_bits(Some(BrokenDoor::type_info())).is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_packed_bits() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Door>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
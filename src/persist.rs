@@ -0,0 +1,173 @@
+use crate::prelude::{SqliteDatabase, SqliteDbError};
+use bevy::prelude::*;
+use bevy_erm::prelude::TableDefinition;
+
+type WriteFn<'a> =
+    Box<dyn Fn(&mut SqliteDatabase, &AppTypeRegistry) -> Result<usize, SqliteDbError> + 'a>;
+
+/// A single write of `SqliteDatabase::persist_all`: a value and the table it is written to.
+/// The values of different writes can have different types, e.g. the components of an entity
+/// stored in several tables.
+pub struct TableWrite<'a> {
+    table: &'a str,
+    write: WriteFn<'a>,
+}
+
+impl<'a> TableWrite<'a> {
+    /// Insert the value as a new row (see `SqliteDatabase::insert`).
+    pub fn insert<T: Reflect + Default + TypePath + Struct>(
+        def: &'a TableDefinition,
+        value: &'a T,
+    ) -> Self {
+        TableWrite {
+            table: &def.sql_name,
+            write: Box::new(move |database, registry| database.insert(def, value, registry)),
+        }
+    }
+
+    /// Insert the value including its key or update the row with this key
+    /// (see `SqliteDatabase::bulk_upsert`).
+    pub fn upsert<T: Reflect + TypePath + Struct>(def: &'a TableDefinition, value: &'a T) -> Self {
+        TableWrite {
+            table: &def.sql_name,
+            write: Box::new(move |database, registry| {
+                database.bulk_upsert(def, std::slice::from_ref(value), registry)
+            }),
+        }
+    }
+
+    /// The name of the table written to.
+    pub fn get_table(&self) -> &str {
+        self.table
+    }
+}
+
+impl SqliteDatabase {
+    /// Execute all writes in a single transaction. If one of them fails, none of them is
+    /// applied. Returns the number of changed rows. Inside of a transaction, the writes are
+    /// nested in a savepoint.
+    pub fn persist_all(
+        &mut self,
+        writes: &[TableWrite],
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        self.savepoint("persist_all", |database| {
+            let mut count = 0;
+            for write in writes {
+                count += (write.write)(database, registry)?;
+            }
+
+            Ok(count)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TableWrite;
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, SqliteDbError};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Knight {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Horse {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        rider: i32,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_persist.sqlite");
+        registry.register_type::<Knight>(&app_registry);
+        registry.register_type::<Horse>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let knight_table = erm_registry.get_table_definition("Knight").unwrap();
+        let horse_table = erm_registry.get_table_definition("Horse").unwrap();
+        database.create_table(knight_table).unwrap();
+        database.create_table(horse_table).unwrap();
+
+        let knight = Knight {
+            id: 1,
+            name: "Lancelot".to_string(),
+        };
+        let horse = Horse {
+            name: "Shadowfax".to_string(),
+            rider: 1,
+            ..Default::default()
+        };
+        let writes = [
+            TableWrite::upsert(knight_table, &knight),
+            TableWrite::insert(horse_table, &horse),
+        ];
+        assert_eq!(writes[1].get_table(), "Horse");
+        let count = database.persist_all(&writes, &registry).unwrap();
+        assert_eq!(count, 2);
+
+        // The failing insert rolls back the update of the knight.
+        database
+            .execute("CREATE UNIQUE INDEX horse_name ON Horse (name);", &[])
+            .unwrap();
+        let renamed = Knight {
+            id: 1,
+            name: "Galahad".to_string(),
+        };
+        let writes = [
+            TableWrite::upsert(knight_table, &renamed),
+            TableWrite::insert(horse_table, &horse),
+        ];
+        let Err(e) = database.persist_all(&writes, &registry) else {
+            panic!("The second horse violates the unique index.");
+        };
+        assert!(matches!(e, SqliteDbError::Execute(_)));
+
+        let names: Vec<String> = database
+            .query_column("SELECT name FROM Knight;", &[])
+            .unwrap();
+        assert_eq!(names, vec!["Lancelot"]);
+        let count = database
+            .query_scalar::<i32>("SELECT COUNT(*) FROM Horse;", &[])
+            .unwrap();
+        assert_eq!(count, Some(1));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_persist_all() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Knight>();
+        app.register_type::<Horse>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
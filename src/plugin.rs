@@ -1,31 +1,198 @@
-use crate::prelude::{SqliteConnectionSettings, ValueWrapper};
-use bevy::{ prelude::*, reflect::{DynamicStruct, Type} };
-use bevy_erm::prelude::{BevyERMPlugin, ColumnDefinition, FromBlob, TableDefinition};
-use rusqlite::{types::FromSql, Connection, OptionalExtension, ToSql};
-use std::sync::Mutex;
+use crate::attributes::{get_field_attribute, get_integer_enum_info, get_variant_name};
+use crate::checksum::{apply_checksum, compute_checksum};
+use crate::child_table::is_stored_in_child_table;
+use crate::converters::{read_converted_columns, Converters};
+use crate::date_time::{
+    assign_date_time, is_date_time_column, read_date_text, read_date_time_text, read_time_seconds,
+};
+use crate::decimal::{assign_decimal, is_decimal_column};
+use crate::entity_names::EntityNames;
+use crate::flatten::{get_component_names, is_flattened, read_flattened_columns};
+use crate::inline::{
+    check_inline_names, get_inline_column_sql, get_inline_names, get_inline_type,
+    read_inline_columns,
+};
+use crate::normalize::normalize_read_fields;
+use crate::packed_bits::{
+    check_packed_bits, get_packed_bit, get_packed_value, is_packed_column, read_packed_columns,
+};
+use crate::prelude::{
+    BoolAsText, Checksum, Collate, FromJoinedRow, MappedRows, MappingErrorPolicy, RelateByName,
+    RowMappingError, SqliteConnectionSettings, SqliteDbError, TableSqlOptions, ValueWrapper,
+};
+use crate::range::{get_range_column_names, get_range_sql_type, read_range_columns};
+use crate::relation::{get_one_to_one_sql, read_related_key};
+use crate::shared::{assign_shared, get_shared_column};
+use crate::small_string::{assign_small_string, is_small_string_column};
+use crate::tagged_enum::{
+    get_field_tagged_enum, get_tagged_column_names, get_tagged_enum_info,
+    get_tagged_value_sql_type, read_tagged_columns,
+};
+use bevy::math::{Affine2, Affine3A, Isometry2d, Isometry3d, Mat3A, Vec3A};
+use bevy::{
+    prelude::*,
+    reflect::{DynamicEnum, DynamicStruct, DynamicVariant, ReflectMut, ReflectRef, Type, TypeInfo},
+    time::Stopwatch,
+};
+use bevy_erm::prelude::{BevyERMPlugin, ColumnDefinition, FromBlob, IntoBlob, TableDefinition};
+use rusqlite::types::{FromSql, ToSqlOutput, Value, ValueRef};
+use rusqlite::{Connection, OptionalExtension, Row, ToSql};
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The number of keys read with a single query by `find_many_by_keys`.
+const KEY_BATCH_SIZE: usize = 500;
+
+/// The number of rows read between two calls of the progress callback of `query_with_progress`.
+const PROGRESS_INTERVAL: usize = 100;
 
 /// The database serves as a wrapper around the sqlite connection so we can use it as a resource.
 #[derive(Default, Resource)]
 pub struct SqliteDatabase {
     connection: Mutex<Option<Connection>>,
+    strict_inserts: bool,
+    max_rows: Option<usize>,
+    entity_names: EntityNames,
+    converters: Converters,
+    type_registry: Option<AppTypeRegistry>,
 }
 
 impl SqliteDatabase {
+    /// If set, `insert` fails for values having fields without a column in the table
+    /// definition, instead of silently dropping those fields. This catches fields added to a
+    /// struct without re-registering the type and migrating the table.
+    pub fn use_strict_inserts(&mut self, value: bool) {
+        self.strict_inserts = value;
+    }
+
+    /// Limit the number of rows a query may return. Queries returning more rows fail instead of
+    /// reading all of them into memory. `None` (the default) means unlimited.
+    pub fn set_max_rows(&mut self, max_rows: Option<usize>) {
+        self.max_rows = max_rows;
+    }
+
+    pub fn get_max_rows(&self) -> Option<usize> {
+        self.max_rows
+    }
+
+    /// Set the names used to store and resolve relations marked as `RelateByName`.
+    pub fn set_entity_names(&mut self, names: EntityNames) {
+        self.entity_names = names;
+    }
+
+    pub fn get_entity_names(&self) -> &EntityNames {
+        &self.entity_names
+    }
+
+    pub fn get_entity_names_mut(&mut self) -> &mut EntityNames {
+        &mut self.entity_names
+    }
+
+    /// Set the registry used to look up the tables of related types when creating tables, e.g.
+    /// the junction table of a many to many relation. The plugin uses the `AppTypeRegistry` of
+    /// the app.
+    pub fn set_type_registry(&mut self, registry: Option<AppTypeRegistry>) {
+        self.type_registry = registry;
+    }
+
+    pub fn get_type_registry(&self) -> Option<&AppTypeRegistry> {
+        self.type_registry.as_ref()
+    }
+
+    /// Store fields of type `T` using the given functions instead of the built-in mappings,
+    /// e.g. types of other crates. `to_sql` converts the field into the stored value and
+    /// `from_sql` converts the stored value back. Tables created afterwards declare columns of
+    /// type `T` without a type (`ANY` for strict tables). See `Converters` for details.
+    pub fn register_converter<T: Reflect>(
+        &mut self,
+        to_sql: impl Fn(&T) -> Value + Send + Sync + 'static,
+        from_sql: impl Fn(ValueRef) -> Result<T, String> + Send + Sync + 'static,
+    ) {
+        self.converters.register::<T>(to_sql, from_sql);
+    }
+
+    pub fn get_converters(&self) -> &Converters {
+        &self.converters
+    }
+
+    /// Create a database and open the file at the given path, using the default settings.
+    /// This does not need an `App`, so the database can be used as a plain library, e.g. in
+    /// tools, tests or importers.
+    pub fn open_path(path: &Path) -> Result<SqliteDatabase, SqliteDbError> {
+        let mut settings = SqliteConnectionSettings::default();
+        settings.set_data_source(&path.to_string_lossy());
+
+        let mut database = SqliteDatabase::default();
+        database.open(&settings)?;
+
+        Ok(database)
+    }
+
     /// Open the database file. The connection is stored guarded by a mutex.
-    pub fn open(&mut self, connection_string: &SqliteConnectionSettings) -> Result<(), String> {
-        if let Ok(mut c) = self.connection.lock() {
-            let Ok(con) = Connection::open(connection_string.get_data_source()) else {
-                return Err("Could not open database connection".to_owned());
-            };
+    /// If the settings contain a retry policy, attempts failing because the file is busy or
+    /// locked are repeated with an exponential backoff. Other errors are returned immediately,
+    /// the error of the last attempt is returned if all attempts fail.
+    pub fn open(
+        &mut self,
+        connection_string: &SqliteConnectionSettings,
+    ) -> Result<(), SqliteDbError> {
+        let attempts = connection_string
+            .get_retry_policy()
+            .map(|p| p.get_attempts())
+            .unwrap_or(1);
+
+        let mut last_error = None;
+        for attempt in 0..attempts {
+            match Self::try_open(connection_string) {
+                Ok(con) => {
+                    let Ok(mut c) = self.connection.lock() else {
+                        return Err(SqliteDbError::MutexPoisoned);
+                    };
+                    *c = Some(con);
 
-            *c = Some(con);
+                    return Ok(());
+                }
+                Err(e) if is_busy(&e) => last_error = Some(e),
+                Err(e) => return Err(SqliteDbError::Open(e)),
+            }
+
+            if let Some(policy) = connection_string.get_retry_policy() {
+                if attempt + 1 < attempts {
+                    std::thread::sleep(policy.backoff(attempt));
+                }
+            }
         }
 
-        Ok(())
+        Err(last_error.map_or(SqliteDbError::NotOpen, SqliteDbError::Open))
+    }
+
+    /// Open a connection and make sure the file can actually be read. Sqlite opens files lazily,
+    /// so a locked file would otherwise only be noticed by the first statement.
+    fn try_open(
+        connection_string: &SqliteConnectionSettings,
+    ) -> Result<Connection, rusqlite::Error> {
+        let con = if connection_string.is_in_memory() {
+            Connection::open_in_memory()?
+        } else {
+            Connection::open(connection_string.get_data_source())?
+        };
+        con.query_row("PRAGMA schema_version;", [], |_| Ok(()))?;
+
+        Ok(con)
     }
 
     /// Close the database connection. This will set the connection to None.
-    pub fn close(&mut self) -> Result<(), String> {
+    /// In-memory databases are destroyed with their connection.
+    pub fn close(&mut self) -> Result<(), SqliteDbError> {
         match self.connection.lock() {
             Ok(mut c) => {
                 let Some(con) = c.take() else {
@@ -34,27 +201,105 @@ impl SqliteDatabase {
 
                 match con.close() {
                     Ok(_) => Ok(()),
-                    Err(_) => Err("Could not close database connection.".to_string()),
+                    Err(_) => Err("Could not close database connection.".into()),
                 }
             }
-            Err(_) => todo!(),
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
         }
     }
 
     /// Execute a query against the database. Returns the number of updated rows.
-    pub fn execute(&mut self, query: &str, parameter: &[&dyn ToSql]) -> Result<usize, String> {
+    pub fn execute(
+        &mut self,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<usize, SqliteDbError> {
         match self.connection.lock() {
             Ok(c) => match c.as_ref() {
                 Some(connection) => {
-                    let mut r = connection.prepare(query).unwrap();
+                    let mut r = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
                     match r.execute(parameter) {
                         Ok(s) => Ok(s),
-                        Err(e) => Err(format!("{}", e)),
+                        Err(e) => Err(SqliteDbError::from_execute(e)),
                     }
                 }
-                None => todo!(),
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Execute several statements separated by semicolons, e.g. a script. Unlike execute, this
+    /// takes no parameters.
+    pub fn execute_batch(&mut self, sql: &str) -> Result<(), SqliteDbError> {
+        match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => connection
+                    .execute_batch(sql)
+                    .map_err(SqliteDbError::from_execute),
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Run the closure inside a savepoint. If the closure returns an error, all changes made since
+    /// the savepoint was created are rolled back, but a surrounding transaction stays alive.
+    /// This allows to isolate failures of single records within a larger import.
+    pub fn savepoint<F, R>(&mut self, name: &str, f: F) -> Result<R, SqliteDbError>
+    where
+        F: FnOnce(&mut SqliteDatabase) -> Result<R, SqliteDbError>,
+    {
+        let name = format!("\"{}\"", name.replace('"', "\"\""));
+        self.execute(&format!("SAVEPOINT {name};"), &[])?;
+
+        match f(self) {
+            Ok(r) => {
+                self.execute(&format!("RELEASE {name};"), &[])?;
+                Ok(r)
+            }
+            Err(e) => {
+                self.execute(&format!("ROLLBACK TO {name};"), &[])?;
+                self.execute(&format!("RELEASE {name};"), &[])?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Run `f` with a time budget. If it takes longer than `timeout`, the running statement is
+    /// interrupted from a timer thread and `SqliteDbError::Timeout` is returned. In contrast to
+    /// the busy timeout, this bounds the total execution time and not just the wait for locks.
+    pub fn with_timeout<F, R>(&mut self, timeout: Duration, f: F) -> Result<R, SqliteDbError>
+    where
+        F: FnOnce(&mut SqliteDatabase) -> Result<R, SqliteDbError>,
+    {
+        let handle = match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => connection.get_interrupt_handle(),
+                None => return Err(SqliteDbError::NotOpen),
             },
-            Err(e) => Err(format!("{}", e)),
+            Err(_) => return Err(SqliteDbError::MutexPoisoned),
+        };
+
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let (done, finished) = mpsc::channel::<()>();
+        let timer = {
+            let timed_out = timed_out.clone();
+            std::thread::spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    handle.interrupt();
+                }
+            })
+        };
+
+        let result = f(self);
+        let _ = done.send(());
+        let _ = timer.join();
+
+        match result {
+            Err(_) if timed_out.load(Ordering::SeqCst) => Err(SqliteDbError::Timeout(timeout)),
+            result => result,
         }
     }
 
@@ -63,423 +308,7619 @@ impl SqliteDatabase {
         &mut self,
         query: &str,
         parameter: &[&dyn ToSql],
-    ) -> Result<Option<T>, rusqlite::Error> {
+    ) -> Result<Option<T>, SqliteDbError> {
         match self.connection.lock() {
             Ok(c) => match c.as_ref() {
                 Some(connection) => match connection.prepare(query) {
                     Ok(mut stmt) => stmt
                         .query_row(parameter, |x| x.get::<usize, T>(0))
-                        .optional(),
-                    Err(e) => Err(e),
+                        .optional()
+                        .map_err(SqliteDbError::from_execute),
+                    Err(e) => Err(SqliteDbError::Prepare(e)),
                 },
-                None => todo!(),
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Retrieve the first column of all rows returned by the query.
+    pub fn query_column<T: FromSql>(
+        &mut self,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<T>, SqliteDbError> {
+        match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => {
+                    let mut stmt = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
+                    let rows = stmt
+                        .query_map(parameter, |x| x.get::<usize, T>(0))
+                        .map_err(SqliteDbError::from_execute)?;
+
+                    rows.collect::<Result<Vec<T>, rusqlite::Error>>()
+                        .map_err(SqliteDbError::from_execute)
+                }
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Read the first two columns of all rows returned by the query.
+    pub(crate) fn query_column_pairs<A: FromSql, B: FromSql>(
+        &mut self,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<(A, B)>, SqliteDbError> {
+        self.query_mapped(query, parameter, |x| {
+            Ok((x.get::<usize, A>(0)?, x.get::<usize, B>(1)?))
+        })
+    }
+
+    /// Map every row returned by the query using the given function.
+    pub(crate) fn query_mapped<R>(
+        &mut self,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        f: impl FnMut(&Row) -> rusqlite::Result<R>,
+    ) -> Result<Vec<R>, SqliteDbError> {
+        match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => {
+                    let mut stmt = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
+                    let rows = stmt
+                        .query_map(parameter, f)
+                        .map_err(SqliteDbError::from_execute)?;
+
+                    rows.collect::<Result<Vec<R>, rusqlite::Error>>()
+                        .map_err(SqliteDbError::from_execute)
+                }
+                None => Err(SqliteDbError::NotOpen),
             },
-            Err(_) => todo!(),
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
         }
     }
 
+    /// Read a single value of the given column from the first row matching the where clause.
+    /// The column has to be part of the table definition. Returns `None`, if no row matches.
+    pub fn query_scalar_where<T: FromSql>(
+        &mut self,
+        def: &TableDefinition,
+        column: &str,
+        where_clause: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Option<T>, SqliteDbError> {
+        let Some(col) = def.get(column) else {
+            return Err(SqliteDbError::UnknownColumn {
+                table: def.sql_name.clone(),
+                column: column.to_string(),
+            });
+        };
+
+        let filter = where_sql(where_clause);
+        let query = format!(
+            "SELECT {} FROM {}{} LIMIT 1;",
+            col.sql_name, def.sql_name, filter
+        );
+
+        let values: Vec<T> = self.query_column(&query, parameter)?;
+        Ok(values.into_iter().next())
+    }
+
+    /// Count the rows of the table matching the where clause, or all rows without one.
+    pub fn count(
+        &mut self,
+        def: &TableDefinition,
+        where_clause: Option<&str>,
+        parameter: &[&dyn ToSql],
+    ) -> Result<i64, SqliteDbError> {
+        let filter = where_sql(where_clause.unwrap_or_default());
+        let query = format!("SELECT COUNT(*) FROM {}{};", def.sql_name, filter);
+
+        Ok(self.query_scalar::<i64>(&query, parameter)?.unwrap_or(0))
+    }
+
+    /// Read all rows returned by the query into values of type `T`. Result columns without a
+    /// column definition, e.g. computed columns like `first || ' ' || last AS full_name`, are
+    /// read into the struct field with the same name, using the type of that field. This
+    /// includes window functions, e.g. `ROW_NUMBER() OVER (ORDER BY deaths DESC) AS rank`.
     pub fn query<T: Default + Reflect>(
         &mut self,
         table_def: &TableDefinition,
         query: &str,
         parameter: &[&dyn ToSql],
-    ) -> Result<Vec<T>, String> {
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let mut result: Vec<T> = Vec::new();
+        self.query_into(table_def, query, parameter, &mut result)?;
+
+        Ok(result)
+    }
+
+    /// Same as query, but keeps only the first row of every key. Joining a one to many relation
+    /// returns the parent row once per child, use this to read every parent only once.
+    pub fn query_dedup_by_key<T: Default + Reflect + bevy::prelude::Struct>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let Some(key) = table_def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(table_def.sql_name.clone()));
+        };
+
+        let rows: Vec<T> = self.query(table_def, query, parameter)?;
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut result: Vec<T> = Vec::new();
+        for row in rows {
+            let Some(value) = row.field(&key.rust_name) else {
+                return Err(SqliteDbError::NoKeyColumn(table_def.sql_name.clone()));
+            };
+            let Some(hash) = value.reflect_hash() else {
+                return Err(format!("The key {} cannot be hashed.", key.rust_name).into());
+            };
+
+            let same_hash = seen.entry(hash).or_default();
+            let duplicate = same_hash.iter().any(|x| {
+                result[*x]
+                    .field(&key.rust_name)
+                    .and_then(|other| other.reflect_partial_eq(value))
+                    .unwrap_or(false)
+            });
+            if !duplicate {
+                same_hash.push(result.len());
+                result.push(row);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Same as query, but groups the values by the value of the given result column, e.g. to
+    /// read the children of several parents at once grouped by their parent key. The column
+    /// has to be part of the result, but does not need to be a field of the type. Values keep
+    /// the order of the query within their group.
+    pub fn query_grouped<K: FromSql + Eq + Hash, T: Default + Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        group_column: &str,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<HashMap<K, Vec<T>>, SqliteDbError> {
+        let entity_names = &self.entity_names;
+        let converters = &self.converters;
         match self.connection.lock() {
             Ok(c) => match c.as_ref() {
                 Some(connection) => {
-                    let Ok(mut r) = connection.prepare(query) else {
-                        return Err("Could not compile query!".to_string());
-                    };
-
+                    let mut stmt = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
                     let names: Vec<String> =
-                        r.column_names().iter().map(|x| x.to_string()).collect();
+                        stmt.column_names().iter().map(|x| x.to_string()).collect();
+                    let Some(group) = names.iter().position(|x| x == group_column) else {
+                        return Err(format!(
+                            "The query does not return the column {group_column}."
+                        )
+                        .into());
+                    };
 
-                    let result : Vec<T> = r.query_map(parameter, |row| {
-                        // let mut value = table_def.reflect_default.default();
-                        let mut value = T::default();
-                        let mut dyn_type = DynamicStruct::default();
-
-                        for (x, name) in names.iter().enumerate().clone() {
-                            // let name = names[x].clone();
-                            match table_def.get(name) {
-                                Some(col) => match col.sql_type {
-                                    bevy_erm::prelude::SqlType::None => panic!("Illegal SQL Type"),
-                                    bevy_erm::prelude::SqlType::Integer(bits, not_null) => {
-                                        match bits {
-                                            8 => {
-                                                let v = row.get_unwrap::<usize, i8>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            16 => {
-                                                let v = row.get_unwrap::<usize, i16>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            32 => {
-                                                let v = row.get_unwrap::<usize, i32>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            64 => {
-                                                let v = row.get_unwrap::<usize, i64>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            _ => {
-                                                panic!("Max bit size for integers is 64!")
-                                            }
-                                        }
-                                    }
-                                    bevy_erm::prelude::SqlType::UnsingedInteger(bits, not_null) => {
-                                        match bits {
-                                            8 => {
-                                                let v = row.get_unwrap::<usize, u8>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            16 => {
-                                                let v = row.get_unwrap::<usize, u16>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            32 => {
-                                                let v = row.get_unwrap::<usize, u32>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            64 => {
-                                                let v = row.get_unwrap::<usize, u64>(x);
-                                                if not_null {
-                                                    dyn_type.insert(name, v);
-                                                } else {
-                                                    dyn_type.insert(name, Some(v));
-                                                }
-                                            }
-                                            _ => {
-                                                panic!("Max bit size for integers is 64!")
-                                            }
-                                        }
-                                    }
-                                    bevy_erm::prelude::SqlType::Float(bits, not_null) => {
-                                        if bits == 32 {
-                                            let v = row.get_unwrap::<usize, f32>(x);
-                                            if not_null {
-                                                dyn_type.insert(name, v);
-                                            } else {
-                                                dyn_type.insert(name, Some(v));
-                                            }
-                                        } else if bits == 64 {
-                                            let v = row.get_unwrap::<usize, f64>(x);
-                                            if not_null {
-                                                dyn_type.insert(name, v);
-                                            } else {
-                                                dyn_type.insert(name, Some(v));
-                                            }
-                                        } else {
-                                            panic!("Floats must have 32 or 64 bits!")
-                                        }
-                                    }
-                                    bevy_erm::prelude::SqlType::Text(not_null) => {
-                                        let v = row.get_unwrap::<usize, String>(x);
-                                        if not_null {
-                                            dyn_type.insert(name, v);
-                                        } else {
-                                            dyn_type.insert(name, Some(v));
-                                        }
-                                    }
-                                    bevy_erm::prelude::SqlType::Date(_) => todo!(),
-                                    bevy_erm::prelude::SqlType::Time(_) => todo!(),
-                                    bevy_erm::prelude::SqlType::DateTime(_) => todo!(),
-                                    bevy_erm::prelude::SqlType::Blob(not_null) => {
-                                        let v = row.get_unwrap::<usize, Vec<u8>>(x);
-                                        // Vec2
-                                        if col.ty.is::<Vec2>() && not_null {
-                                            dyn_type.insert(name, Vec2::from_blob(&v));
-                                        } else if col.ty.is::<Vec2>() && !not_null {
-                                            dyn_type.insert(name, Some(Vec2::from_blob(&v)));
-                                        }
-                                        // Vec3
-                                        else if col.ty.is::<Vec3>() && not_null {
-                                            dyn_type.insert(name, Vec3::from_blob(&v));
-                                        } else if col.ty.is::<Vec3>() && !not_null {
-                                            dyn_type.insert(name, Some(Vec3::from_blob(&v)));
-                                        }
-                                        // Vec4
-                                        else if col.ty.is::<Vec4>() && not_null {
-                                            dyn_type.insert(name, Vec4::from_blob(&v));
-                                        } else if col.ty.is::<Vec4>() && !not_null {
-                                            dyn_type.insert(name, Some(Vec4::from_blob(&v)));
-                                        }
-                                    }
-                                    bevy_erm::prelude::SqlType::Boolean(not_null) => {
-                                        let v = row.get_unwrap::<usize, bool>(x);
-                                        if not_null {
-                                            dyn_type.insert(name, v);
-                                        } else {
-                                            dyn_type.insert(name, Some(v));
-                                        }
-                                    }
-                                    bevy_erm::prelude::SqlType::One2One(_type_id, _) => todo!(),
-                                    bevy_erm::prelude::SqlType::Many2Many(_type_id, _) => todo!(),
-                                },
-                                None => {
-                                    info!("Could not map column {}.", name);
-                                }
-                            }
-                        }
+                    let rows = stmt
+                        .query_map(parameter, |row| {
+                            let value: T =
+                                Self::read_value(table_def, &names, row, entity_names, converters)?;
+                            Ok((row.get::<usize, K>(group)?, value))
+                        })
+                        .map_err(SqliteDbError::from_execute)?;
 
-                        value.apply(dyn_type.as_partial_reflect());
+                    let mut result: HashMap<K, Vec<T>> = HashMap::new();
+                    for (index, row) in rows.enumerate() {
+                        check_max_rows(self.max_rows, index)?;
 
-                        Ok(value)
-                    }).unwrap().map(|x| x.unwrap()).collect();
+                        let (key, value) = row.map_err(SqliteDbError::from_execute)?;
+                        result.entry(key).or_default().push(value);
+                    }
 
                     Ok(result)
                 }
-                None => todo!(),
+                None => Err(SqliteDbError::NotOpen),
             },
-            Err(e) => Err(format!("{}", e)),
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
         }
     }
 
-    /// Returns true, if there is a table with the given name.
-    pub fn table_exists(&mut self, table_name: &str) -> bool {
-        let query = format!("SELECT Count(*) as Tables FROM sqlite_master WHERE type='table' AND name='{table_name}';");
-        match self.query_scalar::<i32>(&query, &[]) {
-            Ok(r) => r.unwrap() > 0,
-            Err(e) => {
-                panic!("{}", e);
-            }
+    /// Read the single row returned by the query, e.g. a lookup by key. Returns `None` if the
+    /// query returns no row and an error if it returns more than one.
+    pub fn query_one<T: Default + Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Option<T>, SqliteDbError> {
+        let mut result: Vec<T> = self.query(table_def, query, parameter)?;
+        match result.len() {
+            0 | 1 => Ok(result.pop()),
+            n => Err(SqliteDbError::TooManyRows(n)),
         }
     }
 
-    // Get all columns of a table.
-    // PRAGMA table_info('Player');
+    /// Same as query, but clears and refills the given vector instead of allocating a new one.
+    /// Systems running the same query every frame can keep the vector around to reuse its
+    /// allocation.
+    pub fn query_into<T: Default + Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        out: &mut Vec<T>,
+    ) -> Result<(), SqliteDbError> {
+        out.clear();
+
+        self.query_rows(
+            table_def,
+            query,
+            parameter,
+            MappingErrorPolicy::FailFast,
+            T::default,
+            |x| out.push(x),
+        )?;
+
+        Ok(())
+    }
+
+    /// Same as query, but every value starts as the value returned by `make` instead of
+    /// `T::default()`. Use this for types without a meaningful default, whose columns fully
+    /// determine the value. Fields without a column keep the value set by `make`.
+    pub fn query_with<T: Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        make: impl Fn() -> T,
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let mut result: Vec<T> = Vec::new();
+        self.query_rows(
+            table_def,
+            query,
+            parameter,
+            MappingErrorPolicy::FailFast,
+            make,
+            |x| result.push(x),
+        )?;
 
-    pub fn get_table_sql(table: &TableDefinition) -> Result<String, String> {
-        let mut columns: Vec<String> = Vec::new();
-        let mut sorted : Vec<&ColumnDefinition> = table.fields.values().collect();
-        sorted.sort_by(|a, b| a.order.cmp(&b.order));
-        for def in sorted {
-            let name = def.sql_name.clone();
-            let mut column = name.clone();
-            match def.sql_type {
-                bevy_erm::prelude::SqlType::None => todo!(),
-                bevy_erm::prelude::SqlType::Integer(_, not_null) => {
-                    if def.is_key() {
-                        column.push_str(" INTEGER PRIMARY KEY AUTOINCREMENT");
-                    } else {
-                        column.push_str(" INTEGER");
-                        if not_null {
-                            column.push_str(" NOT NULL");
-                        }
-                    }
-                }
-                bevy_erm::prelude::SqlType::UnsingedInteger(_, not_null) => {
-                    column.push_str(" INTEGER");
-                    if not_null {
-                        column.push_str(" NOT NULL");
-                    }
-                    column.push_str(&format!(" CHECK({column} >= 0)"));
-                }
-                bevy_erm::prelude::SqlType::Float(_, not_null) => {
-                    column.push_str(" REAL");
-                    if not_null {
-                        column.push_str(" NOT NULL");
-                    }
+        Ok(result)
+    }
+
+    /// Same as query, but every value is passed to the given function as soon as its row is
+    /// read, instead of collecting all values first. Large results can be processed without
+    /// holding all of them in memory.
+    pub fn query_each<T: Default + Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        f: impl FnMut(T),
+    ) -> Result<(), SqliteDbError> {
+        self.query_rows(
+            table_def,
+            query,
+            parameter,
+            MappingErrorPolicy::FailFast,
+            T::default,
+            f,
+        )?;
+
+        Ok(())
+    }
+
+    /// Same as query, but reports the progress to the given callback, e.g. to show a progress
+    /// bar while loading a big save. The callback receives the number of rows read so far and
+    /// the total number of rows, which is counted before reading. It is called once before the
+    /// first row, every `PROGRESS_INTERVAL` rows and once after the last row.
+    pub fn query_with_progress<T: Default + Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let query = query.trim().trim_end_matches(';');
+        let count = format!("SELECT COUNT(*) FROM ({query});");
+
+        self.savepoint("query_with_progress", |database| {
+            let total = database.query_scalar::<i64>(&count, parameter)?;
+            let total = total.unwrap_or(0) as usize;
+            progress(0, total);
+
+            let mut result: Vec<T> = Vec::new();
+            database.query_each(table_def, query, parameter, |value| {
+                result.push(value);
+                if result.len().is_multiple_of(PROGRESS_INTERVAL) {
+                    progress(result.len(), total);
                 }
-                bevy_erm::prelude::SqlType::Text(not_null) => {
-                    if def.has_max_length() {
-                        column.push_str(&format!(" VARCHAR({})", def.get_max_length()));
-                    } else {
-                        column.push_str(" TEXT");
-                    }
-                    if not_null {
-                        column.push_str(" NOT NULL");
+            })?;
+            if !result.len().is_multiple_of(PROGRESS_INTERVAL) {
+                progress(result.len(), total);
+            }
+
+            Ok(result)
+        })
+    }
+
+    /// Map the columns of the result to the fields of `T` by position instead of by name: the
+    /// first column is read into the first field, the second into the second and so on.
+    /// Columns are converted to the type of their field. Fields without a column keep their
+    /// default value. Useful for queries whose column names differ from the field names.
+    pub fn query_positional<T: Default + Reflect + bevy::prelude::Struct>(
+        &mut self,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<T>, SqliteDbError> {
+        match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => {
+                    let mut r = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
+
+                    let template = T::default();
+                    if r.column_count() > template.field_len() {
+                        return Err(format!(
+                            "The query returns {} columns, but {} has only {} fields.",
+                            r.column_count(),
+                            template.reflect_type_path(),
+                            template.field_len()
+                        )
+                        .into());
                     }
-                }
-                bevy_erm::prelude::SqlType::Date(not_null) => {
-                    column.push_str(" TEXT");
-                    if not_null {
-                        column.push_str(" NOT NULL");
+
+                    let type_info = template.get_represented_type_info();
+                    let names: Vec<String> = (0..r.column_count())
+                        .filter_map(|x| template.name_at(x).map(|n| n.to_string()))
+                        .collect();
+
+                    let rows = r
+                        .query_map(parameter, |row| {
+                            let mut dyn_type = DynamicStruct::default();
+                            for (x, name) in names.iter().enumerate() {
+                                let read = Self::read_unmapped_column(
+                                    type_info,
+                                    row,
+                                    x,
+                                    name,
+                                    &mut dyn_type,
+                                )?;
+                                if !read {
+                                    return Err(rusqlite::Error::InvalidColumnName(format!(
+                                        "Column {x} cannot be read into field {name}."
+                                    )));
+                                }
+                            }
+
+                            let mut value = T::default();
+                            value.apply(dyn_type.as_partial_reflect());
+                            Ok(value)
+                        })
+                        .map_err(SqliteDbError::from_execute)?;
+
+                    let mut result: Vec<T> = Vec::new();
+                    for (index, value) in rows.enumerate() {
+                        match value {
+                            Ok(v) => result.push(v),
+                            Err(e) => return Err(SqliteDbError::MapRow(index, e)),
+                        }
                     }
+
+                    Ok(result)
                 }
-                bevy_erm::prelude::SqlType::Time(not_null) => {
-                    column.push_str(" REAL");
-                    if not_null {
-                        column.push_str(" NOT NULL");
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Same as query, but rows which cannot be mapped are handled according to the policy.
+    /// With `MappingErrorPolicy::SkipAndCollect` the successfully mapped rows are returned
+    /// together with the errors of all skipped rows, so a partially corrupt save can still be
+    /// loaded.
+    pub fn query_with_policy<T: Default + Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        policy: MappingErrorPolicy,
+    ) -> Result<MappedRows<T>, SqliteDbError> {
+        let mut rows: Vec<T> = Vec::new();
+        let errors = self.query_rows(table_def, query, parameter, policy, T::default, |x| {
+            rows.push(x)
+        })?;
+
+        Ok(MappedRows { rows, errors })
+    }
+
+    fn query_rows<T: Reflect>(
+        &mut self,
+        table_def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        policy: MappingErrorPolicy,
+        make: impl Fn() -> T,
+        mut out: impl FnMut(T),
+    ) -> Result<Vec<RowMappingError>, SqliteDbError> {
+        let mut errors: Vec<RowMappingError> = Vec::new();
+        let entity_names = &self.entity_names;
+        let converters = &self.converters;
+        match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => {
+                    let mut r = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
+
+                    let names: Vec<String> =
+                        r.column_names().iter().map(|x| x.to_string()).collect();
+
+                    let rows = r
+                        .query_map(parameter, |row| {
+                            Self::read_value_with(
+                                make(),
+                                table_def,
+                                &names,
+                                row,
+                                entity_names,
+                                converters,
+                            )
+                        })
+                        .map_err(SqliteDbError::from_execute)?;
+
+                    for (index, value) in rows.enumerate() {
+                        check_max_rows(self.max_rows, index)?;
+
+                        match value {
+                            Ok(v) => out(v),
+                            Err(e) => match policy {
+                                MappingErrorPolicy::FailFast => {
+                                    return Err(SqliteDbError::MapRow(index, e));
+                                }
+                                MappingErrorPolicy::SkipAndCollect => {
+                                    errors.push(RowMappingError {
+                                        row: index,
+                                        message: e.to_string(),
+                                    });
+                                }
+                            },
+                        }
                     }
+
+                    Ok(errors)
                 }
-                bevy_erm::prelude::SqlType::DateTime(not_null) => {
-                    column.push_str(" TEXT");
-                    if not_null {
-                        column.push_str(" NOT NULL");
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Query a joined result and map every row into a tuple of values, one per joined table,
+    /// e.g. a bundle of components which can be spawned directly. Every table is given with
+    /// the alias its columns are prefixed with (see `FromJoinedRow`).
+    pub fn query_bundle<B: FromJoinedRow>(
+        &mut self,
+        tables: &[(&str, &TableDefinition)],
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<B>, SqliteDbError> {
+        if tables.len() != B::COUNT {
+            return Err(format!(
+                "Expected {} tables to map the joined rows, but got {}.",
+                B::COUNT,
+                tables.len()
+            )
+            .into());
+        }
+
+        let entity_names = &self.entity_names;
+        let converters = &self.converters;
+        match self.connection.lock() {
+            Ok(c) => match c.as_ref() {
+                Some(connection) => {
+                    let mut r = connection.prepare(query).map_err(SqliteDbError::Prepare)?;
+
+                    let names: Vec<String> =
+                        r.column_names().iter().map(|x| x.to_string()).collect();
+
+                    let rows = match r.query_map(parameter, |row| {
+                        B::from_joined_row(tables, &names, row, entity_names, converters)
+                    }) {
+                        Ok(rows) => rows,
+                        Err(e) => return Err(SqliteDbError::from_execute(e)),
+                    };
+
+                    let mut out: Vec<B> = Vec::new();
+                    for (index, value) in rows.enumerate() {
+                        check_max_rows(self.max_rows, index)?;
+
+                        match value {
+                            Ok(v) => out.push(v),
+                            Err(e) => return Err(SqliteDbError::MapRow(index, e)),
+                        }
                     }
+
+                    Ok(out)
                 }
-                bevy_erm::prelude::SqlType::Blob(not_null) => {
-                    column.push_str(" BLOB");
-                    if not_null {
-                        column.push_str(" NOT NULL");
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Read a result row into a new value of type `T`.
+    pub(crate) fn read_value<T: Default + Reflect>(
+        table_def: &TableDefinition,
+        names: &[String],
+        row: &Row,
+        entity_names: &EntityNames,
+        converters: &Converters,
+    ) -> rusqlite::Result<T> {
+        Self::read_value_with(
+            T::default(),
+            table_def,
+            names,
+            row,
+            entity_names,
+            converters,
+        )
+    }
+
+    /// Read a result row into the given value. Fields without a column keep their value.
+    fn read_value_with<T: Reflect>(
+        mut value: T,
+        table_def: &TableDefinition,
+        names: &[String],
+        row: &Row,
+        entity_names: &EntityNames,
+        converters: &Converters,
+    ) -> rusqlite::Result<T> {
+        let type_info = value.get_represented_type_info();
+        let dyn_type = Self::read_row(table_def, type_info, names, row, converters)?;
+        value.apply(dyn_type.as_partial_reflect());
+        read_converted_columns(table_def, names, row, converters, &mut value)?;
+        Self::read_assigned_columns(table_def, names, row, entity_names, converters, &mut value)?;
+        read_flattened_columns(table_def, names, row, &mut value)?;
+        read_range_columns(table_def, names, row, &mut value)?;
+        read_tagged_columns(table_def, names, row, &mut value)?;
+        read_inline_columns(table_def, names, row, &mut value)?;
+        read_packed_columns(names, row, &mut value)?;
+        normalize_read_fields(&mut value);
+
+        Ok(value)
+    }
+
+    /// Read all columns of a result row into a dynamic struct. Columns are mapped by name
+    /// using the table definition. Columns that are not part of the table definition
+    /// (e.g. computed columns or values extracted from JSON) are mapped using the type of
+    /// the struct field with the same name, if there is one. Columns with a custom converter
+    /// are skipped.
+    fn read_row(
+        table_def: &TableDefinition,
+        type_info: Option<&TypeInfo>,
+        names: &[String],
+        row: &Row,
+        converters: &Converters,
+    ) -> rusqlite::Result<DynamicStruct> {
+        let mut dyn_type = DynamicStruct::default();
+
+        for (x, name) in names.iter().enumerate().clone() {
+            // let name = names[x].clone();
+            // Columns of the other tables of a joined row are masked with an empty name.
+            if name.is_empty() {
+                continue;
+            }
+
+            match table_def.get(name) {
+                Some(col) if converters.contains(col.ty.id()) => {}
+                Some(col) if is_assigned_column(col) => {}
+                Some(col) if get_field_tagged_enum(type_info, col).is_some() => {}
+                Some(col) => match col.sql_type {
+                    bevy_erm::prelude::SqlType::None => {
+                        if !Self::read_integer_enum(type_info, row, x, name, &mut dyn_type)? {
+                            return Err(invalid_column_type(row, x, name));
+                        }
+                    }
+                    bevy_erm::prelude::SqlType::Integer(bits, not_null) => match bits {
+                        8 => {
+                            let v = row.get::<usize, Option<i8>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        16 => {
+                            let v = row.get::<usize, Option<i16>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        32 => {
+                            let v = row.get::<usize, Option<i32>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        64 => {
+                            let v = row.get::<usize, Option<i64>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        _ => return Err(invalid_column_type(row, x, name)),
+                    },
+                    bevy_erm::prelude::SqlType::UnsingedInteger(bits, not_null) => match bits {
+                        8 => {
+                            let v = row.get::<usize, Option<u8>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        16 => {
+                            let v = row.get::<usize, Option<u16>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        32 => {
+                            let v = row.get::<usize, Option<u32>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        64 => {
+                            let v = row.get::<usize, Option<u64>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                        _ => return Err(invalid_column_type(row, x, name)),
+                    },
+                    bevy_erm::prelude::SqlType::Float(bits, not_null) => {
+                        if bits == 32 {
+                            let v = row.get::<usize, Option<f32>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        } else if bits == 64 {
+                            let v = row.get::<usize, Option<f64>>(x)?;
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        } else {
+                            return Err(invalid_column_type(row, x, name));
+                        }
+                    }
+                    bevy_erm::prelude::SqlType::Text(not_null) => {
+                        let v = row.get::<usize, Option<String>>(x)?;
+                        insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                    }
+                    bevy_erm::prelude::SqlType::Date(not_null) => {
+                        let v = read_date_text(row, x)?;
+                        insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                    }
+                    bevy_erm::prelude::SqlType::Time(not_null) => {
+                        let v = read_time_seconds(row, x)?;
+                        if col.ty.is::<f32>() {
+                            let v = v.map(|v| v as f32);
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        } else {
+                            insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                        }
+                    }
+                    bevy_erm::prelude::SqlType::DateTime(not_null) => {
+                        let v = read_date_time_text(row, x)?;
+                        insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                    }
+                    bevy_erm::prelude::SqlType::Blob(not_null) => {
+                        let v = row.get::<usize, Option<Vec<u8>>>(x)?;
+                        let v = v.as_deref();
+                        let not_null = not_null && !is_optional_field(type_info, name);
+
+                        macro_rules! read_blob {
+                            ($($t:ty),*) => {
+                                $(
+                                    if col.ty.is::<$t>() || col.ty.is::<Option<$t>>() {
+                                        let v = v.map(<$t>::from_blob);
+                                        insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                                    }
+                                )*
+                            };
+                        }
+
+                        read_blob!(
+                            Vec2, Vec3, Vec4, UVec2, UVec3, UVec4, IVec2, IVec3, IVec4, Quat, Srgba
+                        );
                     }
+                    bevy_erm::prelude::SqlType::Boolean(not_null) => {
+                        let as_text = type_info
+                            .and_then(|t| get_field_attribute::<BoolAsText>(t, &col.rust_name))
+                            .is_some();
+                        let v = match row.get_ref(x)? {
+                            rusqlite::types::ValueRef::Null => None,
+                            _ if as_text => Some(parse_bool_text(row, x)?),
+                            _ => Some(row.get::<usize, bool>(x)?),
+                        };
+                        insert_nullable(&mut dyn_type, name, x, v, not_null)?;
+                    }
+                    bevy_erm::prelude::SqlType::One2One(..) => {
+                        read_related_key(type_info, row, x, name, &mut dyn_type)?;
+                    }
+                    bevy_erm::prelude::SqlType::Many2Many(..) => {}
+                },
+                None if is_packed_column(type_info, name) => {}
+                None => {
+                    if !Self::read_unmapped_column(type_info, row, x, name, &mut dyn_type)? {
+                        info!("Could not map column {}.", name);
+                    }
+                }
+            }
+        }
+
+        Ok(dyn_type)
+    }
+
+    /// Read a column storing the discriminant of an `IntegerEnum`. Returns false, if the struct
+    /// field with the same name is not an `IntegerEnum`.
+    fn read_integer_enum(
+        type_info: Option<&TypeInfo>,
+        row: &Row,
+        x: usize,
+        name: &str,
+        dyn_type: &mut DynamicStruct,
+    ) -> rusqlite::Result<bool> {
+        let Some(TypeInfo::Struct(info)) = type_info else {
+            return Ok(false);
+        };
+
+        let Some(enum_type) = info.field(name).and_then(|f| f.type_info()) else {
+            return Ok(false);
+        };
+
+        let Some(enum_info) = get_integer_enum_info(enum_type) else {
+            return Ok(false);
+        };
+
+        let discriminant = row.get::<usize, i64>(x)?;
+        let Some(variant) = get_variant_name(enum_info, discriminant) else {
+            return Err(rusqlite::Error::IntegralValueOutOfRange(x, discriminant));
+        };
+
+        let mut value = DynamicEnum::new(variant, DynamicVariant::Unit);
+        value.set_represented_type(Some(enum_type));
+        dyn_type.insert(name, value);
+
+        Ok(true)
+    }
+
+    /// Read a column without a column definition, using the type of the struct field with the
+    /// same name. NULL keeps the value of a field which is not an `Option`, e.g. aggregates
+    /// over no rows. Returns false, if there is no such field or its type is not supported.
+    pub(crate) fn read_unmapped_column(
+        type_info: Option<&TypeInfo>,
+        row: &Row,
+        x: usize,
+        name: &str,
+        dyn_type: &mut DynamicStruct,
+    ) -> rusqlite::Result<bool> {
+        let Some(TypeInfo::Struct(info)) = type_info else {
+            return Ok(false);
+        };
+
+        let Some(field) = info.field(name) else {
+            return Ok(false);
+        };
+
+        let is_null = matches!(row.get_ref(x)?, ValueRef::Null);
+        macro_rules! read_as {
+            ($($t:ty),*) => {
+                $(
+                    if field.is::<$t>() {
+                        if !is_null {
+                            dyn_type.insert(name, row.get::<usize, $t>(x)?);
+                        }
+                        return Ok(true);
+                    }
+
+                    if field.is::<Option<$t>>() {
+                        dyn_type.insert(name, row.get::<usize, Option<$t>>(x)?);
+                        return Ok(true);
+                    }
+                )*
+            };
+        }
+
+        read_as!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, bool, String);
+        read_as!(Vec<u8>);
+
+        Ok(false)
+    }
+
+    /// Read columns whose values are assigned to the fields of the value directly
+    /// (see `is_assigned_column`), because their types cannot be derived from the column
+    /// definition or stored in a dynamic struct.
+    fn read_assigned_columns(
+        table_def: &TableDefinition,
+        names: &[String],
+        row: &Row,
+        entity_names: &EntityNames,
+        converters: &Converters,
+        value: &mut dyn PartialReflect,
+    ) -> rusqlite::Result<()> {
+        let type_info = value.get_represented_type_info();
+        let ReflectMut::Struct(target) = value.reflect_mut() else {
+            return Ok(());
+        };
+
+        for (x, name) in names.iter().enumerate() {
+            let Some(col) = table_def.get(name) else {
+                continue;
+            };
+
+            if !is_assigned_column(col) || converters.contains(col.ty.id()) {
+                continue;
+            }
+
+            let Some(field) = target
+                .field_mut(&col.rust_name)
+                .and_then(|f| f.try_as_reflect_mut())
+            else {
+                continue;
+            };
+
+            let field = field.as_any_mut();
+            if col.ty.is::<IpAddr>() || col.ty.is::<Option<IpAddr>>() {
+                assign_parsed::<IpAddr>(field, row, x)?;
+            } else if col.ty.is::<SocketAddr>() || col.ty.is::<Option<SocketAddr>>() {
+                assign_parsed::<SocketAddr>(field, row, x)?;
+            } else if col.ty.is::<Option<Entity>>()
+                && type_info
+                    .and_then(|t| get_field_attribute::<RelateByName>(t, &col.rust_name))
+                    .is_some()
+            {
+                assign_entity_by_name(field, row, x, entity_names)?;
+            } else if col.ty.is::<Entity>() || col.ty.is::<Option<Entity>>() {
+                assign_entity(field, row, x)?;
+            } else if col.ty.is::<Dir2>() || col.ty.is::<Option<Dir2>>() {
+                assign_from_blob(field, row, x, dir2_from_blob)?;
+            } else if col.ty.is::<Dir3>() || col.ty.is::<Option<Dir3>>() {
+                assign_from_blob(field, row, x, dir3_from_blob)?;
+            } else if col.ty.is::<Vec3A>() || col.ty.is::<Option<Vec3A>>() {
+                assign_from_blob(field, row, x, vec3a_from_blob)?;
+            } else if col.ty.is::<Isometry2d>() || col.ty.is::<Option<Isometry2d>>() {
+                assign_from_blob(field, row, x, isometry2d_from_blob)?;
+            } else if col.ty.is::<Isometry3d>() || col.ty.is::<Option<Isometry3d>>() {
+                assign_from_blob(field, row, x, isometry3d_from_blob)?;
+            } else if col.ty.is::<Mat3A>() || col.ty.is::<Option<Mat3A>>() {
+                assign_from_blob(field, row, x, mat3a_from_blob)?;
+            } else if col.ty.is::<Affine2>() || col.ty.is::<Option<Affine2>>() {
+                assign_from_blob(field, row, x, affine2_from_blob)?;
+            } else if col.ty.is::<Affine3A>() || col.ty.is::<Option<Affine3A>>() {
+                assign_from_blob(field, row, x, affine3a_from_blob)?;
+            } else if col.ty.is::<Stopwatch>() {
+                assign_stopwatch(field, row, x)?;
+            } else if col.ty.is::<Timer>() {
+                assign_timer(field, row, x)?;
+            } else if col.ty.is::<Cow<'static, str>>() {
+                assign_cow(field, row, x)?;
+            } else if col.ty.is::<char>() || col.ty.is::<Option<char>>() {
+                assign_parsed::<char>(field, row, x)?;
+            } else if col.ty.is::<u128>() || col.ty.is::<Option<u128>>() {
+                assign_parsed::<u128>(field, row, x)?;
+            } else if col.ty.is::<i128>() || col.ty.is::<Option<i128>>() {
+                assign_parsed::<i128>(field, row, x)?;
+            } else if is_date_time_column(col) {
+                assign_date_time(field, row, x)?;
+            } else if is_decimal_column(col) {
+                assign_decimal(field, row, x)?;
+            } else if is_small_string_column(col) {
+                assign_small_string(field, row, x)?;
+            } else if get_shared_column(col).is_some() {
+                assign_shared(field, row, x)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true, if there is a table with the given name.
+    pub fn table_exists(&mut self, table_name: &str) -> Result<bool, SqliteDbError> {
+        let query = "SELECT Count(*) as Tables FROM sqlite_master WHERE type='table' AND name=?1;";
+        let count = self.query_scalar::<i32>(query, &[&table_name])?;
+
+        Ok(count.unwrap_or(0) > 0)
+    }
+
+    pub fn get_table_sql(table: &TableDefinition) -> Result<String, SqliteDbError> {
+        Self::get_table_sql_with_options(table, &TableSqlOptions::default())
+    }
+
+    /// Generate the SQL to create the table, taking the given options into account.
+    pub fn get_table_sql_with_options(
+        table: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<String, SqliteDbError> {
+        check_inline_names(table, options.get_type_info())?;
+        check_packed_bits(options.get_type_info())?;
+
+        let sorted = sort_columns(table);
+        if options.is_unique_orders() {
+            if let Some([a, b]) = sorted.windows(2).find(|x| x[0].order == x[1].order) {
+                return Err(format!(
+                    "Columns {} and {} of table {} share the order {}.",
+                    a.sql_name, b.sql_name, table.sql_name, a.order
+                )
+                .into());
+            }
+        }
+
+        let columns = Self::get_table_column_sqls(sorted, options)?;
+
+        let table_name = table.sql_name.clone();
+        let column_defs = columns.join(",\n");
+        let strict = if options.is_strict() { " STRICT" } else { "" };
+        let if_not_exists = if options.is_if_not_exists() {
+            " IF NOT EXISTS"
+        } else {
+            ""
+        };
+        let sql = format!("CREATE TABLE{if_not_exists} '{table_name}'({column_defs}){strict};");
+
+        Ok(sql)
+    }
+
+    /// Generate the column definitions of the given columns. Columns stored in child tables are
+    /// skipped, packed flags share one column.
+    pub(crate) fn get_table_column_sqls(
+        sorted: Vec<&ColumnDefinition>,
+        options: &TableSqlOptions,
+    ) -> Result<Vec<String>, SqliteDbError> {
+        let mut columns: Vec<String> = Vec::new();
+        let mut packed_columns: HashSet<&str> = HashSet::new();
+        for def in sorted {
+            if is_stored_in_child_table(def) || is_zero_sized(options.get_type_info(), def) {
+                continue;
+            }
+
+            if let Some(bit) = get_packed_bit(options.get_type_info(), def) {
+                if packed_columns.insert(bit.0) {
+                    columns.push(format!("{} INTEGER NOT NULL DEFAULT 0", bit.0));
+                }
+                continue;
+            }
+
+            columns.push(Self::get_column_sql(def, options)?);
+        }
+
+        Ok(columns)
+    }
+
+    /// Generate the column definition (name, type and constraints) of a single column.
+    pub(crate) fn get_column_sql(
+        def: &ColumnDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<String, SqliteDbError> {
+        let name = def.sql_name.clone();
+        let mut column = name.clone();
+        match def.sql_type {
+            _ if get_inline_type(options.get_type_info(), def).is_some() => {
+                if let Some(inner) = get_inline_type(options.get_type_info(), def) {
+                    column = get_inline_column_sql(inner)?;
+                }
+            }
+            bevy_erm::prelude::SqlType::None if options.is_converted(def.ty.id()) => {
+                if options.is_strict() {
+                    column.push_str(" ANY");
+                }
+            }
+            bevy_erm::prelude::SqlType::None
+                if def.ty.is::<Option<Entity>>()
+                    && options
+                        .get_field_attribute::<RelateByName>(&def.rust_name)
+                        .is_some() =>
+            {
+                column.push_str(" TEXT");
+            }
+            bevy_erm::prelude::SqlType::None if get_range_sql_type(def).is_some() => {
+                let sql_type = get_range_sql_type(def).unwrap_or_default();
+                column = get_range_column_names(def)
+                    .iter()
+                    .map(|x| format!("{x} {sql_type} NOT NULL"))
+                    .collect::<Vec<String>>()
+                    .join(",\n");
+            }
+            bevy_erm::prelude::SqlType::None if get_shared_column(def).is_some() => {
+                if let Some(inner) = get_shared_column(def) {
+                    column = Self::get_column_sql(&inner, options)?;
                 }
-                bevy_erm::prelude::SqlType::Boolean(not_null) => {
+            }
+            bevy_erm::prelude::SqlType::None if is_assigned_column(def) => {
+                column.push_str(assigned_column_sql_type(def));
+            }
+            bevy_erm::prelude::SqlType::None
+                if options
+                    .get_field_type_info(&def.rust_name)
+                    .and_then(get_integer_enum_info)
+                    .is_some() =>
+            {
+                column.push_str(" INTEGER NOT NULL");
+            }
+            bevy_erm::prelude::SqlType::None
+                if options
+                    .get_field_type_info(&def.rust_name)
+                    .and_then(get_tagged_enum_info)
+                    .is_some() =>
+            {
+                let value_type = options
+                    .get_field_type_info(&def.rust_name)
+                    .and_then(get_tagged_enum_info)
+                    .map(|(_, ty)| ty);
+                let Some(sql_type) = value_type.as_ref().and_then(get_tagged_value_sql_type) else {
+                    return Err(format!("The values of the enum {name} cannot be stored.").into());
+                };
+
+                let [tag, value] = get_tagged_column_names(def);
+                column = format!("{tag} TEXT NOT NULL,\n{value} {sql_type} NOT NULL");
+            }
+            bevy_erm::prelude::SqlType::None => {
+                return Err(SqliteDbError::UnsupportedType(def.ty.id()));
+            }
+            bevy_erm::prelude::SqlType::Integer(_, not_null) => {
+                if def.is_key() {
+                    column.push_str(" INTEGER PRIMARY KEY AUTOINCREMENT");
+                } else {
                     column.push_str(" INTEGER");
                     if not_null {
                         column.push_str(" NOT NULL");
                     }
-                    column.push_str(&format!(" CHECK({name} >= 0 AND {name} < 2)"));
                 }
-                bevy_erm::prelude::SqlType::One2One(_type_id, _) => todo!(),
-                bevy_erm::prelude::SqlType::Many2Many(_type_id, _) => todo!(),
             }
+            bevy_erm::prelude::SqlType::UnsingedInteger(_, not_null) => {
+                column.push_str(" INTEGER");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+                column.push_str(&format!(" CHECK({name} >= 0)"));
+            }
+            bevy_erm::prelude::SqlType::Float(_, not_null) => {
+                column.push_str(" REAL");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+            }
+            bevy_erm::prelude::SqlType::Text(not_null) => {
+                if def.has_max_length() && !options.is_strict() {
+                    column.push_str(&format!(" VARCHAR({})", def.get_max_length()));
+                } else {
+                    column.push_str(" TEXT");
+                }
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+                if let Some(collate) = options.get_field_attribute::<Collate>(&def.rust_name) {
+                    column.push_str(&format!(" COLLATE {}", collate.to_sql()));
+                }
+                // Strict tables do not know VARCHAR, so the length is enforced by a constraint.
+                if def.has_max_length() && options.is_strict() {
+                    column.push_str(&format!(
+                        " CHECK(length({name}) <= {})",
+                        def.get_max_length()
+                    ));
+                }
+            }
+            bevy_erm::prelude::SqlType::Date(not_null) => {
+                column.push_str(" TEXT");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+            }
+            bevy_erm::prelude::SqlType::Time(not_null) => {
+                column.push_str(" REAL");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+            }
+            bevy_erm::prelude::SqlType::DateTime(not_null) => {
+                column.push_str(" TEXT");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+            }
+            bevy_erm::prelude::SqlType::Blob(_) if is_flattened(options.get_type_info(), def) => {
+                column = get_component_names(def)
+                    .iter()
+                    .map(|x| format!("{x} REAL NOT NULL"))
+                    .collect::<Vec<String>>()
+                    .join(",\n");
+            }
+            bevy_erm::prelude::SqlType::Blob(not_null) => {
+                column.push_str(" BLOB");
+                let optional = options
+                    .get_field_type_info(&def.rust_name)
+                    .is_some_and(is_option_type);
+                if not_null && !optional {
+                    column.push_str(" NOT NULL");
+                }
+            }
+            bevy_erm::prelude::SqlType::Boolean(not_null)
+                if options
+                    .get_field_attribute::<BoolAsText>(&def.rust_name)
+                    .is_some() =>
+            {
+                column.push_str(" TEXT");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+                column.push_str(&format!(" CHECK({name} IN ('true', 'false'))"));
+            }
+            bevy_erm::prelude::SqlType::Boolean(not_null) => {
+                column.push_str(" INTEGER");
+                if not_null {
+                    column.push_str(" NOT NULL");
+                }
+                column.push_str(&format!(" CHECK({name} >= 0 AND {name} < 2)"));
+            }
+            bevy_erm::prelude::SqlType::One2One(type_id, not_null) => {
+                column = get_one_to_one_sql(def, type_id, not_null, options)?;
+            }
+            bevy_erm::prelude::SqlType::Many2Many(..) => {
+                return Err(format!("Column {name} is stored in a junction table.").into());
+            }
+        }
+
+        Ok(column)
+    }
+
+    /// Create a new table from the given table definition. If the table already exists,
+    /// it will not be created. This method prints an info instead and returns ok.
+    pub fn create_table(&mut self, def: &TableDefinition) -> Result<(), SqliteDbError> {
+        self.create_table_with_options(def, &TableSqlOptions::default())
+    }
+
+    /// Same as create_table, but generates the table using the given options.
+    pub fn create_table_with_options(
+        &mut self,
+        def: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<(), SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        if self.table_exists(&table_name)? {
+            info!("A table with the name {table_name} already exists");
+            return Ok(());
+        }
+
+        let options = self.get_sql_options(options);
+        let table_sql = Self::get_table_sql_with_options(def, &options)?;
+        self.execute(&table_sql, &[])?;
+
+        self.create_junction_tables(def, &options)
+    }
+
+    /// The options used to create tables of this database: the given options declaring the
+    /// types of the registered converters and using the type registry of the database, unless
+    /// the options set their own.
+    pub(crate) fn get_sql_options(&self, options: &TableSqlOptions) -> TableSqlOptions {
+        let mut options = options
+            .clone()
+            .with_converted_types(self.converters.type_ids());
+        if options.get_type_registry().is_none() {
+            options.set_type_registry(self.type_registry.as_ref());
+        }
+
+        options
+    }
+
+    /// Same as create_table, but lets sqlite skip an existing table (`CREATE TABLE IF NOT
+    /// EXISTS`) instead of checking for it first, so a table created in between does not fail
+    /// the call.
+    pub fn create_table_if_not_exists(
+        &mut self,
+        def: &TableDefinition,
+    ) -> Result<(), SqliteDbError> {
+        let mut options = self.get_sql_options(&TableSqlOptions::default());
+        options.use_if_not_exists(true);
+
+        let table_sql = Self::get_table_sql_with_options(def, &options)?;
+        self.execute(&table_sql, &[])?;
+
+        self.create_junction_tables(def, &options)
+    }
+
+    /// Create a view over the given select statement, unless a view with this name exists.
+    /// Structs can be queried from the view like from a table, as long as the selected
+    /// column names match the columns of their table definition.
+    pub fn create_view(&mut self, view_name: &str, select_sql: &str) -> Result<(), SqliteDbError> {
+        let select_sql = select_sql.trim().trim_end_matches(';');
+        let view_sql = format!("CREATE VIEW IF NOT EXISTS {view_name} AS {select_sql};");
+
+        match self.execute(&view_sql, &[]) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("Could not create view {view_name}: {e}").into()),
+        }
+    }
+
+    /// Drop the table, unless it does not exist. Child and junction tables of the table are
+    /// not dropped.
+    pub fn drop_table(&mut self, def: &TableDefinition) -> Result<(), SqliteDbError> {
+        self.drop_table_by_name(&def.sql_name)
+    }
+
+    /// Same as drop_table, but drops the table with the given name. The stored schema hash of
+    /// the table is removed as well.
+    pub fn drop_table_by_name(&mut self, table_name: &str) -> Result<(), SqliteDbError> {
+        self.execute(&format!("DROP TABLE IF EXISTS '{table_name}';"), &[])?;
+        self.remove_schema_hash(table_name)
+    }
+
+    pub fn insert<T: Reflect + Default + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        assert_eq!(table_name, Type::of::<T>().short_path());
+
+        if self.strict_inserts {
+            Self::check_fields(def, value)?;
+        }
+        check_inline_names(def, value.get_represented_type_info())?;
+
+        let (names_vec, mut wrapped_values) = Self::get_insert_values(def, value, registry);
+        self.resolve_entity_names(&mut wrapped_values)?;
+        apply_checksum(&names_vec, &mut wrapped_values)?;
+        self.execute_insert(&table_name, &names_vec, &wrapped_values)
+    }
+
+    /// Insert the default value of the type and return the key of the new row, e.g. to create
+    /// the settings row on the first run. Use find_or_create to insert the row only once.
+    pub fn insert_default<T: Reflect + Default + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        registry: &AppTypeRegistry,
+    ) -> Result<i64, SqliteDbError> {
+        self.insert(def, &T::default(), registry)?;
+        let id = self.query_scalar::<i64>("SELECT last_insert_rowid();", &[])?;
+
+        Ok(id.unwrap_or(0))
+    }
+
+    /// Insert only the given fields of the value. All other columns are left to the database,
+    /// so they receive their declared defaults instead of the defaults of the rust type.
+    /// The checksum of types with a `Checksum` column is computed from the stored row, so it
+    /// covers the defaults of the omitted columns as well.
+    pub fn insert_set<T: Reflect + Default + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        value: &T,
+        fields: &[&str],
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        check_inline_names(def, value.get_represented_type_info())?;
+
+        let mut names_vec: Vec<String> = Vec::new();
+        let mut wrapped_values: Vec<ValueWrapper> = Vec::new();
+        for field in fields {
+            let Some(col) = def.fields.values().find(|x| x.rust_name == *field) else {
+                return Err(SqliteDbError::UnknownColumn {
+                    table: table_name.clone(),
+                    column: field.to_string(),
+                });
+            };
+
+            if is_zero_sized(value.get_represented_type_info(), col) {
+                continue;
+            }
+
+            Self::push_insert_value(col, value, registry, &mut names_vec, &mut wrapped_values);
+        }
+
+        let type_info = value.get_represented_type_info();
+        let Some(checksum) = def.fields.values().find(|x| {
+            type_info
+                .and_then(|t| get_field_attribute::<Checksum>(t, &x.rust_name))
+                .is_some()
+        }) else {
+            self.resolve_entity_names(&mut wrapped_values)?;
+            return self.execute_insert(&table_name, &names_vec, &wrapped_values);
+        };
+
+        // The omitted columns receive the defaults of the database, so the checksum is computed
+        // from the stored row instead of the given value.
+        if !fields.contains(&checksum.rust_name.as_str()) {
+            Self::push_insert_value(
+                checksum,
+                value,
+                registry,
+                &mut names_vec,
+                &mut wrapped_values,
+            );
+        }
+        self.resolve_entity_names(&mut wrapped_values)?;
+
+        self.savepoint("insert_set", |database| {
+            let count = database.execute_insert(&table_name, &names_vec, &wrapped_values)?;
+            let row_id = database
+                .query_scalar::<i64>("SELECT last_insert_rowid();", &[])?
+                .unwrap_or(0);
+
+            let query = format!("SELECT * FROM '{table_name}' WHERE rowid = ?1;");
+            let Some(stored) = database.query_one::<T>(def, &query, &[&row_id])? else {
+                return Err(format!("The inserted row of {table_name} could not be read.").into());
+            };
+            let (names, mut wrapped) = Self::get_insert_values(def, &stored, registry);
+            database.resolve_entity_names(&mut wrapped)?;
+            let hash = compute_checksum(&names, &wrapped)? as i64;
+
+            let update = format!(
+                "UPDATE '{table_name}' SET {} = ?1 WHERE rowid = ?2;",
+                checksum.sql_name
+            );
+            database.execute(&update, &[&hash, &row_id])?;
+
+            Ok(count)
+        })
+    }
+
+    /// Insert the value including its key. If a row with this key (or any other unique value of
+    /// the row) exists, it is replaced.
+    ///
+    /// Unlike `bulk_upsert`, which updates the existing row in place, `INSERT OR REPLACE`
+    /// deletes the existing row and inserts a new one. Foreign keys with `ON DELETE CASCADE`
+    /// delete the rows referencing the old row, delete triggers fire (if recursive triggers are
+    /// enabled) and columns which are not written receive their defaults instead of keeping
+    /// their values.
+    pub fn insert_or_replace<T: Reflect + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        let Some(key) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(table_name));
+        };
+        check_inline_names(def, value.get_represented_type_info())?;
+
+        let (mut names_vec, mut wrapped_values) = Self::get_insert_values(def, value, registry);
+        self.resolve_entity_names(&mut wrapped_values)?;
+        apply_checksum(&names_vec, &mut wrapped_values)?;
+
+        names_vec.push(key.sql_name.clone());
+        wrapped_values.push(ValueWrapper::build(value, &key.rust_name, registry));
+        let wrapped_links: Vec<&dyn ToSql> =
+            wrapped_values.iter().map(|x| x as &dyn ToSql).collect();
+
+        let parameter: Vec<&str> = names_vec.iter().map(|_| "?").collect();
+        let query = format!(
+            "INSERT OR REPLACE INTO {} ({}) VALUES ({});",
+            table_name,
+            names_vec.join(", "),
+            parameter.join(", ")
+        );
+        self.execute(&query, &wrapped_links)
+    }
+
+    /// Update the row with the key of the given value. All other columns are set to the values
+    /// of the fields. Returns the number of changed rows, which is 0 if there is no row with
+    /// this key.
+    pub fn update<T: Reflect + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        let Some(key) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(table_name));
+        };
+        check_inline_names(def, value.get_represented_type_info())?;
+
+        let (names_vec, mut wrapped_values) = Self::get_insert_values(def, value, registry);
+        if names_vec.is_empty() {
+            return Err(format!("Table {table_name} has no columns to update.").into());
+        }
+        self.resolve_entity_names(&mut wrapped_values)?;
+        apply_checksum(&names_vec, &mut wrapped_values)?;
+
+        let key_value = ValueWrapper::build(value, &key.rust_name, registry);
+        let wrapped_links: Vec<&dyn ToSql> = wrapped_values
+            .iter()
+            .chain(std::iter::once(&key_value))
+            .map(|x| x as &dyn ToSql)
+            .collect();
+
+        let set: Vec<String> = names_vec.iter().map(|x| format!("{x} = ?")).collect();
+        let query = format!(
+            "UPDATE {} SET {} WHERE {} = ?;",
+            table_name,
+            set.join(", "),
+            key.sql_name
+        );
+        self.execute(&query, &wrapped_links)
+    }
+
+    /// Delete the row with the key of the given value. Returns the number of deleted rows.
+    /// Values whose key is still unset (0, empty or NULL) were never stored, so they are
+    /// rejected instead of deleting whatever row might use this key.
+    pub fn delete<T: Reflect + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        let Some(key) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(table_name));
+        };
+
+        let key_value = ValueWrapper::build(value, &key.rust_name, registry);
+        let is_unset = match key_value.to_sql().map_err(SqliteDbError::from_execute)? {
+            ToSqlOutput::Owned(Value::Integer(0) | Value::Null) => true,
+            ToSqlOutput::Owned(Value::Text(text)) => text.is_empty(),
+            _ => false,
+        };
+        if is_unset {
+            return Err(format!(
+                "The value has no key, so it was never stored in table {table_name}."
+            )
+            .into());
+        }
+
+        let query = format!("DELETE FROM {} WHERE {} = ?;", table_name, key.sql_name);
+        self.execute(&query, &[&key_value])
+    }
+
+    /// Make sure that every field of the value has a column in the table definition.
+    /// Lists are skipped, because they are stored in child tables.
+    pub fn check_fields<T: bevy::prelude::Struct>(
+        def: &TableDefinition,
+        value: &T,
+    ) -> Result<(), SqliteDbError> {
+        for i in 0..value.field_len() {
+            let (Some(name), Some(field)) = (value.name_at(i), value.field_at(i)) else {
+                continue;
+            };
+
+            if matches!(field.reflect_ref(), ReflectRef::List(_)) {
+                continue;
+            }
+
+            if !def.fields.values().any(|x| x.rust_name == name) {
+                return Err(format!(
+                    "Field {} has no column in table {}. Was the type re-registered and the table migrated?",
+                    name, def.sql_name
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute_insert(
+        &mut self,
+        table_name: &str,
+        names_vec: &[String],
+        wrapped_values: &[ValueWrapper],
+    ) -> Result<usize, SqliteDbError> {
+        if names_vec.is_empty() {
+            return self.execute(&format!("INSERT INTO {table_name} DEFAULT VALUES;"), &[]);
+        }
+
+        let params_vec: Vec<&str> = names_vec.iter().map(|_| "?").collect();
+
+        let column_names = names_vec.join(", ");
+        let parameter = params_vec.join(", ");
+
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES ({});",
+            table_name, column_names, parameter
+        );
+
+        let wrapped_links: Vec<&dyn ToSql> =
+            wrapped_values.iter().map(|x| x as &dyn ToSql).collect();
+
+        self.execute(&query, &wrapped_links)
+    }
+
+    /// Get the row with the given key or insert the value created by `factory` with this key,
+    /// if there is none. The key of the created value is replaced by the given key.
+    pub fn find_or_create<T: Default + Reflect + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        key: &dyn ToSql,
+        factory: impl FnOnce() -> T,
+        registry: &AppTypeRegistry,
+    ) -> Result<T, SqliteDbError> {
+        if let Some(value) = self.find(def, key)? {
+            return Ok(value);
+        }
+
+        let Some(key_column) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(def.sql_name.clone()));
+        };
+
+        let value = factory();
+        let (mut names_vec, mut wrapped_values) = Self::get_insert_values(def, &value, registry);
+        self.resolve_entity_names(&mut wrapped_values)?;
+        apply_checksum(&names_vec, &mut wrapped_values)?;
+
+        names_vec.push(key_column.sql_name.clone());
+        let mut params_vec: Vec<&dyn ToSql> =
+            wrapped_values.iter().map(|x| x as &dyn ToSql).collect();
+        params_vec.push(key);
+
+        let parameter: Vec<&str> = names_vec.iter().map(|_| "?").collect();
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES ({});",
+            def.sql_name,
+            names_vec.join(", "),
+            parameter.join(", ")
+        );
+        self.execute(&query, &params_vec)?;
+
+        match self.find(def, key)? {
+            Some(value) => Ok(value),
+            None => {
+                Err(format!("Could not read the created row of table {}.", def.sql_name).into())
+            }
+        }
+    }
+
+    /// Get the row with the given key or `None`, if there is no such row. Fails, if the table
+    /// has no key column.
+    pub fn find<T: Default + Reflect>(
+        &mut self,
+        def: &TableDefinition,
+        key: &dyn ToSql,
+    ) -> Result<Option<T>, SqliteDbError> {
+        let Some(key_column) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(def.sql_name.clone()));
+        };
+
+        let query = format!(
+            "SELECT * FROM {} WHERE {} = ?;",
+            def.sql_name, key_column.sql_name
+        );
+        self.query_one(def, &query, &[key])
+    }
+
+    /// Fill the fields of the target, which still have their default value, with the values of
+    /// the row with the given key. Fields set at runtime keep their value. Returns false, if
+    /// there is no row with the given key.
+    pub fn merge_into<T: Default + Reflect + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        target: &mut T,
+        key: &dyn ToSql,
+    ) -> Result<bool, SqliteDbError> {
+        let Some(stored) = self.find::<T>(def, key)? else {
+            return Ok(false);
+        };
+
+        let default = T::default();
+        for x in 0..target.field_len() {
+            let is_default = match (target.field_at(x), default.field_at(x)) {
+                (Some(field), Some(default)) => field.reflect_partial_eq(default).unwrap_or(false),
+                _ => false,
+            };
+            if !is_default {
+                continue;
+            }
+
+            if let (Some(field), Some(value)) = (target.field_at_mut(x), stored.field_at(x)) {
+                field.apply(value);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Get all rows with one of the given keys in a single query per batch of keys, instead of
+    /// one query per key. Keys without a row are skipped, the order of the rows is unspecified.
+    pub fn find_many_by_keys<T: Default + Reflect, K: ToSql>(
+        &mut self,
+        def: &TableDefinition,
+        keys: &[K],
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let Some(key_column) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(def.sql_name.clone()));
+        };
+
+        let mut result: Vec<T> = Vec::with_capacity(keys.len());
+        // Older versions of sqlite allow at most 999 parameters per statement.
+        for batch in keys.chunks(KEY_BATCH_SIZE) {
+            let parameter: Vec<&dyn ToSql> = batch.iter().map(|x| x as &dyn ToSql).collect();
+            let placeholders: Vec<&str> = batch.iter().map(|_| "?").collect();
+            let query = format!(
+                "SELECT * FROM {} WHERE {} IN ({});",
+                def.sql_name,
+                key_column.sql_name,
+                placeholders.join(", ")
+            );
+            self.query_each(def, &query, &parameter, |x| result.push(x))?;
+        }
+
+        Ok(result)
+    }
+
+    /// Delete all rows matching the where clause and return the deleted rows. SQLite 3.35 and
+    /// newer delete and return the rows in one statement. Older versions select the rows first
+    /// and delete them afterwards within a savepoint.
+    pub fn delete_returning<T: Default + Reflect>(
+        &mut self,
+        def: &TableDefinition,
+        where_clause: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        let filter = where_sql(where_clause);
+
+        if rusqlite::version_number() >= 3_035_000 {
+            let query = format!("DELETE FROM {table_name}{filter} RETURNING *;");
+            return self.query(def, &query, parameter);
+        }
+
+        self.savepoint("delete_returning", |database| {
+            let select = format!("SELECT * FROM {table_name}{filter};");
+            let rows = database.query(def, &select, parameter)?;
+            database.execute(&format!("DELETE FROM {table_name}{filter};"), parameter)?;
+
+            Ok(rows)
+        })
+    }
+
+    /// Read one page of the rows returned by the query. `LIMIT ? OFFSET ?` is appended to the
+    /// query and bound after the given parameters, so the query has to order its rows to get
+    /// pages which do not overlap.
+    pub fn query_paged<T: Default + Reflect>(
+        &mut self,
+        def: &TableDefinition,
+        base_query: &str,
+        parameter: &[&dyn ToSql],
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<T>, SqliteDbError> {
+        let base_query = base_query.trim().trim_end_matches(';');
+        let query = format!("{base_query} LIMIT ? OFFSET ?;");
+        let (limit, offset) = (limit as i64, offset as i64);
+
+        let mut params_vec: Vec<&dyn ToSql> = parameter.to_vec();
+        params_vec.push(&limit);
+        params_vec.push(&offset);
+
+        self.query(def, &query, &params_vec)
+    }
+
+    /// Read one page of the rows matching the where clause together with the total number of
+    /// matching rows, e.g. to show "10 of 237" in a list. Both are read within a savepoint, so
+    /// the total is consistent with the page. Rows are ordered by the key column, if there is
+    /// one, so pages do not overlap.
+    pub fn query_paged_with_total<T: Default + Reflect>(
+        &mut self,
+        def: &TableDefinition,
+        where_clause: &str,
+        limit: usize,
+        offset: usize,
+        parameter: &[&dyn ToSql],
+    ) -> Result<(Vec<T>, i64), SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        let filter = where_sql(where_clause);
+        let order = match def.fields.values().find(|x| x.is_key()) {
+            Some(key) => format!(" ORDER BY {}", key.sql_name),
+            None => String::new(),
+        };
+
+        self.savepoint("query_paged_with_total", |database| {
+            let select =
+                format!("SELECT * FROM {table_name}{filter}{order} LIMIT {limit} OFFSET {offset};");
+            let rows = database.query(def, &select, parameter)?;
+            let count = format!("SELECT COUNT(*) FROM {table_name}{filter};");
+            let total = database.query_scalar::<i64>(&count, parameter)?;
+
+            Ok((rows, total.unwrap_or(0)))
+        })
+    }
+
+    /// Update the given columns of all rows matching the where clause and return the number of
+    /// changed rows. The column names are checked against the table definition. The values of
+    /// the columns are bound before the parameters of the where clause, so the where clause has
+    /// to use unnumbered `?` placeholders. The values are written as they are, so the stored
+    /// checksum of types with a `Checksum` column is not updated and `verify_row` fails for the
+    /// changed rows. Use `update` for such types.
+    pub fn update_where(
+        &mut self,
+        def: &TableDefinition,
+        set_fields: &[(&str, &dyn ToSql)],
+        where_clause: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<usize, SqliteDbError> {
+        if set_fields.is_empty() {
+            return Err("No columns to update.".into());
+        }
+
+        let mut assignments: Vec<String> = Vec::new();
+        let mut params_vec: Vec<&dyn ToSql> = Vec::new();
+        for (name, value) in set_fields {
+            let Some(col) = def.get(name) else {
+                return Err(SqliteDbError::UnknownColumn {
+                    table: def.sql_name.clone(),
+                    column: name.to_string(),
+                });
+            };
+
+            assignments.push(format!("{} = ?", col.sql_name));
+            params_vec.push(*value);
+        }
+        params_vec.extend_from_slice(parameter);
+
+        let filter = where_sql(where_clause);
+        let query = format!(
+            "UPDATE {} SET {}{};",
+            def.sql_name,
+            assignments.join(", "),
+            filter
+        );
+
+        self.execute(&query, &params_vec)
+    }
+
+    /// Insert or update all given values within a single transaction, using one prepared
+    /// `INSERT ... ON CONFLICT DO UPDATE` statement. Values are matched by their key, so rows
+    /// with an existing key are updated and all others are inserted with the given key.
+    /// Returns the number of written rows.
+    pub fn bulk_upsert<T: Reflect + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        values: &[T],
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        assert_eq!(table_name, Type::of::<T>().short_path());
+
+        let Some(key) = def.fields.values().find(|x| x.is_key()) else {
+            return Err(SqliteDbError::NoKeyColumn(table_name));
+        };
+
+        let Some(first) = values.first() else {
+            return Ok(0);
+        };
+
+        let (names_vec, _) = Self::get_insert_values(def, first, registry);
+        let updates: Vec<String> = names_vec
+            .iter()
+            .map(|x| format!("{x} = excluded.{x}"))
+            .collect();
+        let on_conflict = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", updates.join(", "))
+        };
+
+        let params_vec: Vec<&str> = (0..=names_vec.len()).map(|_| "?").collect();
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) {};",
+            table_name,
+            std::iter::once(&key.sql_name)
+                .chain(names_vec.iter())
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", "),
+            params_vec.join(", "),
+            key.sql_name,
+            on_conflict
+        );
+
+        let entity_names = &self.entity_names;
+        let converters = &self.converters;
+        match self.connection.lock() {
+            Ok(mut c) => match c.as_mut() {
+                Some(connection) => {
+                    let savepoint = connection
+                        .savepoint()
+                        .map_err(SqliteDbError::from_execute)?;
+                    let mut count = 0;
+                    {
+                        let mut stmt = savepoint.prepare(&query).map_err(SqliteDbError::Prepare)?;
+                        for value in values {
+                            let key_value = ValueWrapper::build(value, &key.rust_name, registry);
+                            let (_, mut wrapped_values) =
+                                Self::get_insert_values(def, value, registry);
+                            for wrapped in wrapped_values.iter_mut() {
+                                wrapped.resolve_name(entity_names)?;
+                                wrapped.convert(converters);
+                            }
+                            apply_checksum(&names_vec, &mut wrapped_values)?;
+
+                            let wrapped_links: Vec<&dyn ToSql> = std::iter::once(&key_value)
+                                .chain(wrapped_values.iter())
+                                .map(|x| x as &dyn ToSql)
+                                .collect();
+
+                            count += stmt
+                                .execute(wrapped_links.as_slice())
+                                .map_err(SqliteDbError::from_execute)?;
+                        }
+                    }
+                    savepoint.commit().map_err(SqliteDbError::from_execute)?;
+
+                    Ok(count)
+                }
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Insert all given values within a single transaction, using one prepared statement.
+    /// Either all values are inserted or none of them. Returns the number of inserted rows.
+    pub fn insert_many<T: Reflect + Default + TypePath + bevy::prelude::Struct>(
+        &mut self,
+        def: &TableDefinition,
+        values: &[T],
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        let table_name = def.sql_name.clone();
+        assert_eq!(table_name, Type::of::<T>().short_path());
+
+        let Some(first) = values.first() else {
+            return Ok(0);
+        };
+
+        for value in values {
+            if self.strict_inserts {
+                Self::check_fields(def, value)?;
+            }
+            check_inline_names(def, value.get_represented_type_info())?;
+        }
+
+        let (names_vec, _) = Self::get_insert_values(def, first, registry);
+        let query = if names_vec.is_empty() {
+            format!("INSERT INTO {table_name} DEFAULT VALUES;")
+        } else {
+            let params_vec: Vec<&str> = names_vec.iter().map(|_| "?").collect();
+            format!(
+                "INSERT INTO {} ({}) VALUES ({});",
+                table_name,
+                names_vec.join(", "),
+                params_vec.join(", ")
+            )
+        };
+
+        let entity_names = &self.entity_names;
+        let converters = &self.converters;
+        match self.connection.lock() {
+            Ok(mut c) => match c.as_mut() {
+                Some(connection) => {
+                    let savepoint = connection
+                        .savepoint()
+                        .map_err(SqliteDbError::from_execute)?;
+                    let mut count = 0;
+                    {
+                        let mut stmt = savepoint.prepare(&query).map_err(SqliteDbError::Prepare)?;
+                        for value in values {
+                            let (_, mut wrapped_values) =
+                                Self::get_insert_values(def, value, registry);
+                            for wrapped in wrapped_values.iter_mut() {
+                                wrapped.resolve_name(entity_names)?;
+                                wrapped.convert(converters);
+                            }
+                            apply_checksum(&names_vec, &mut wrapped_values)?;
+
+                            let wrapped_links: Vec<&dyn ToSql> =
+                                wrapped_values.iter().map(|x| x as &dyn ToSql).collect();
+
+                            count += stmt
+                                .execute(wrapped_links.as_slice())
+                                .map_err(SqliteDbError::from_execute)?;
+                        }
+                    }
+                    savepoint.commit().map_err(SqliteDbError::from_execute)?;
+
+                    Ok(count)
+                }
+                None => Err(SqliteDbError::NotOpen),
+            },
+            Err(_) => Err(SqliteDbError::MutexPoisoned),
+        }
+    }
+
+    /// Store relations marked as `RelateByName` as the name of the related entity and fields
+    /// with a custom converter as the converted value.
+    pub(crate) fn resolve_entity_names(
+        &self,
+        wrapped_values: &mut [ValueWrapper],
+    ) -> Result<(), SqliteDbError> {
+        for wrapped in wrapped_values.iter_mut() {
+            wrapped.resolve_name(&self.entity_names)?;
+            wrapped.convert(&self.converters);
+        }
+
+        Ok(())
+    }
+
+    /// Collect the column names and wrapped values of all non-key columns of the given value.
+    pub(crate) fn get_insert_values<'a, T: Reflect + TypePath + bevy::prelude::Struct>(
+        def: &TableDefinition,
+        value: &'a T,
+        registry: &AppTypeRegistry,
+    ) -> (Vec<String>, Vec<ValueWrapper<'a>>) {
+        let mut names_vec: Vec<String> = Vec::new();
+        let mut wrapped_values: Vec<ValueWrapper> = Vec::new();
+
+        let type_info = value.get_represented_type_info();
+        for x in def.fields.values() {
+            if x.is_key() || is_stored_in_child_table(x) || is_zero_sized(type_info, x) {
+                continue;
+            }
+
+            Self::push_insert_value(x, value, registry, &mut names_vec, &mut wrapped_values);
+        }
+
+        (names_vec, wrapped_values)
+    }
+
+    /// Add the column(s) storing the given field. Flattened vectors use one column per component,
+    /// ranges one column per bound, tagged enums one column for the variant and one for its value.
+    /// Inlined structs use one column per field. Packed flags share one column, which is added
+    /// with the first of its fields.
+    fn push_insert_value<'a, T: Reflect + TypePath + bevy::prelude::Struct>(
+        col: &ColumnDefinition,
+        value: &'a T,
+        registry: &AppTypeRegistry,
+        names_vec: &mut Vec<String>,
+        wrapped_values: &mut Vec<ValueWrapper<'a>>,
+    ) {
+        if let Some(bit) = get_packed_bit(value.get_represented_type_info(), col) {
+            if !names_vec.iter().any(|x| x == bit.0) {
+                let mut wrapped = ValueWrapper::build(value, &col.rust_name, registry);
+                wrapped.set_value(Value::Integer(get_packed_value(value, bit.0)));
+                names_vec.push(bit.0.to_string());
+                wrapped_values.push(wrapped);
+            }
+
+            return;
+        }
+
+        if is_flattened(value.get_represented_type_info(), col) {
+            for (component, name) in get_component_names(col).into_iter().enumerate() {
+                let wrapped = ValueWrapper::build(value, &col.rust_name, registry);
+                names_vec.push(name);
+                wrapped_values.push(wrapped.with_component(component));
+            }
+
+            return;
+        }
+
+        if let Some(inner) = get_inline_type(value.get_represented_type_info(), col) {
+            let Some(ReflectRef::Struct(fields)) =
+                value.field(&col.rust_name).map(|f| f.reflect_ref())
+            else {
+                return;
+            };
+
+            for name in get_inline_names(inner) {
+                let Some(field) = fields.field(name).and_then(|f| f.try_as_reflect()) else {
+                    continue;
+                };
+
+                let wrapped = ValueWrapper::build(value, &col.rust_name, registry);
+                names_vec.push(name.to_string());
+                wrapped_values.push(wrapped.with_getter(field));
+            }
+
+            return;
+        }
+
+        if get_field_tagged_enum(value.get_represented_type_info(), col).is_some() {
+            for (component, name) in get_tagged_column_names(col).into_iter().enumerate() {
+                let wrapped = ValueWrapper::build(value, &col.rust_name, registry);
+                names_vec.push(name);
+                wrapped_values.push(wrapped.with_component(component));
+            }
+
+            return;
+        }
+
+        if get_range_sql_type(col).is_some() {
+            for (component, name) in get_range_column_names(col).into_iter().enumerate() {
+                let wrapped = ValueWrapper::build(value, &col.rust_name, registry);
+                names_vec.push(name);
+                wrapped_values.push(wrapped.with_component(component));
+            }
+
+            return;
+        }
+
+        names_vec.push(col.sql_name.clone());
+        wrapped_values.push(ValueWrapper::build(value, &col.rust_name, registry));
+    }
+}
+
+/// The columns of the table, sorted by their order. Columns sharing an order are sorted by the
+/// name of their field, so the sequence does not depend on the order of the hash map.
+pub(crate) fn sort_columns(table: &TableDefinition) -> Vec<&ColumnDefinition> {
+    let mut sorted: Vec<&ColumnDefinition> = table.fields.values().collect();
+    sorted.sort_by(|a, b| {
+        a.order
+            .cmp(&b.order)
+            .then_with(|| a.rust_name.cmp(&b.rust_name))
+    });
+    sorted
+}
+
+/// True, if the type is an `Option`.
+fn is_option_type(info: &TypeInfo) -> bool {
+    matches!(info, TypeInfo::Enum(e) if e.type_path().starts_with("core::option::Option<"))
+}
+
+/// True, if the field of the struct is an `Option`. Blob columns take their nullability from
+/// the field, so the column, the written value and the read value agree on it.
+fn is_optional_field(type_info: Option<&TypeInfo>, name: &str) -> bool {
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return false;
+    };
+
+    info.field(name)
+        .and_then(|f| f.type_info())
+        .is_some_and(is_option_type)
+}
+
+/// Zero-sized fields (`()`, `PhantomData<T>` and marker structs without fields) carry no data.
+/// They are skipped when creating, inserting and reading the table and keep their default value.
+/// Marker structs can only be detected, if the type info of the owning struct is known.
+pub(crate) fn is_zero_sized(type_info: Option<&TypeInfo>, col: &ColumnDefinition) -> bool {
+    if !matches!(col.sql_type, bevy_erm::prelude::SqlType::None) {
+        return false;
+    }
+
+    if col.ty.is::<()>() || col.ty.path().starts_with("core::marker::PhantomData<") {
+        return true;
+    }
+
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return false;
+    };
+
+    match info.field(&col.rust_name).and_then(|f| f.type_info()) {
+        Some(TypeInfo::Struct(field)) => field.field_len() == 0,
+        Some(TypeInfo::TupleStruct(field)) => field.field_len() == 0,
+        Some(TypeInfo::Tuple(field)) => field.field_len() == 0,
+        _ => false,
+    }
+}
+
+/// Columns of types which are assigned to the fields directly when reading a row.
+/// Network addresses are stored in their textual form and parsed on read. Entities and optional
+/// entity relations are stored as the entity bits or NULL (see `assign_entity`). Directions are
+/// stored as the blob of their unit vector, `Vec3A` as the blob of a `Vec3`. Isometries are
+/// stored as the blob of their rotation followed by the blob of their translation. Matrices are
+/// stored as the blobs of their columns, affine transforms as the blob of their matrix followed
+/// by the blob of their translation. Stopwatches are stored as their elapsed seconds, timers as
+/// their remaining seconds (see `assign_timer`). Timestamps are stored as RFC 3339 text
+/// (see `date_time`), decimals as their canonical text (see `decimal`), small strings as text
+/// (see `small_string`). Shared values are stored like their inner value. `Cow<str>` is stored
+/// as text and read as an owned string.
+pub(crate) fn is_assigned_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<IpAddr>()
+        || col.ty.is::<Option<IpAddr>>()
+        || col.ty.is::<SocketAddr>()
+        || col.ty.is::<Option<SocketAddr>>()
+        || col.ty.is::<Entity>()
+        || col.ty.is::<Option<Entity>>()
+        || is_direction_column(col)
+        || col.ty.is::<Vec3A>()
+        || col.ty.is::<Option<Vec3A>>()
+        || is_isometry_column(col)
+        || is_matrix_column(col)
+        || col.ty.is::<Stopwatch>()
+        || col.ty.is::<Timer>()
+        || col.ty.is::<Cow<'static, str>>()
+        || is_text_number_column(col)
+        || is_date_time_column(col)
+        || is_decimal_column(col)
+        || is_small_string_column(col)
+        || get_shared_column(col).is_some()
+}
+
+fn is_direction_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<Dir2>()
+        || col.ty.is::<Option<Dir2>>()
+        || col.ty.is::<Dir3>()
+        || col.ty.is::<Option<Dir3>>()
+}
+
+fn is_isometry_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<Isometry2d>()
+        || col.ty.is::<Option<Isometry2d>>()
+        || col.ty.is::<Isometry3d>()
+        || col.ty.is::<Option<Isometry3d>>()
+}
+
+fn is_matrix_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<Mat3A>()
+        || col.ty.is::<Option<Mat3A>>()
+        || col.ty.is::<Affine2>()
+        || col.ty.is::<Option<Affine2>>()
+        || col.ty.is::<Affine3A>()
+        || col.ty.is::<Option<Affine3A>>()
+}
+
+/// Columns of characters and 128 bit integers, which are stored as text. Note that sqlite
+/// compares text character by character, so `ORDER BY` on a 128 bit integer column does not
+/// follow the numeric order.
+fn is_text_number_column(col: &ColumnDefinition) -> bool {
+    col.ty.is::<char>()
+        || col.ty.is::<Option<char>>()
+        || col.ty.is::<u128>()
+        || col.ty.is::<Option<u128>>()
+        || col.ty.is::<i128>()
+        || col.ty.is::<Option<i128>>()
+}
+
+/// True, if the error is caused by another connection holding a lock on the database, so
+/// trying again later may succeed.
+fn is_busy(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Fail with `RowLimitExceeded`, if the row with the given index exceeds the row limit.
+fn check_max_rows(max_rows: Option<usize>, index: usize) -> Result<(), SqliteDbError> {
+    match max_rows {
+        Some(max_rows) if index >= max_rows => Err(SqliteDbError::RowLimitExceeded(max_rows)),
+        _ => Ok(()),
+    }
+}
+
+/// The column type of an assigned column (see `is_assigned_column`).
+fn assigned_column_sql_type(col: &ColumnDefinition) -> &'static str {
+    if col.ty.is::<Entity>() {
+        " INTEGER NOT NULL"
+    } else if col.ty.is::<Option<Entity>>() {
+        " INTEGER"
+    } else if col.ty.is::<Vec3A>()
+        || col.ty.is::<Isometry2d>()
+        || col.ty.is::<Isometry3d>()
+        || col.ty.is::<Mat3A>()
+        || col.ty.is::<Affine2>()
+        || col.ty.is::<Affine3A>()
+    {
+        " BLOB NOT NULL"
+    } else if is_direction_column(col)
+        || is_isometry_column(col)
+        || is_matrix_column(col)
+        || col.ty.is::<Option<Vec3A>>()
+    {
+        " BLOB"
+    } else if col.ty.is::<Stopwatch>() || col.ty.is::<Timer>() {
+        " REAL NOT NULL"
+    } else if col.ty.is::<Cow<'static, str>>()
+        || col.ty.is::<char>()
+        || col.ty.is::<u128>()
+        || col.ty.is::<i128>()
+    {
+        " TEXT NOT NULL"
+    } else {
+        " TEXT"
+    }
+}
+
+/// The `WHERE` part of a statement for the given where clause, or nothing for an empty clause.
+fn where_sql(clause: &str) -> String {
+    let clause = clause.trim().trim_end_matches(';');
+    if clause.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {clause}")
+    }
+}
+
+/// The error of a column whose declared type cannot be read, e.g. an integer of more than 64
+/// bits.
+fn invalid_column_type(row: &Row, x: usize, name: &str) -> rusqlite::Error {
+    match row.get_ref(x) {
+        Ok(value) => rusqlite::Error::InvalidColumnType(x, name.to_string(), value.data_type()),
+        Err(e) => e,
+    }
+}
+
+/// Insert a value read from a nullable column. Columns which are not null are inserted as `V`
+/// and fail on NULL, all other columns are inserted as `Option<V>`.
+fn insert_nullable<V>(
+    dyn_type: &mut DynamicStruct,
+    name: &str,
+    x: usize,
+    value: Option<V>,
+    not_null: bool,
+) -> rusqlite::Result<()>
+where
+    V: PartialReflect,
+    Option<V>: PartialReflect,
+{
+    if !not_null {
+        dyn_type.insert(name, value);
+        return Ok(());
+    }
+
+    let Some(value) = value else {
+        return Err(rusqlite::Error::InvalidColumnType(
+            x,
+            name.to_string(),
+            rusqlite::types::Type::Null,
+        ));
+    };
+    dyn_type.insert(name, value);
+
+    Ok(())
+}
+
+/// Read a `bool` stored as TEXT `'true'`/`'false'` (see `BoolAsText`).
+fn parse_bool_text(row: &Row, x: usize) -> rusqlite::Result<bool> {
+    match row.get::<usize, String>(x)?.as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        v => Err(rusqlite::Error::FromSqlConversionFailure(
+            x,
+            rusqlite::types::Type::Text,
+            format!("{v} is not a boolean").into(),
+        )),
+    }
+}
+
+/// Parse the text stored in the given column.
+fn parse_text<V: FromStr>(row: &Row, x: usize) -> rusqlite::Result<V>
+where
+    V::Err: std::error::Error + Send + Sync + 'static,
+{
+    let text = row.get::<usize, String>(x)?;
+    text.parse::<V>().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(x, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Parse the text stored in the given column and assign it to the field, which is either of
+/// type `V` or `Option<V>`.
+pub(crate) fn assign_parsed<V: FromStr + Any>(
+    field: &mut dyn Any,
+    row: &Row,
+    x: usize,
+) -> rusqlite::Result<()>
+where
+    V::Err: std::error::Error + Send + Sync + 'static,
+{
+    if let Some(f) = field.downcast_mut::<V>() {
+        *f = parse_text(row, x)?;
+    } else if let Some(f) = field.downcast_mut::<Option<V>>() {
+        *f = match row.get_ref(x)? {
+            rusqlite::types::ValueRef::Null => None,
+            _ => Some(parse_text(row, x)?),
+        };
+    }
+
+    Ok(())
+}
+
+/// Read the entity bits stored in the given column and assign them to an `Entity` or an
+/// `Option<Entity>` field. NULL is read as `None`. Entity ids are not stable across runs of the
+/// app, so stored entities are only valid within the session which stored them, e.g. for
+/// snapshots of the world.
+fn assign_entity(field: &mut dyn Any, row: &Row, x: usize) -> rusqlite::Result<()> {
+    let from_bits = |bits: i64| {
+        Entity::try_from_bits(bits as u64).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                x,
+                rusqlite::types::Type::Integer,
+                Box::new(e),
+            )
+        })
+    };
+
+    if let Some(f) = field.downcast_mut::<Entity>() {
+        *f = from_bits(row.get::<usize, i64>(x)?)?;
+    } else if let Some(f) = field.downcast_mut::<Option<Entity>>() {
+        *f = match row.get::<usize, Option<i64>>(x)? {
+            Some(bits) => Some(from_bits(bits)?),
+            None => None,
+        };
+    }
+
+    Ok(())
+}
+
+/// Read the name stored in the given column and assign the entity with this name to an
+/// `Option<Entity>` field. NULL is read as `None`, unknown names are an error.
+fn assign_entity_by_name(
+    field: &mut dyn Any,
+    row: &Row,
+    x: usize,
+    entity_names: &EntityNames,
+) -> rusqlite::Result<()> {
+    if let Some(f) = field.downcast_mut::<Option<Entity>>() {
+        *f = match row.get::<usize, Option<String>>(x)? {
+            Some(name) => match entity_names.get_entity(&name) {
+                Some(entity) => Some(entity),
+                None => {
+                    return Err(rusqlite::Error::FromSqlConversionFailure(
+                        x,
+                        rusqlite::types::Type::Text,
+                        format!("There is no entity named {name}").into(),
+                    ))
+                }
+            },
+            None => None,
+        };
+    }
+
+    Ok(())
+}
+
+/// Read the elapsed seconds stored in the given column into a `Stopwatch` field. The
+/// stopwatch is not paused, regardless of its state when it was stored.
+fn assign_stopwatch(field: &mut dyn Any, row: &Row, x: usize) -> rusqlite::Result<()> {
+    if let Some(f) = field.downcast_mut::<Stopwatch>() {
+        let seconds = row.get::<usize, f64>(x)?;
+        let elapsed = Duration::try_from_secs_f64(seconds).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(x, rusqlite::types::Type::Real, Box::new(e))
+        })?;
+
+        *f = Stopwatch::new();
+        f.set_elapsed(elapsed);
+    }
+
+    Ok(())
+}
+
+/// Read the remaining seconds stored in the given column into a `Timer` field. The timer is
+/// restarted with the remaining time as its duration and keeps its mode, so a cooldown
+/// continues where it was stored. Negative values are clamped to zero.
+fn assign_timer(field: &mut dyn Any, row: &Row, x: usize) -> rusqlite::Result<()> {
+    if let Some(f) = field.downcast_mut::<Timer>() {
+        let seconds = row.get::<usize, f64>(x)?.max(0.0);
+        let remaining = Duration::try_from_secs_f64(seconds).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(x, rusqlite::types::Type::Real, Box::new(e))
+        })?;
+
+        *f = Timer::new(remaining, f.mode());
+    }
+
+    Ok(())
+}
+
+/// Read the text stored in the given column into a `Cow<str>` field.
+fn assign_cow(field: &mut dyn Any, row: &Row, x: usize) -> rusqlite::Result<()> {
+    if let Some(f) = field.downcast_mut::<Cow<'static, str>>() {
+        *f = Cow::Owned(row.get::<usize, String>(x)?);
+    }
+
+    Ok(())
+}
+
+/// Read the blob stored in the given column and assign it to a field, which is either of type
+/// `D` or `Option<D>`. NULL is read as `None`.
+fn assign_from_blob<D: Any>(
+    field: &mut dyn Any,
+    row: &Row,
+    x: usize,
+    from_blob: fn(&[u8]) -> Result<D, String>,
+) -> rusqlite::Result<()> {
+    let read = |blob: Vec<u8>| {
+        from_blob(&blob).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(x, rusqlite::types::Type::Blob, e.into())
+        })
+    };
+
+    if let Some(f) = field.downcast_mut::<D>() {
+        *f = read(row.get::<usize, Vec<u8>>(x)?)?;
+    } else if let Some(f) = field.downcast_mut::<Option<D>>() {
+        *f = match row.get::<usize, Option<Vec<u8>>>(x)? {
+            Some(blob) => Some(read(blob)?),
+            None => None,
+        };
+    }
+
+    Ok(())
+}
+
+/// A stored direction has to be normalized already. Anything else indicates a corrupted value,
+/// so it is not silently normalized again.
+fn dir2_from_blob(blob: &[u8]) -> Result<Dir2, String> {
+    let v = Vec2::from_blob(blob);
+    if !v.is_normalized() {
+        return Err(format!("Stored direction {v} is not normalized"));
+    }
+
+    Ok(Dir2::new_unchecked(v))
+}
+
+fn dir3_from_blob(blob: &[u8]) -> Result<Dir3, String> {
+    let v = Vec3::from_blob(blob);
+    if !v.is_normalized() {
+        return Err(format!("Stored direction {v} is not normalized"));
+    }
+
+    Ok(Dir3::new_unchecked(v))
+}
+
+fn vec3a_from_blob(blob: &[u8]) -> Result<Vec3A, String> {
+    Ok(Vec3A::from(Vec3::from_blob(blob)))
+}
+
+/// The rotation of a 2d isometry is stored as the blob of a `Vec2` holding its cosine and sine.
+fn isometry2d_from_blob(blob: &[u8]) -> Result<Isometry2d, String> {
+    let rotation_len = Vec2::ZERO.into_blob().len();
+    let (rotation, translation) = split_blob(blob, rotation_len, rotation_len)?;
+    let rotation = Vec2::from_blob(rotation);
+    if !rotation.is_normalized() {
+        return Err(format!("Stored rotation {rotation} is not normalized"));
+    }
+
+    let rotation = Rot2::from_sin_cos(rotation.y, rotation.x);
+    Ok(Isometry2d::new(Vec2::from_blob(translation), rotation))
+}
+
+fn isometry3d_from_blob(blob: &[u8]) -> Result<Isometry3d, String> {
+    let rotation_len = Quat::IDENTITY.into_blob().len();
+    let translation_len = Vec3::ZERO.into_blob().len();
+    let (rotation, translation) = split_blob(blob, rotation_len, translation_len)?;
+    let rotation = Quat::from_blob(rotation);
+    if !rotation.is_normalized() {
+        return Err(format!("Stored rotation {rotation} is not normalized"));
+    }
+
+    Ok(Isometry3d::new(Vec3::from_blob(translation), rotation))
+}
+
+/// A `Mat3A` is stored like a `Mat3`, as the blobs of its three columns without padding.
+fn mat3a_from_blob(blob: &[u8]) -> Result<Mat3A, String> {
+    let [x, y, z] = split_columns(blob, Vec3::ZERO.into_blob().len())?;
+    Ok(Mat3A::from_cols(
+        Vec3A::from(Vec3::from_blob(x)),
+        Vec3A::from(Vec3::from_blob(y)),
+        Vec3A::from(Vec3::from_blob(z)),
+    ))
+}
+
+fn affine2_from_blob(blob: &[u8]) -> Result<Affine2, String> {
+    let [x, y, translation] = split_columns(blob, Vec2::ZERO.into_blob().len())?;
+    Ok(Affine2::from_cols(
+        Vec2::from_blob(x),
+        Vec2::from_blob(y),
+        Vec2::from_blob(translation),
+    ))
+}
+
+fn affine3a_from_blob(blob: &[u8]) -> Result<Affine3A, String> {
+    let [x, y, z, translation] = split_columns(blob, Vec3::ZERO.into_blob().len())?;
+    Ok(Affine3A::from_cols(
+        Vec3A::from(Vec3::from_blob(x)),
+        Vec3A::from(Vec3::from_blob(y)),
+        Vec3A::from(Vec3::from_blob(z)),
+        Vec3A::from(Vec3::from_blob(translation)),
+    ))
+}
+
+/// Split a blob into the blobs of `N` columns of the same length.
+fn split_columns<const N: usize>(blob: &[u8], column_len: usize) -> Result<[&[u8]; N], String> {
+    if blob.len() != N * column_len {
+        return Err(format!(
+            "Stored matrix has {} instead of {} bytes",
+            blob.len(),
+            N * column_len
+        ));
+    }
+
+    Ok(std::array::from_fn(|x| {
+        &blob[x * column_len..(x + 1) * column_len]
+    }))
+}
+
+/// Split a blob into the blob of the rotation and the blob of the translation of an isometry.
+fn split_blob(
+    blob: &[u8],
+    rotation_len: usize,
+    translation_len: usize,
+) -> Result<(&[u8], &[u8]), String> {
+    if blob.len() != rotation_len + translation_len {
+        return Err(format!(
+            "Stored isometry has {} instead of {} bytes",
+            blob.len(),
+            rotation_len + translation_len
+        ));
+    }
+
+    Ok(blob.split_at(rotation_len))
+}
+
+impl Plugin for SqliteDatabase {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BevyERMPlugin);
+
+        let mut database = SqliteDatabase::default();
+        database.set_type_registry(app.world().get_resource::<AppTypeRegistry>().cloned());
+
+        app.insert_resource(SqliteConnectionSettings::default());
+        app.insert_resource(database);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_busy, SqliteDatabase};
+    use crate::prelude::{
+        BoolAsText, Checksum, Collate, Discriminant, EntityNames, EulerAngles, Flatten, Inline,
+        IntegerEnum, MappingErrorPolicy, RelateByName, RetryPolicy, SqliteConnectionSettings,
+        SqliteDbError, TableSqlOptions, TaggedEnum,
+    };
+    use bevy::math::{Affine2, Affine3A, Isometry2d, Isometry3d, Mat3A, Vec3A};
+    use bevy::prelude::*;
+    use bevy::time::Stopwatch;
+    use bevy_erm::prelude::{ErmTypesRegistry, IntoBlob, Key, SqlType, TableDefinition};
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::net::{IpAddr, SocketAddr};
+    use std::ops::{Range, RangeInclusive};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Player {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        deaths: i32,
+        email: String,
+    }
+
+    fn setup() -> App {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Player>();
+
+        app
+    }
+
+    // Test 1
+    fn update_database_path_1(mut settings: ResMut<SqliteConnectionSettings>) {
+        settings.set_data_source("test_1.sqlite");
+    }
+    fn run_test_1(mut database: ResMut<SqliteDatabase>, settings: Res<SqliteConnectionSettings>) {
+        database.open(&settings).unwrap();
+        assert!(!database.table_exists("Player").unwrap());
+
+        let rows = database
+            .execute(
+                "
+            CREATE TABLE Player 
+            (
+                id INTEGER PRIMARY KEY, 
+                name TEXT NOT NULL, 
+                deaths INTEGER NOT NULL DEFAULT 0
+            );",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(rows, 0);
+        assert!(database.table_exists("Player").unwrap());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+        assert!(matches!(
+            database.table_exists("Player"),
+            Err(SqliteDbError::NotOpen)
+        ));
+    }
+
+    #[test]
+    fn test_database_connection() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_1);
+        app.add_systems(Startup, run_test_1);
+
+        app.update();
+    }
+
+    // Test 2
+    fn update_database_path_2(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_2.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+    fn run_test_2(
+        registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+        assert!(!database.table_exists("Player").unwrap());
+
+        assert!(registry.get_table_definition("Player").is_some());
+        assert!(database
+            .create_table(registry.get_table_definition("Player").unwrap())
+            .is_ok());
+
+        assert!(database.table_exists("Player").unwrap());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_simple_table_generation() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_2);
+        app.add_systems(Startup, run_test_2);
+
+        app.update();
+    }
+
+    // Test 3
+    fn update_database_path_3(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_3.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn insert_player(
+        table: &TableDefinition,
+        registry: &AppTypeRegistry,
+        database: &mut SqliteDatabase,
+        deaths: i32,
+        name: &str,
+        email: &str,
+    ) {
+        let test = Player {
+            deaths,
+            name: name.to_string(),
+            email: email.to_string(),
+
+            ..Default::default()
+        };
+
+        match database.insert(table, &test, registry) {
+            Ok(_) => {}
+            Err(e) => {
+                println!("Could not insert item: {}", e);
+                panic!("Could not insert item: {}", e);
+            }
+        }
+    }
+
+    fn run_test_3(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        assert!(erm_registry.get_table_definition("Player").is_some());
+        assert!(database
+            .create_table(erm_registry.get_table_definition("Player").unwrap())
+            .is_ok());
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            30,
+            "Anne Straße",
+            "test_2@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            100,
+            "Timo Beil",
+            "test_3@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            24,
+            "Rainer Szuvall",
+            "test_4@testen.com",
+        );
+
+        let test: Vec<Player> = database
+            .query(
+                table,
+                "SELECT * FROM 'Player' WHERE name LIKE 'Timo%';",
+                &[],
+            )
+            .unwrap();
+        assert!(!test.is_empty());
+        assert_eq!(test[0].deaths, 100);
+        assert_eq!(test[0].name, "Timo Beil".to_string());
+        assert_eq!(test[0].email, "test_3@testen.com".to_string());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_insert_item() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_3);
+        app.add_systems(Startup, run_test_3);
+
+        app.update();
+    }
+
+    #[test]
+    fn test_open_retries_only_busy_errors() {
+        let busy = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        );
+        let locked = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_LOCKED),
+            None,
+        );
+        let cant_open = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+            None,
+        );
+        assert!(is_busy(&busy));
+        assert!(is_busy(&locked));
+        assert!(!is_busy(&cant_open));
+
+        // A missing directory does not go away by waiting.
+        let mut settings = SqliteConnectionSettings::default();
+        settings.set_data_source("does/not/exist/test_4.sqlite");
+        settings.set_retry_policy(Some(RetryPolicy::new(3, Duration::from_millis(200))));
+
+        let mut database = SqliteDatabase::default();
+        let start = Instant::now();
+        let result = database.open(&settings);
+
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("Could not open database connection"));
+    }
+
+    #[test]
+    fn test_open_poisoned() {
+        let database = SqliteDatabase::default();
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = database.connection.lock().unwrap();
+            panic!("Poison the connection.");
+        }));
+
+        let mut database = database;
+        let mut settings = SqliteConnectionSettings::default();
+        settings.use_in_memory(true);
+        assert!(matches!(
+            database.open(&settings),
+            Err(SqliteDbError::MutexPoisoned)
+        ));
+    }
+
+    // Test 5
+    fn update_database_path_5(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_5.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_5(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            30,
+            "Anne Straße",
+            "test_2@testen.com",
+        );
+
+        let mut buffer: Vec<Player> = Vec::with_capacity(8);
+        database
+            .query_into(table, "SELECT * FROM 'Player';", &[], &mut buffer)
+            .unwrap();
+        assert_eq!(buffer.len(), 2);
+
+        database
+            .query_into(
+                table,
+                "SELECT * FROM 'Player' WHERE deaths > 20;",
+                &[],
+                &mut buffer,
+            )
+            .unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer[0].name, "Anne Straße".to_string());
+        assert!(buffer.capacity() >= 8);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_into() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_5);
+        app.add_systems(Startup, run_test_5);
+
+        app.update();
+    }
+
+    // Test 6
+    fn update_database_path_6(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_6.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_6(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+
+        database.execute("BEGIN;", &[]).unwrap();
+        let failed = database.savepoint("record_1", |db| {
+            insert_player(
+                table,
+                &registry,
+                db,
+                10,
+                "Runna vom Sofa",
+                "test_1@testen.com",
+            );
+            Err::<(), SqliteDbError>("Invalid record".into())
+        });
+        assert_eq!(failed.unwrap_err().to_string(), "Invalid record");
+
+        let succeeded = database.savepoint("record_2", |db| {
+            insert_player(table, &registry, db, 30, "Anne Straße", "test_2@testen.com");
+            Ok(())
+        });
+        assert!(succeeded.is_ok());
+        database.execute("COMMIT;", &[]).unwrap();
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM 'Player';", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Anne Straße".to_string());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_savepoint_rollback() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_6);
+        app.add_systems(Startup, run_test_6);
+
+        app.update();
+    }
+
+    // Test 7
+    fn update_database_path_7(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_7.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_7(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let mut options = TableSqlOptions::new();
+        options.use_strict(true);
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.ends_with(") STRICT;"));
+
+        database.create_table_with_options(table, &options).unwrap();
+        assert!(database
+            .execute(
+                "INSERT INTO Player (name, deaths, email) VALUES ('Timo Beil', 'many', 'test_3@testen.com');",
+                &[],
+            )
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_strict_table() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_7);
+        app.add_systems(Startup, run_test_7);
+
+        app.update();
+    }
+
+    // Test 8
+    fn update_database_path_8(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_8.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_8(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database
+            .execute(
+                "CREATE TABLE Player (id INTEGER PRIMARY KEY, name TEXT NOT NULL, deaths INTEGER NOT NULL DEFAULT 7, email TEXT NOT NULL DEFAULT 'unknown');",
+                &[],
+            )
+            .unwrap();
+
+        let player = Player {
+            name: "Timo Beil".to_string(),
+            ..Default::default()
+        };
+        database
+            .insert_set(table, &player, &["name"], &registry)
+            .unwrap();
+        assert!(database
+            .insert_set(table, &player, &["level"], &registry)
+            .is_err());
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM 'Player';", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Timo Beil".to_string());
+        assert_eq!(test[0].deaths, 7);
+        assert_eq!(test[0].email, "unknown".to_string());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_insert_set() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_8);
+        app.add_systems(Startup, run_test_8);
+
+        app.update();
+    }
+
+    #[test]
+    fn test_parse_text_columns() {
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+
+        let ip = connection
+            .query_row("SELECT '127.0.0.1';", [], |row| {
+                super::parse_text::<IpAddr>(row, 0)
+            })
+            .unwrap();
+        assert_eq!(ip, IpAddr::from([127, 0, 0, 1]));
+
+        let socket = connection
+            .query_row("SELECT '[::1]:8080';", [], |row| {
+                super::parse_text::<SocketAddr>(row, 0)
+            })
+            .unwrap();
+        assert_eq!(socket.port(), 8080);
+
+        let mut field: Option<IpAddr> = Some(ip);
+        connection
+            .query_row("SELECT NULL;", [], |row| {
+                super::assign_parsed::<IpAddr>(&mut field, row, 0)
+            })
+            .unwrap();
+        assert_eq!(field, None);
+
+        assert!(connection
+            .query_row(
+                "SELECT 'localhost';",
+                [],
+                |row| super::parse_text::<IpAddr>(row, 0)
+            )
+            .is_err());
+    }
+
+    // Test 9
+    fn update_database_path_9(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_9.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_9(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            30,
+            "Anne Straße",
+            "test_2@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            100,
+            "Timo Beil",
+            "test_3@testen.com",
+        );
+
+        // Corrupt the second row.
+        database
+            .execute("UPDATE Player SET deaths = 'many' WHERE id = 2;", &[])
+            .unwrap();
+
+        let query = "SELECT * FROM 'Player' ORDER BY id;";
+        assert!(database
+            .query_with_policy::<Player>(table, query, &[], MappingErrorPolicy::FailFast)
+            .is_err());
+
+        let result = database
+            .query_with_policy::<Player>(table, query, &[], MappingErrorPolicy::SkipAndCollect)
+            .unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[1].name, "Timo Beil".to_string());
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].row, 1);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_mapping_error_policy() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_9);
+        app.add_systems(Startup, run_test_9);
+
+        app.update();
+    }
+
+    // Test 10
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Node {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        parent: Option<Entity>,
+    }
+
+    fn update_database_path_10(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_10.sqlite");
+        registry.register_type::<Node>(&app_registry);
+    }
+
+    fn run_test_10(
+        mut commands: Commands,
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Node").unwrap();
+        database.create_table(table).unwrap();
+
+        let root = commands.spawn_empty().id();
+        let nodes = [
+            Node {
+                name: "Root".to_string(),
+                parent: None,
+                ..Default::default()
+            },
+            Node {
+                name: "Child".to_string(),
+                parent: Some(root),
+                ..Default::default()
+            },
+        ];
+        for node in nodes.iter() {
+            database.insert(table, node, &registry).unwrap();
+        }
+
+        let test: Vec<Node> = database
+            .query(table, "SELECT * FROM 'Node' ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].parent, None);
+        assert_eq!(test[1].name, "Child".to_string());
+        assert_eq!(test[1].parent, Some(root));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_optional_entity() {
+        let mut app = setup();
+        app.register_type::<Node>();
+        app.add_systems(PreStartup, update_database_path_10);
+        app.add_systems(Startup, run_test_10);
+
+        app.update();
+    }
+
+    // Test 11
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct PlayerLevel {
+        id: i32,
+        name: String,
+        level: i64,
+    }
+
+    fn update_database_path_11(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_11.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_11(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "{\"level\": 12}",
+        );
+
+        let test: Vec<PlayerLevel> = database
+            .query(
+                table,
+                "SELECT id, name, json_extract(email, '$.level') AS level FROM 'Player';",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Runna vom Sofa".to_string());
+        assert_eq!(test[0].level, 12);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_json_extract() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_11);
+        app.add_systems(Startup, run_test_11);
+
+        app.update();
+    }
+
+    // Test 12
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Guild {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    /// The same type after a new field was added, but without re-registering it.
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct GuildWithMotto {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        motto: String,
+    }
+
+    fn register_types_12(
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        registry.register_type::<Guild>(&app_registry);
+    }
+
+    fn run_test_12(erm_registry: Res<ErmTypesRegistry>) {
+        let table = erm_registry.get_table_definition("Guild").unwrap();
+
+        let guild = Guild::default();
+        assert!(SqliteDatabase::check_fields(table, &guild).is_ok());
+
+        let guild = GuildWithMotto::default();
+        let error = SqliteDatabase::check_fields(table, &guild).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Field motto has no column in table Guild."));
+    }
+
+    #[test]
+    fn test_strict_insert_check() {
+        let mut app = setup();
+        app.register_type::<Guild>();
+        app.add_systems(PreStartup, register_types_12);
+        app.add_systems(Startup, run_test_12);
+
+        app.update();
+    }
+
+    // Test 13
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Account {
+        #[reflect(@Key)]
+        id: i32,
+        #[reflect(@Collate::NoCase)]
+        name: String,
+    }
+
+    fn update_database_path_13(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_13.sqlite");
+        registry.register_type::<Account>(&app_registry);
+    }
+
+    fn run_test_13(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Account").unwrap();
+        let options = TableSqlOptions::for_type::<Account>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("name TEXT NOT NULL COLLATE NOCASE"));
+
+        database.create_table_with_options(table, &options).unwrap();
+        database
+            .execute("CREATE UNIQUE INDEX account_name ON Account(name);", &[])
+            .unwrap();
+
+        let account = Account {
+            name: "Timo".to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &account, &registry).unwrap();
+
+        let account = Account {
+            name: "TIMO".to_string(),
+            ..Default::default()
+        };
+        assert!(database.insert(table, &account, &registry).is_err());
+
+        let test: Vec<Account> = database
+            .query(table, "SELECT * FROM Account WHERE name = 'tImO';", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_collate_nocase() {
+        let mut app = setup();
+        app.register_type::<Account>();
+        app.add_systems(PreStartup, update_database_path_13);
+        app.add_systems(Startup, run_test_13);
+
+        app.update();
+    }
+
+    // Test 14
+    fn update_database_path_14(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_14.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_14(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(table, &registry, &mut database, 3, "Timo", "timo@test.de");
+        insert_player(table, &registry, &mut database, 0, "Bert", "bert@test.de");
+        insert_player(table, &registry, &mut database, 7, "Anna", "anna@test.de");
+
+        database
+            .create_view(
+                "DeadPlayers",
+                "SELECT * FROM Player WHERE deaths > 0 ORDER BY deaths DESC;",
+            )
+            .unwrap();
+        // Creating the view a second time is fine.
+        database
+            .create_view("DeadPlayers", "SELECT * FROM Player WHERE deaths > 0")
+            .unwrap();
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM DeadPlayers;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].name, "Anna");
+        assert_eq!(test[1].name, "Timo");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_create_view() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_14);
+        app.add_systems(Startup, run_test_14);
+
+        app.update();
+    }
+
+    // Test 15
+    #[derive(Reflect)]
+    #[reflect(Default)]
+    struct Unit {
+        #[reflect(@Key)]
+        id: i32,
+        facing: Dir3,
+        aim: Option<Dir2>,
+    }
+
+    impl Default for Unit {
+        fn default() -> Self {
+            Unit {
+                id: 0,
+                facing: Dir3::Z,
+                aim: None,
+            }
+        }
+    }
+
+    fn update_database_path_15(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_15.sqlite");
+        registry.register_type::<Unit>(&app_registry);
+    }
+
+    fn run_test_15(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Unit").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("facing BLOB"));
+        assert!(sql.contains("aim BLOB"));
+        database.create_table(table).unwrap();
+
+        let unit = Unit {
+            facing: Dir3::new(Vec3::new(1.0, 1.0, 0.0)).unwrap(),
+            aim: Some(Dir2::NEG_Y),
+            ..Default::default()
+        };
+        database.insert(table, &unit, &registry).unwrap();
+        database.insert(table, &Unit::default(), &registry).unwrap();
+
+        let test: Vec<Unit> = database
+            .query(table, "SELECT * FROM Unit ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].facing, unit.facing);
+        assert_eq!(test[0].aim, Some(Dir2::NEG_Y));
+        assert_eq!(test[1].facing, Dir3::Z);
+        assert_eq!(test[1].aim, None);
+
+        // A vector which is not normalized can not be read as a direction.
+        let corrupted = Vec3::new(2.0, 0.0, 0.0).into_blob();
+        database
+            .execute("UPDATE Unit SET facing = ?1 WHERE id = 1;", &[&corrupted])
+            .unwrap();
+        assert!(database
+            .query::<Unit>(table, "SELECT * FROM Unit;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_directions() {
+        let mut app = setup();
+        app.register_type::<Unit>();
+        app.add_systems(PreStartup, update_database_path_15);
+        app.add_systems(Startup, run_test_15);
+
+        app.update();
+    }
+
+    // Test 16
+    #[derive(Default, Reflect, Debug, PartialEq)]
+    #[reflect(@IntegerEnum)]
+    #[repr(u8)]
+    enum Class {
+        #[default]
+        Warrior,
+        Rogue,
+        #[reflect(@Discriminant(10))]
+        Mage = 10,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Hero {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        class: Class,
+    }
+
+    fn update_database_path_16(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_16.sqlite");
+        registry.register_type::<Hero>(&app_registry);
+    }
+
+    fn run_test_16(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Hero").unwrap();
+        let options = TableSqlOptions::for_type::<Hero>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("class INTEGER NOT NULL"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        for (name, class) in [
+            ("Timo", Class::Mage),
+            ("Bert", Class::Warrior),
+            ("Anna", Class::Rogue),
+        ] {
+            let hero = Hero {
+                name: name.to_string(),
+                class,
+                ..Default::default()
+            };
+            database.insert(table, &hero, &registry).unwrap();
+        }
+
+        let stored: Vec<i64> = database
+            .query_column("SELECT class FROM Hero ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(stored, vec![10, 0, 1]);
+
+        let test: Vec<Hero> = database
+            .query(table, "SELECT * FROM Hero ORDER BY class;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 3);
+        assert_eq!(test[0].class, Class::Warrior);
+        assert_eq!(test[1].class, Class::Rogue);
+        assert_eq!(test[2].class, Class::Mage);
+        assert_eq!(test[2].name, "Timo");
+
+        // A discriminant without a variant can not be read.
+        database
+            .execute("UPDATE Hero SET class = 5 WHERE id = 1;", &[])
+            .unwrap();
+        assert!(database
+            .query::<Hero>(table, "SELECT * FROM Hero;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_integer_enum() {
+        let mut app = setup();
+        app.register_type::<Hero>();
+        app.add_systems(PreStartup, update_database_path_16);
+        app.add_systems(Startup, run_test_16);
+
+        app.update();
+    }
+
+    // Test 17
+    fn update_database_path_17(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_17.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_17(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(table, &registry, &mut database, 3, "Timo", "timo@test.de");
+        insert_player(table, &registry, &mut database, 0, "Bert", "bert@test.de");
+
+        let players = vec![
+            Player {
+                id: 1,
+                name: "Timo".to_string(),
+                deaths: 4,
+                email: "timo@test.de".to_string(),
+            },
+            Player {
+                id: 3,
+                name: "Anna".to_string(),
+                deaths: 1,
+                email: "anna@test.de".to_string(),
+            },
+        ];
+        let count = database.bulk_upsert(table, &players, &registry).unwrap();
+        assert_eq!(count, 2);
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM Player ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 3);
+        assert_eq!(test[0].deaths, 4);
+        assert_eq!(test[1].name, "Bert");
+        assert_eq!(test[2].name, "Anna");
+
+        assert_eq!(
+            database
+                .bulk_upsert::<Player>(table, &[], &registry)
+                .unwrap(),
+            0
+        );
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_bulk_upsert() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_17);
+        app.add_systems(Startup, run_test_17);
+
+        app.update();
+    }
+
+    // Test 18
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Member {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        #[reflect(@BoolAsText)]
+        active: bool,
+        admin: bool,
+    }
+
+    fn update_database_path_18(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_18.sqlite");
+        registry.register_type::<Member>(&app_registry);
+    }
+
+    fn run_test_18(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Member").unwrap();
+        let options = TableSqlOptions::for_type::<Member>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("active TEXT NOT NULL CHECK(active IN ('true', 'false'))"));
+        assert!(sql.contains("admin INTEGER NOT NULL"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        let member = Member {
+            name: "Timo".to_string(),
+            active: true,
+            admin: true,
+            ..Default::default()
+        };
+        database.insert(table, &member, &registry).unwrap();
+        let member = Member {
+            name: "Bert".to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &member, &registry).unwrap();
+
+        let stored: Vec<String> = database
+            .query_column("SELECT active FROM Member ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(stored, vec!["true", "false"]);
+
+        let test: Vec<Member> = database
+            .query(table, "SELECT * FROM Member ORDER BY id;", &[])
+            .unwrap();
+        assert!(test[0].active);
+        assert!(test[0].admin);
+        assert!(!test[1].active);
+        assert!(!test[1].admin);
+
+        // Other values are rejected by the constraint.
+        assert!(database
+            .execute("UPDATE Member SET active = 'yes';", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_bool_as_text() {
+        let mut app = setup();
+        app.register_type::<Member>();
+        app.add_systems(PreStartup, update_database_path_18);
+        app.add_systems(Startup, run_test_18);
+
+        app.update();
+    }
+
+    // Test 19
+    fn update_database_path_19(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_19.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_19(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(table, &registry, &mut database, 3, "Timo", "timo@test.de");
+        insert_player(table, &registry, &mut database, 7, "Bert", "bert@test.de");
+
+        let deaths: Option<i32> = database
+            .query_scalar_where(table, "deaths", "id = ?", &[&2])
+            .unwrap();
+        assert_eq!(deaths, Some(7));
+
+        let name: Option<String> = database
+            .query_scalar_where(table, "name", "deaths < ?1;", &[&5])
+            .unwrap();
+        assert_eq!(name, Some("Timo".to_string()));
+
+        let missing: Option<i32> = database
+            .query_scalar_where(table, "deaths", "id = ?", &[&9])
+            .unwrap();
+        assert_eq!(missing, None);
+
+        assert!(database
+            .query_scalar_where::<i32>(table, "level", "id = ?", &[&1])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_scalar_where() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_19);
+        app.add_systems(Startup, run_test_19);
+
+        app.update();
+    }
+
+    // Test 20
+    fn update_database_path_20(mut settings: ResMut<SqliteConnectionSettings>) {
+        settings.set_data_source("test_20.sqlite");
+    }
+
+    fn run_test_20(mut database: ResMut<SqliteDatabase>, settings: Res<SqliteConnectionSettings>) {
+        database.open(&settings).unwrap();
+
+        let count: Vec<i64> = database
+            .with_timeout(Duration::from_secs(5), |db| {
+                db.query_column("SELECT 1 + 1;", &[])
+            })
+            .unwrap();
+        assert_eq!(count, vec![2]);
+
+        let start = Instant::now();
+        let result: Result<Vec<i64>, SqliteDbError> =
+            database.with_timeout(Duration::from_millis(50), |db| {
+                db.query_column(
+                    "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+                     SELECT max(x) FROM c;",
+                    &[],
+                )
+            });
+        assert!(matches!(
+            result,
+            Err(SqliteDbError::Timeout(timeout)) if timeout == Duration::from_millis(50)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // The connection is usable after the interrupt.
+        let count: Vec<i64> = database.query_column("SELECT 2 + 2;", &[]).unwrap();
+        assert_eq!(count, vec![4]);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_20);
+        app.add_systems(Startup, run_test_20);
+
+        app.update();
+    }
+
+    // Test 21
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Room {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        #[reflect(@RelateByName)]
+        building: Option<Entity>,
+    }
+
+    fn update_database_path_21(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_21.sqlite");
+        registry.register_type::<Room>(&app_registry);
+    }
+
+    fn run_test_21(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Room").unwrap();
+        let options = TableSqlOptions::for_type::<Room>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("building TEXT"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        let castle = Entity::from_raw(10);
+        let mut names = EntityNames::new();
+        names.insert(castle, &Name::new("Castle")).unwrap();
+        database.set_entity_names(names);
+
+        let room = Room {
+            name: "Hall".to_string(),
+            building: Some(castle),
+            ..Default::default()
+        };
+        database.insert(table, &room, &registry).unwrap();
+        let room = Room {
+            name: "Yard".to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &room, &registry).unwrap();
+
+        // Entities without a name can not be stored.
+        let room = Room {
+            name: "Cellar".to_string(),
+            building: Some(Entity::from_raw(11)),
+            ..Default::default()
+        };
+        assert!(database.insert(table, &room, &registry).is_err());
+
+        let stored: Vec<Option<String>> = database
+            .query_column("SELECT building FROM Room ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(stored, vec![Some("Castle".to_string()), None]);
+
+        // In the next session the castle is a different entity.
+        let castle = Entity::from_raw(42);
+        database.get_entity_names_mut().clear();
+        database
+            .get_entity_names_mut()
+            .insert(castle, &Name::new("Castle"))
+            .unwrap();
+
+        let test: Vec<Room> = database
+            .query(table, "SELECT * FROM Room ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].building, Some(castle));
+        assert_eq!(test[1].building, None);
+
+        // Unknown names can not be resolved.
+        database.get_entity_names_mut().clear();
+        assert!(database
+            .query::<Room>(table, "SELECT * FROM Room;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_relate_by_name() {
+        let mut app = setup();
+        app.register_type::<Room>();
+        app.add_systems(PreStartup, update_database_path_21);
+        app.add_systems(Startup, run_test_21);
+
+        app.update();
+    }
+
+    // Test 22
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct SaveGame {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        gold: i32,
+        #[reflect(@Checksum)]
+        checksum: i64,
+    }
+
+    fn update_database_path_22(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_22.sqlite");
+        registry.register_type::<SaveGame>(&app_registry);
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_22(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("SaveGame").unwrap();
+        database.create_table(table).unwrap();
+
+        for (name, gold) in [("Timo", 100), ("Bert", 20)] {
+            let save = SaveGame {
+                name: name.to_string(),
+                gold,
+                ..Default::default()
+            };
+            database.insert(table, &save, &registry).unwrap();
+        }
+
+        let test: Vec<SaveGame> = database
+            .query(table, "SELECT * FROM SaveGame ORDER BY id;", &[])
+            .unwrap();
+        assert_ne!(test[0].checksum, 0);
+        assert_ne!(test[0].checksum, test[1].checksum);
+        assert!(database.verify_row(table, &test[0], &registry).unwrap());
+        assert!(database.verify_row(table, &test[1], &registry).unwrap());
+
+        // Edit a row without updating its checksum.
+        database
+            .execute("UPDATE SaveGame SET gold = 9999 WHERE id = 1;", &[])
+            .unwrap();
+        let test: Vec<SaveGame> = database
+            .query(table, "SELECT * FROM SaveGame ORDER BY id;", &[])
+            .unwrap();
+        assert!(!database.verify_row(table, &test[0], &registry).unwrap());
+        assert!(database.verify_row(table, &test[1], &registry).unwrap());
+
+        // Types without a checksum can not be verified.
+        let players = erm_registry.get_table_definition("Player").unwrap();
+        assert!(database
+            .verify_row(players, &Player::default(), &registry)
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut app = setup();
+        app.register_type::<SaveGame>();
+        app.add_systems(PreStartup, update_database_path_22);
+        app.add_systems(Startup, run_test_22);
+
+        app.update();
+    }
+
+    // Test 23
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Waypoint {
+        #[reflect(@Key)]
+        id: i32,
+        #[reflect(@Flatten)]
+        pos: Vec3,
+        #[reflect(@Flatten)]
+        offset: Vec2,
+        scale: Vec3,
+    }
+
+    fn update_database_path_23(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_23.sqlite");
+        registry.register_type::<Waypoint>(&app_registry);
+    }
+
+    fn run_test_23(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Waypoint").unwrap();
+        let options = TableSqlOptions::for_type::<Waypoint>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("pos_x REAL NOT NULL,\npos_y REAL NOT NULL,\npos_z REAL NOT NULL"));
+        assert!(sql.contains("offset_y REAL NOT NULL"));
+        assert!(sql.contains("scale BLOB"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        for (x, scale) in [(50.0, 1.0), (150.0, 2.0)] {
+            let waypoint = Waypoint {
+                pos: Vec3::new(x, 2.0, -3.5),
+                offset: Vec2::new(0.5, x),
+                scale: Vec3::splat(scale),
+                ..Default::default()
+            };
+            database.insert(table, &waypoint, &registry).unwrap();
+        }
+
+        let test: Vec<Waypoint> = database
+            .query(table, "SELECT * FROM Waypoint WHERE pos_x > 100;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].pos, Vec3::new(150.0, 2.0, -3.5));
+        assert_eq!(test[0].offset, Vec2::new(0.5, 150.0));
+        assert_eq!(test[0].scale, Vec3::splat(2.0));
+
+        let waypoint = Waypoint {
+            pos: Vec3::ONE,
+            ..Default::default()
+        };
+        database
+            .insert_set(table, &waypoint, &["pos", "offset", "scale"], &registry)
+            .unwrap();
+        let z: Vec<f32> = database
+            .query_column("SELECT pos_z FROM Waypoint WHERE id = 3;", &[])
+            .unwrap();
+        assert_eq!(z, vec![1.0]);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_flatten() {
+        let mut app = setup();
+        app.register_type::<Waypoint>();
+        app.add_systems(PreStartup, update_database_path_23);
+        app.add_systems(Startup, run_test_23);
+
+        app.update();
+    }
+
+    // Test 24
+    #[test]
+    fn test_open_path() {
+        let path = std::path::Path::new("test_24.sqlite");
+        let mut database = SqliteDatabase::open_path(path).unwrap();
+
+        database
+            .execute(
+                "CREATE TABLE Score (id INTEGER PRIMARY KEY, points INTEGER);",
+                &[],
+            )
+            .unwrap();
+        database
+            .execute("INSERT INTO Score (points) VALUES (?1), (?2);", &[&5, &8])
+            .unwrap();
+        let points: Vec<i32> = database
+            .query_column("SELECT points FROM Score ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(points, vec![5, 8]);
+        database.close().unwrap();
+
+        // The data is persisted in the file.
+        let mut database = SqliteDatabase::open_path(path).unwrap();
+        assert!(database.table_exists("Score").unwrap());
+        database.close().unwrap();
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(path).unwrap();
+    }
+
+    // Test 25
+    fn update_database_path_25(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_25.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_25(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(table, &registry, &mut database, 3, "Timo", "timo@test.de");
+        insert_player(table, &registry, &mut database, 7, "Bert", "bert@test.de");
+        insert_player(table, &registry, &mut database, 9, "Anna", "anna@test.de");
+
+        let count = database
+            .update_where(
+                table,
+                &[("deaths", &0), ("email", &"")],
+                "deaths > ? AND name <> ?",
+                &[&5, &"Anna"],
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM Player ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].deaths, 3);
+        assert_eq!(test[1].deaths, 0);
+        assert_eq!(test[1].email, "");
+        assert_eq!(test[2].deaths, 9);
+
+        // Without a where clause, all rows are updated.
+        let count = database
+            .update_where(table, &[("deaths", &1)], "", &[])
+            .unwrap();
+        assert_eq!(count, 3);
+
+        assert!(database
+            .update_where(table, &[("level", &1)], "", &[])
+            .is_err());
+        assert!(database.update_where(table, &[], "", &[]).is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_update_where() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_25);
+        app.add_systems(Startup, run_test_25);
+
+        app.update();
+    }
+
+    // Test 26
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Profile {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        playtime: Stopwatch,
+    }
+
+    fn update_database_path_26(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_26.sqlite");
+        registry.register_type::<Profile>(&app_registry);
+    }
+
+    fn run_test_26(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Profile").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("playtime REAL NOT NULL"));
+        database.create_table(table).unwrap();
+
+        let mut profile = Profile {
+            name: "Timo".to_string(),
+            ..Default::default()
+        };
+        profile.playtime.tick(Duration::from_millis(90_500));
+        database.insert(table, &profile, &registry).unwrap();
+
+        let stored: Vec<f64> = database
+            .query_column("SELECT playtime FROM Profile;", &[])
+            .unwrap();
+        assert_eq!(stored, vec![90.5]);
+
+        let test: Vec<Profile> = database
+            .query(table, "SELECT * FROM Profile;", &[])
+            .unwrap();
+        assert_eq!(test[0].playtime.elapsed(), Duration::from_millis(90_500));
+
+        // Negative durations can not be read.
+        database
+            .execute("UPDATE Profile SET playtime = -1.0;", &[])
+            .unwrap();
+        assert!(database
+            .query::<Profile>(table, "SELECT * FROM Profile;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_stopwatch() {
+        let mut app = setup();
+        app.register_type::<Profile>();
+        app.add_systems(PreStartup, update_database_path_26);
+        app.add_systems(Startup, run_test_26);
+
+        app.update();
+    }
+
+    // Test 27
+    fn update_database_path_27(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_27.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_27(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(table, &registry, &mut database, 3, "Timo", "timo@test.de");
+
+        // An existing row is returned without calling the factory.
+        let player: Player = database
+            .find_or_create(table, &1, || panic!("Row exists"), &registry)
+            .unwrap();
+        assert_eq!(player.name, "Timo");
+
+        // A missing row is created with the given key.
+        let player: Player = database
+            .find_or_create(
+                table,
+                &42,
+                || Player {
+                    name: "Bert".to_string(),
+                    ..Default::default()
+                },
+                &registry,
+            )
+            .unwrap();
+        assert_eq!(player.id, 42);
+        assert_eq!(player.name, "Bert");
+
+        let player: Player = database
+            .find_or_create(table, &42, Player::default, &registry)
+            .unwrap();
+        assert_eq!(player.name, "Bert");
+
+        let count: Vec<i32> = database
+            .query_column("SELECT Count(*) FROM Player;", &[])
+            .unwrap();
+        assert_eq!(count, vec![2]);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_find_or_create() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_27);
+        app.add_systems(Startup, run_test_27);
+
+        app.update();
+    }
+
+    // Test 28
+    #[derive(Reflect)]
+    #[reflect(Default)]
+    struct Weapon {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        damage: RangeInclusive<i32>,
+        reach: Range<f32>,
+    }
+
+    impl Default for Weapon {
+        fn default() -> Self {
+            Weapon {
+                id: 0,
+                name: String::new(),
+                damage: 0..=0,
+                reach: 0.0..0.0,
+            }
+        }
+    }
+
+    fn update_database_path_28(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_28.sqlite");
+        registry.register_type::<Weapon>(&app_registry);
+    }
+
+    fn run_test_28(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Weapon").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("damage_min INTEGER NOT NULL,\ndamage_max INTEGER NOT NULL"));
+        assert!(sql.contains("reach_min REAL NOT NULL,\nreach_max REAL NOT NULL"));
+        database.create_table(table).unwrap();
+
+        for (name, damage, reach) in [("Sword", 10..=20, 0.0..1.5), ("Spear", 8..=12, 1.0..3.0)] {
+            let weapon = Weapon {
+                name: name.to_string(),
+                damage,
+                reach,
+                ..Default::default()
+            };
+            database.insert(table, &weapon, &registry).unwrap();
+        }
+
+        let test: Vec<Weapon> = database
+            .query(table, "SELECT * FROM Weapon WHERE damage_max > 15;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Sword");
+        assert_eq!(test[0].damage, 10..=20);
+        assert_eq!(test[0].reach, 0.0..1.5);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut app = setup();
+        app.register_type::<Weapon>();
+        app.add_systems(PreStartup, update_database_path_28);
+        app.add_systems(Startup, run_test_28);
+
+        app.update();
+    }
+
+    // Test 29
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Friendly;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Npc {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        marker: (),
+        faction: Friendly,
+    }
+
+    fn update_database_path_29(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_29.sqlite");
+        registry.register_type::<Npc>(&app_registry);
+    }
+
+    fn run_test_29(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Npc").unwrap();
+        let options = TableSqlOptions::for_type::<Npc>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(!sql.contains("marker"));
+        assert!(!sql.contains("faction"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        let npc = Npc {
+            name: "Merchant".to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &npc, &registry).unwrap();
+        database
+            .insert_set(table, &npc, &["name", "marker", "faction"], &registry)
+            .unwrap();
+
+        let test: Vec<Npc> = database.query(table, "SELECT * FROM Npc;", &[]).unwrap();
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[1].name, "Merchant");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_zero_sized_fields() {
+        let mut app = setup();
+        app.register_type::<Npc>();
+        app.add_systems(PreStartup, update_database_path_29);
+        app.add_systems(Startup, run_test_29);
+
+        app.update();
+    }
+
+    // Test 30
+    fn update_database_path_30(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_30.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_30(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            30,
+            "Anne Straße",
+            "test_2@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            50,
+            "Karl Kopf",
+            "test_3@testen.com",
+        );
+
+        database.set_max_rows(Some(2));
+        assert_eq!(database.get_max_rows(), Some(2));
+        let result: Result<Vec<Player>, SqliteDbError> =
+            database.query(table, "SELECT * FROM 'Player';", &[]);
+        assert!(matches!(result, Err(SqliteDbError::RowLimitExceeded(2))));
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM 'Player' WHERE deaths > 20;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 2);
+
+        database.set_max_rows(None);
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM 'Player';", &[])
+            .unwrap();
+        assert_eq!(test.len(), 3);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_max_rows() {
+        let mut app = setup();
+        app.register_type::<Player>();
+        app.add_systems(PreStartup, update_database_path_30);
+        app.add_systems(Startup, run_test_30);
+
+        app.update();
+    }
+
+    // Test 31
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Banner {
+        #[reflect(@Key)]
+        id: i32,
+        motto: Arc<String>,
+        size: Arc<u16>,
+        visible: Arc<bool>,
+    }
+
+    fn update_database_path_31(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_31.sqlite");
+        registry.register_type::<Banner>(&app_registry);
+    }
+
+    fn run_test_31(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Banner").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("motto TEXT NOT NULL"));
+        assert!(sql.contains("size INTEGER NOT NULL CHECK(size >= 0)"));
+        database.create_table(table).unwrap();
+
+        let banner = Banner {
+            motto: Arc::new("Winter is coming".to_string()),
+            size: Arc::new(3),
+            visible: Arc::new(true),
+            ..Default::default()
+        };
+        database.insert(table, &banner, &registry).unwrap();
+
+        let test: Vec<Banner> = database
+            .query(table, "SELECT * FROM Banner WHERE size = 3;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(*test[0].motto, "Winter is coming");
+        assert_eq!(*test[0].size, 3);
+        assert!(*test[0].visible);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_shared_values() {
+        let mut app = setup();
+        app.register_type::<Banner>();
+        app.add_systems(PreStartup, update_database_path_31);
+        app.add_systems(Startup, run_test_31);
+
+        app.update();
+    }
+
+    // Test 32
+    fn update_database_path_32(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_32.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_32(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            30,
+            "Anne Straße",
+            "test_2@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            50,
+            "Karl Kopf",
+            "test_3@testen.com",
+        );
+
+        let deleted: Vec<Player> = database
+            .delete_returning(table, "deaths > ?1", &[&20])
+            .unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted.iter().any(|x| x.name == "Anne Straße"));
+        assert!(deleted.iter().any(|x| x.name == "Karl Kopf"));
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM 'Player';", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Runna vom Sofa");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_delete_returning() {
+        let mut app = setup();
+        app.register_type::<Player>();
+        app.add_systems(PreStartup, update_database_path_32);
+        app.add_systems(Startup, run_test_32);
+
+        app.update();
+    }
+
+    // Test 33
+    #[derive(Reflect, Debug, PartialEq)]
+    #[reflect(@TaggedEnum)]
+    enum Damage {
+        Physical(i32),
+        Magical(i32),
+    }
+
+    impl Default for Damage {
+        fn default() -> Self {
+            Damage::Physical(0)
+        }
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Attack {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        damage: Damage,
+    }
+
+    fn update_database_path_33(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_33.sqlite");
+        registry.register_type::<Attack>(&app_registry);
+    }
+
+    fn run_test_33(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Attack").unwrap();
+        let options = TableSqlOptions::for_type::<Attack>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("damage TEXT NOT NULL,\ndamage_value INTEGER NOT NULL"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        for (name, damage) in [
+            ("Slash", Damage::Physical(12)),
+            ("Fireball", Damage::Magical(30)),
+        ] {
+            let attack = Attack {
+                name: name.to_string(),
+                damage,
+                ..Default::default()
+            };
+            database.insert(table, &attack, &registry).unwrap();
+        }
+
+        let test: Vec<Attack> = database
+            .query(table, "SELECT * FROM Attack WHERE damage = 'Magical';", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Fireball");
+        assert_eq!(test[0].damage, Damage::Magical(30));
+
+        // Unknown variants can not be read.
+        database
+            .execute("UPDATE Attack SET damage = 'Poison';", &[])
+            .unwrap();
+        assert!(database
+            .query::<Attack>(table, "SELECT * FROM Attack;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_tagged_enum() {
+        let mut app = setup();
+        app.register_type::<Attack>();
+        app.add_systems(PreStartup, update_database_path_33);
+        app.add_systems(Startup, run_test_33);
+
+        app.update();
+    }
+
+    // Test 34
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Particle {
+        #[reflect(@Key)]
+        id: i32,
+        velocity: Vec3A,
+        target: Option<Vec3A>,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct PlainParticle {
+        #[reflect(@Key)]
+        id: i32,
+        velocity: Vec3,
+        target: Option<Vec3>,
+    }
+
+    fn update_database_path_34(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_34.sqlite");
+        registry.register_type::<Particle>(&app_registry);
+        registry.register_type::<PlainParticle>(&app_registry);
+    }
+
+    fn run_test_34(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Particle").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("velocity BLOB NOT NULL"));
+        database.create_table(table).unwrap();
+
+        let particle = Particle {
+            velocity: Vec3A::new(1.0, -2.5, 3.0),
+            target: Some(Vec3A::Z),
+            ..Default::default()
+        };
+        database.insert(table, &particle, &registry).unwrap();
+        database
+            .insert(table, &Particle::default(), &registry)
+            .unwrap();
+
+        let test: Vec<Particle> = database
+            .query(table, "SELECT * FROM Particle ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].velocity, Vec3A::new(1.0, -2.5, 3.0));
+        assert_eq!(test[0].target, Some(Vec3A::Z));
+        assert_eq!(test[1].target, None);
+
+        // The blobs are compatible with Vec3.
+        let plain = erm_registry.get_table_definition("PlainParticle").unwrap();
+        let test: Vec<PlainParticle> = database
+            .query(plain, "SELECT * FROM Particle WHERE id = 1;", &[])
+            .unwrap();
+        assert_eq!(test[0].velocity, Vec3::new(1.0, -2.5, 3.0));
+        assert_eq!(test[0].target, Some(Vec3::Z));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_vec3a() {
+        let mut app = setup();
+        app.register_type::<Particle>();
+        app.register_type::<PlainParticle>();
+        app.add_systems(PreStartup, update_database_path_34);
+        app.add_systems(Startup, run_test_34);
+
+        app.update();
+    }
+
+    // Test 35
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Attributes {
+        strength: i32,
+        title: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Champion {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        #[reflect(@Inline)]
+        attributes: Attributes,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Pretender {
+        #[reflect(@Key)]
+        id: i32,
+        title: String,
+        #[reflect(@Inline)]
+        attributes: Attributes,
+    }
+
+    fn update_database_path_35(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_35.sqlite");
+        registry.register_type::<Champion>(&app_registry);
+        registry.register_type::<Pretender>(&app_registry);
+    }
+
+    fn run_test_35(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Champion").unwrap();
+        let options = TableSqlOptions::for_type::<Champion>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("strength INTEGER NOT NULL"));
+        assert!(sql.contains("title TEXT NOT NULL"));
+        assert!(!sql.contains("attributes"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        let champion = Champion {
+            name: "Conan".to_string(),
+            attributes: Attributes {
+                strength: 18,
+                title: "Barbarian".to_string(),
+            },
+            ..Default::default()
+        };
+        database.insert(table, &champion, &registry).unwrap();
+
+        let test: Vec<Champion> = database
+            .query(table, "SELECT * FROM Champion WHERE strength = 18;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Conan");
+        assert_eq!(test[0].attributes.strength, 18);
+        assert_eq!(test[0].attributes.title, "Barbarian");
+
+        // The inlined title collides with the title of the table.
+        let table = erm_registry.get_table_definition("Pretender").unwrap();
+        let options = TableSqlOptions::for_type::<Pretender>();
+        assert!(SqliteDatabase::get_table_sql_with_options(table, &options).is_err());
+        assert!(database
+            .insert(table, &Pretender::default(), &registry)
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_inline() {
+        let mut app = setup();
+        app.register_type::<Champion>();
+        app.register_type::<Pretender>();
+        app.add_systems(PreStartup, update_database_path_35);
+        app.add_systems(Startup, run_test_35);
+
+        app.update();
+    }
+
+    // Test 36
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Score {
+        #[reflect(@Key)]
+        id: i32,
+        points: i32,
+        bonus: i32,
+    }
+
+    fn run_test_36(app_registry: Res<AppTypeRegistry>, mut registry: ResMut<ErmTypesRegistry>) {
+        registry.register_type::<Score>(&app_registry);
+        let table = registry.get_table_definition("Score").unwrap();
+
+        // Let bonus share the order of points.
+        let order = table.get("points").unwrap().order;
+        let table = TableDefinition {
+            sql_name: table.sql_name.clone(),
+            fields: table
+                .fields
+                .iter()
+                .map(|(name, col)| {
+                    let mut col = col.clone();
+                    if col.rust_name == "bonus" {
+                        col.order = order;
+                    }
+                    (name.clone(), col)
+                })
+                .collect(),
+        };
+
+        // Columns sharing an order are sorted by their field name.
+        let sql = SqliteDatabase::get_table_sql(&table).unwrap();
+        let bonus = sql.find("bonus").unwrap();
+        let points = sql.find("points").unwrap();
+        assert!(bonus < points);
+        for _ in 0..10 {
+            assert_eq!(SqliteDatabase::get_table_sql(&table).unwrap(), sql);
+        }
+
+        let mut options = TableSqlOptions::new();
+        options.use_unique_orders(true);
+        let error = SqliteDatabase::get_table_sql_with_options(&table, &options).unwrap_err();
+        assert!(error.to_string().contains("share the order"));
+    }
+
+    #[test]
+    fn test_duplicate_orders() {
+        let mut app = setup();
+        app.register_type::<Score>();
+        app.add_systems(Startup, run_test_36);
+
+        app.update();
+    }
+
+    // Test 37
+    fn update_database_path_37(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_37.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_37(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for i in 0..1200 {
+            let player = Player {
+                name: format!("Player {i}"),
+                ..Default::default()
+            };
+            database.insert(table, &player, &registry).unwrap();
+        }
+
+        let mut test: Vec<Player> = database.find_many_by_keys(table, &[3, 1, 5000]).unwrap();
+        test.sort_by_key(|x| x.id);
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].name, "Player 0");
+        assert_eq!(test[1].name, "Player 2");
+
+        // More keys than fit into a single query.
+        let keys: Vec<i32> = (1..=1200).collect();
+        let test: Vec<Player> = database.find_many_by_keys(table, &keys).unwrap();
+        assert_eq!(test.len(), 1200);
+
+        let test: Vec<Player> = database
+            .find_many_by_keys::<Player, i32>(table, &[])
+            .unwrap();
+        assert!(test.is_empty());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_find_many_by_keys() {
+        let mut app = setup();
+        app.register_type::<Player>();
+        app.add_systems(PreStartup, update_database_path_37);
+        app.add_systems(Startup, run_test_37);
+
+        app.update();
+    }
+
+    // Test 38
+    fn update_database_path_38(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_38.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_38(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        let player = Player {
+            name: "Saved".to_string(),
+            deaths: 7,
+            email: "saved@test.com".to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &player, &registry).unwrap();
+
+        // The name was set at runtime, everything else comes from the save.
+        let mut target = Player {
+            name: "Runtime".to_string(),
+            ..Default::default()
+        };
+        assert!(database.merge_into(table, &mut target, &1).unwrap());
+        assert_eq!(target.id, 1);
+        assert_eq!(target.name, "Runtime");
+        assert_eq!(target.deaths, 7);
+        assert_eq!(target.email, "saved@test.com");
+
+        let mut target = Player::default();
+        assert!(!database.merge_into(table, &mut target, &2).unwrap());
+        assert_eq!(target.name, "");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_merge_into() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_38);
+        app.add_systems(Startup, run_test_38);
+
+        app.update();
+    }
+
+    // Test 39
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Birthday {
+        #[reflect(@Key)]
+        id: i32,
+        date: String,
+    }
+
+    fn update_database_path_39(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_39.sqlite");
+        registry.register_type::<Birthday>(&app_registry);
+    }
+
+    fn run_test_39(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        // Store the date column as a date.
+        let table = erm_registry.get_table_definition("Birthday").unwrap();
+        let table = TableDefinition {
+            sql_name: table.sql_name.clone(),
+            fields: table
+                .fields
+                .iter()
+                .map(|(name, col)| {
+                    let mut col = col.clone();
+                    if col.rust_name == "date" {
+                        col.sql_type = bevy_erm::prelude::SqlType::Date(true);
+                    }
+                    (name.clone(), col)
+                })
+                .collect(),
+        };
+        database.create_table(&table).unwrap();
+        database
+            .execute("INSERT INTO Birthday (date) VALUES ('1990-05-17');", &[])
+            .unwrap();
+
+        let test: Vec<Birthday> = database
+            .query(&table, "SELECT * FROM Birthday;", &[])
+            .unwrap();
+        assert_eq!(test[0].date, "1990-05-17");
+
+        // Malformed dates are an error instead of a panic.
+        for date in ["", "17.05.1990", "1990-13-01"] {
+            database
+                .execute("UPDATE Birthday SET date = ?;", &[&date])
+                .unwrap();
+            let test: Result<Vec<Birthday>, SqliteDbError> =
+                database.query(&table, "SELECT * FROM Birthday;", &[]);
+            assert!(test.is_err());
+        }
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_date() {
+        let mut app = setup();
+        app.register_type::<Birthday>();
+        app.add_systems(PreStartup, update_database_path_39);
+        app.add_systems(Startup, run_test_39);
+
+        app.update();
+    }
+
+    // Test 40
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Appointment {
+        #[reflect(@Key)]
+        id: i32,
+        start: f64,
+        created: String,
+    }
+
+    fn update_database_path_40(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_40.sqlite");
+        registry.register_type::<Appointment>(&app_registry);
+    }
+
+    fn run_test_40(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        // Store start as a time and created as a date time.
+        let table = erm_registry.get_table_definition("Appointment").unwrap();
+        let table = TableDefinition {
+            sql_name: table.sql_name.clone(),
+            fields: table
+                .fields
+                .iter()
+                .map(|(name, col)| {
+                    let mut col = col.clone();
+                    if col.rust_name == "start" {
+                        col.sql_type = bevy_erm::prelude::SqlType::Time(true);
+                    } else if col.rust_name == "created" {
+                        col.sql_type = bevy_erm::prelude::SqlType::DateTime(true);
+                    }
+                    (name.clone(), col)
+                })
+                .collect(),
+        };
+        let sql = SqliteDatabase::get_table_sql(&table).unwrap();
+        assert!(sql.contains("start REAL NOT NULL"));
+        assert!(sql.contains("created TEXT NOT NULL"));
+        database.create_table(&table).unwrap();
+
+        let appointment = Appointment {
+            start: 9.5 * 3600.0,
+            created: "2024-05-01T18:30:00+02:00".to_string(),
+            ..Default::default()
+        };
+        database.insert(&table, &appointment, &registry).unwrap();
+        database
+            .execute(
+                "INSERT INTO Appointment (start, created) VALUES (0, datetime('2024-05-01'));",
+                &[],
+            )
+            .unwrap();
+
+        let test: Vec<Appointment> = database
+            .query(&table, "SELECT * FROM Appointment ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].start, appointment.start);
+        assert_eq!(test[0].created, appointment.created);
+        assert_eq!(test[1].created, "2024-05-01 00:00:00");
+
+        // Values which are no time of day or date time are an error.
+        database
+            .execute("UPDATE Appointment SET start = 90000 WHERE id = 1;", &[])
+            .unwrap();
+        assert!(database
+            .query::<Appointment>(&table, "SELECT * FROM Appointment WHERE id = 1;", &[])
+            .is_err());
+        database
+            .execute(
+                "UPDATE Appointment SET created = '2024-05-01' WHERE id = 2;",
+                &[],
+            )
+            .unwrap();
+        assert!(database
+            .query::<Appointment>(&table, "SELECT * FROM Appointment WHERE id = 2;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_time_and_date_time() {
+        let mut app = setup();
+        app.register_type::<Appointment>();
+        app.add_systems(PreStartup, update_database_path_40);
+        app.add_systems(Startup, run_test_40);
+
+        app.update();
+    }
+
+    // Test 41
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Turret {
+        #[reflect(@Key)]
+        id: i32,
+        #[reflect(@EulerAngles)]
+        rotation: Quat,
+    }
+
+    fn update_database_path_41(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_41.sqlite");
+        registry.register_type::<Turret>(&app_registry);
+    }
+
+    fn run_test_41(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Turret").unwrap();
+        let options = TableSqlOptions::for_type::<Turret>();
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.contains("rotation_x REAL NOT NULL"));
+        assert!(sql.contains("rotation_z REAL NOT NULL"));
+        database.create_table_with_options(table, &options).unwrap();
+
+        let turret = Turret {
+            rotation: Quat::from_euler(EulerRot::XYZ, 0.5, -0.25, 1.0),
+            ..Default::default()
+        };
+        database.insert(table, &turret, &registry).unwrap();
+
+        // The angles can be used in queries.
+        let test: Vec<Turret> = database
+            .query(table, "SELECT * FROM Turret WHERE rotation_z > 0.9;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert!(test[0].rotation.abs_diff_eq(turret.rotation, 1e-5));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_euler_angles() {
+        let mut app = setup();
+        app.register_type::<Turret>();
+        app.add_systems(PreStartup, update_database_path_41);
+        app.add_systems(Startup, run_test_41);
+
+        app.update();
+    }
+
+    // Test 42
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct DeathCount {
+        player: String,
+        total: f64,
+    }
+
+    fn update_database_path_42(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_42.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_42(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for (name, deaths) in [("Alice", 3), ("Bob", 5)] {
+            let player = Player {
+                name: name.to_string(),
+                deaths,
+                ..Default::default()
+            };
+            database.insert(table, &player, &registry).unwrap();
+        }
+
+        // The integer deaths are converted into the float total.
+        let test: Vec<DeathCount> = database
+            .query_positional("SELECT name, deaths * 2 FROM Player ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 2);
+        assert_eq!(test[0].player, "Alice");
+        assert_eq!(test[0].total, 6.0);
+        assert_eq!(test[1].player, "Bob");
+        assert_eq!(test[1].total, 10.0);
+
+        // More columns than fields.
+        assert!(database
+            .query_positional::<DeathCount>("SELECT name, deaths, email FROM Player;", &[])
+            .is_err());
+
+        // Errors of the statement are returned as such.
+        assert!(matches!(
+            database.query_positional::<DeathCount>("SELECT name, deaths FROM Playr;", &[]),
+            Err(SqliteDbError::Prepare(_))
+        ));
+        assert!(matches!(
+            database.query_positional::<DeathCount>(
+                "SELECT name, deaths FROM Player WHERE id = ?1;",
+                &[]
+            ),
+            Err(SqliteDbError::Execute(_))
+        ));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_positional() {
+        let mut app = setup();
+        app.register_type::<DeathCount>();
+        app.add_systems(PreStartup, update_database_path_42);
+        app.add_systems(Startup, run_test_42);
+
+        app.update();
+    }
+
+    // Test 43
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Note {
+        text: String,
+    }
+
+    fn update_database_path_43(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_43.sqlite");
+        registry.register_type::<Player>(&app_registry);
+        registry.register_type::<Note>(&app_registry);
+    }
+
+    fn run_test_43(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for name in ["Alice", "Bob"] {
+            let player = Player {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            database.insert(table, &player, &registry).unwrap();
+        }
+
+        let player = Player {
+            id: 2,
+            name: "Robert".to_string(),
+            deaths: 4,
+            email: "robert@test.com".to_string(),
+        };
+        assert_eq!(database.update(table, &player, &registry).unwrap(), 1);
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM Player ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].name, "Alice");
+        assert_eq!(test[1].name, "Robert");
+        assert_eq!(test[1].deaths, 4);
+        assert_eq!(test[1].email, "robert@test.com");
+
+        // There is no row with this key.
+        let player = Player {
+            id: 3,
+            ..Default::default()
+        };
+        assert_eq!(database.update(table, &player, &registry).unwrap(), 0);
+
+        // Tables without a key cannot be updated.
+        let table = erm_registry.get_table_definition("Note").unwrap();
+        assert!(database.update(table, &Note::default(), &registry).is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_update() {
+        let mut app = setup();
+        app.register_type::<Note>();
+        app.add_systems(PreStartup, update_database_path_43);
+        app.add_systems(Startup, run_test_43);
+
+        app.update();
+    }
+
+    // Test 44
+    fn update_database_path_44(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_44.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_44(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for name in ["Alice", "Bob"] {
+            let player = Player {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            database.insert(table, &player, &registry).unwrap();
+        }
+
+        let players: Vec<Player> = database
+            .query(table, "SELECT * FROM Player WHERE name = 'Bob';", &[])
+            .unwrap();
+        assert_eq!(database.delete(table, &players[0], &registry).unwrap(), 1);
+        assert_eq!(database.delete(table, &players[0], &registry).unwrap(), 0);
+
+        // A value which was never stored has no key.
+        assert!(database
+            .delete(table, &Player::default(), &registry)
+            .is_err());
+
+        let test: Vec<Player> = database.query(table, "SELECT * FROM Player;", &[]).unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Alice");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_44);
+        app.add_systems(Startup, run_test_44);
+
+        app.update();
+    }
+
+    // Test 45
+    fn update_database_path_45(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_45.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_45(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        database
+            .execute("CREATE TABLE Removed (name TEXT);", &[])
+            .unwrap();
+        database
+            .execute(
+                "CREATE TRIGGER on_delete AFTER DELETE ON Player
+                BEGIN INSERT INTO Removed VALUES (old.name); END;",
+                &[],
+            )
+            .unwrap();
+        database
+            .execute("PRAGMA recursive_triggers = ON;", &[])
+            .unwrap();
+
+        let mut player = Player {
+            id: 7,
+            name: "Alice".to_string(),
+            ..Default::default()
+        };
+        database
+            .insert_or_replace(table, &player, &registry)
+            .unwrap();
+
+        // The existing row is deleted and inserted again.
+        player.name = "Alicia".to_string();
+        database
+            .insert_or_replace(table, &player, &registry)
+            .unwrap();
+
+        let test: Vec<Player> = database.query(table, "SELECT * FROM Player;", &[]).unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].id, 7);
+        assert_eq!(test[0].name, "Alicia");
+
+        let removed: Vec<String> = database
+            .query_column("SELECT name FROM Removed;", &[])
+            .unwrap();
+        assert_eq!(removed, vec!["Alice".to_string()]);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_insert_or_replace() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_45);
+        app.add_systems(Startup, run_test_45);
+
+        app.update();
+    }
+
+    // Test 46
+    fn run_test_46(
+        app_registry: Res<AppTypeRegistry>,
+        mut erm_registry: ResMut<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+    ) {
+        erm_registry.register_type::<Player>(&app_registry);
+        let table = erm_registry.get_table_definition("Player").unwrap();
+
+        // The database was never opened.
+        let test: Result<Vec<Player>, SqliteDbError> =
+            database.query(table, "SELECT * FROM Player;", &[]);
+        assert!(matches!(test, Err(SqliteDbError::NotOpen)));
+        assert!(database.execute("DELETE FROM Player;", &[]).is_err());
+        assert!(database
+            .query_scalar::<i32>("SELECT COUNT(*) FROM Player;", &[])
+            .is_err());
+        assert!(database.close().is_ok());
+    }
+
+    #[test]
+    fn test_closed_database() {
+        let mut app = setup();
+        app.add_systems(Startup, run_test_46);
+
+        app.update();
+    }
+
+    // Test 47
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Setting {
+        #[reflect(@Key)]
+        id: i32,
+        label: Cow<'static, str>,
+    }
+
+    fn update_database_path_47(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_47.sqlite");
+        registry.register_type::<Setting>(&app_registry);
+    }
+
+    fn run_test_47(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Setting").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("label TEXT NOT NULL"));
+        database.create_table(table).unwrap();
+
+        for label in [
+            Cow::Borrowed("Volume"),
+            Cow::Owned("Brightness".to_string()),
+        ] {
+            let setting = Setting {
+                label,
+                ..Default::default()
+            };
+            database.insert(table, &setting, &registry).unwrap();
+        }
+
+        let test: Vec<Setting> = database
+            .query(table, "SELECT * FROM Setting ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].label, "Volume");
+        assert_eq!(test[1].label, "Brightness");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_cow() {
+        let mut app = setup();
+        app.register_type::<Setting>();
+        app.add_systems(PreStartup, update_database_path_47);
+        app.add_systems(Startup, run_test_47);
+
+        app.update();
+    }
+
+    // Test 48
+    fn update_database_path_48(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_48.sqlite");
+        settings.use_in_memory(true);
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_48(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+
+        let test: Vec<Player> = database.query(table, "SELECT * FROM Player;", &[]).unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].name, "Runna vom Sofa");
+        assert!(!std::path::Path::new(settings.get_data_source()).exists());
+
+        // The data is gone with the connection.
+        database.close().unwrap();
+        database.open(&settings).unwrap();
+        assert!(!database.table_exists("Player").unwrap());
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_48);
+        app.add_systems(Startup, run_test_48);
+
+        app.update();
+    }
+
+    // Test 49
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct PlayerContact {
+        id: i32,
+        contact: String,
+        score: f64,
+    }
+
+    fn update_database_path_49(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_49.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_49(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            10,
+            "Runna vom Sofa",
+            "test_1@testen.com",
+        );
+
+        // Neither contact nor score are columns of the table.
+        let test: Vec<PlayerContact> = database
+            .query(
+                table,
+                "SELECT id, name || ' <' || email || '>' AS contact, deaths * 1.5 AS score \
+                 FROM Player;",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].contact, "Runna vom Sofa <test_1@testen.com>");
+        assert_eq!(test[0].score, 15.0);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_computed_columns() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_49);
+        app.add_systems(Startup, run_test_49);
+
+        app.update();
+    }
+
+    // Test 50
+    fn update_database_path_50(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_50.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_50(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        let table = erm_registry.get_table_definition("Player").unwrap();
+
+        // An empty slice does not need a connection.
+        assert_eq!(
+            database
+                .insert_many::<Player>(table, &[], &registry)
+                .unwrap(),
+            0
+        );
+
+        database.open(&settings).unwrap();
+        database.create_table(table).unwrap();
+
+        let players: Vec<Player> = ["Runna vom Sofa", "Anne Straße", "Karl Kopf"]
+            .iter()
+            .enumerate()
+            .map(|(x, name)| Player {
+                name: name.to_string(),
+                deaths: x as i32 * 10,
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(database.insert_many(table, &players, &registry).unwrap(), 3);
+
+        let test: Vec<Player> = database
+            .query(table, "SELECT * FROM Player ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test.len(), 3);
+        assert_eq!(test[0].id, 1);
+        assert_eq!(test[1].name, "Anne Straße");
+        assert_eq!(test[2].deaths, 20);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_insert_many() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_50);
+        app.add_systems(Startup, run_test_50);
+
+        app.update();
+    }
+
+    // Test 51
+    fn update_database_path_51(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_51.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_51(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for deaths in 0..25 {
+            insert_player(
+                table,
+                &registry,
+                &mut database,
+                deaths,
+                "Karl Kopf",
+                "test_3@testen.com",
+            );
+        }
+
+        let (page, total): (Vec<Player>, i64) = database
+            .query_paged_with_total(table, "deaths >= ?", 10, 10, &[&5])
+            .unwrap();
+        assert_eq!(total, 20);
+        assert_eq!(page.len(), 10);
+        assert_eq!(page[0].deaths, 15);
+
+        let (page, total): (Vec<Player>, i64) = database
+            .query_paged_with_total(table, "", 10, 20, &[])
+            .unwrap();
+        assert_eq!(total, 25);
+        assert_eq!(page.len(), 5);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_paged_with_total() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_51);
+        app.add_systems(Startup, run_test_51);
+
+        app.update();
+    }
+
+    // Test 52
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Rookie {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        nickname: Option<String>,
+        level: Option<u8>,
+        rating: Option<f32>,
+    }
+
+    fn update_database_path_52(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_52.sqlite");
+        registry.register_type::<Rookie>(&app_registry);
+    }
+
+    fn run_test_52(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Rookie").unwrap();
+        database.create_table(table).unwrap();
+
+        let named = Rookie {
+            name: "Runna vom Sofa".to_string(),
+            nickname: Some("Sofa".to_string()),
+            level: Some(3),
+            rating: Some(0.5),
+            ..Default::default()
+        };
+        database.insert(table, &named, &registry).unwrap();
+        let unnamed = Rookie {
+            name: "Anne Straße".to_string(),
+            ..Default::default()
+        };
+        database.insert(table, &unnamed, &registry).unwrap();
+
+        let nulls = database
+            .query_scalar::<i32>("SELECT COUNT(*) FROM Rookie WHERE nickname IS NULL;", &[])
+            .unwrap();
+        assert_eq!(nulls, Some(1));
+
+        let test: Vec<Rookie> = database
+            .query(table, "SELECT * FROM Rookie ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].nickname, Some("Sofa".to_string()));
+        assert_eq!(test[0].level, Some(3));
+        assert_eq!(test[0].rating, Some(0.5));
+        assert_eq!(test[1].nickname, None);
+        assert_eq!(test[1].level, None);
+        assert_eq!(test[1].rating, None);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_optional_fields() {
+        let mut app = setup();
+        app.register_type::<Rookie>();
+        app.add_systems(PreStartup, update_database_path_52);
+        app.add_systems(Startup, run_test_52);
+
+        app.update();
+    }
+
+    // Test 53
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Checkpoint {
+        #[reflect(@Key)]
+        id: i32,
+        pose: Isometry3d,
+        marker: Isometry2d,
+        target: Option<Isometry3d>,
+    }
+
+    fn update_database_path_53(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_53.sqlite");
+        registry.register_type::<Checkpoint>(&app_registry);
+    }
+
+    fn run_test_53(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Checkpoint").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("pose BLOB NOT NULL"));
+        assert!(sql.contains("target BLOB") && !sql.contains("target BLOB NOT NULL"));
+        database.create_table(table).unwrap();
+
+        let pose = Isometry3d::new(Vec3::new(1.0, 2.0, 3.0), Quat::from_rotation_y(0.5));
+        let marker = Isometry2d::new(Vec2::new(-4.0, 8.0), Rot2::radians(1.25));
+        let waypoint = Checkpoint {
+            pose,
+            marker,
+            target: Some(pose),
+            ..Default::default()
+        };
+        database.insert(table, &waypoint, &registry).unwrap();
+        database
+            .insert(table, &Checkpoint::default(), &registry)
+            .unwrap();
+
+        let test: Vec<Checkpoint> = database
+            .query(table, "SELECT * FROM Checkpoint ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].pose, pose);
+        assert_eq!(test[0].marker, marker);
+        assert_eq!(test[0].target, Some(pose));
+        assert_eq!(test[1].pose, Isometry3d::IDENTITY);
+        assert_eq!(test[1].target, None);
+
+        // A rotation which is not normalized indicates a corrupted value.
+        database
+            .execute("UPDATE Checkpoint SET marker = zeroblob(16);", &[])
+            .unwrap();
+        let test: Result<Vec<Checkpoint>, SqliteDbError> =
+            database.query(table, "SELECT * FROM Checkpoint;", &[]);
+        assert!(test.is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_isometry() {
+        let mut app = setup();
+        app.register_type::<Checkpoint>();
+        app.add_systems(PreStartup, update_database_path_53);
+        app.add_systems(Startup, run_test_53);
+
+        app.update();
+    }
+
+    // Test 54
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Outlaw {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        banned: bool,
+    }
+
+    fn update_database_path_54(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_54.sqlite");
+        registry.register_type::<Outlaw>(&app_registry);
+    }
+
+    fn run_test_54(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Outlaw").unwrap();
+        database.create_table(table).unwrap();
+        for (name, banned) in [("Runna vom Sofa", true), ("Anne Straße", false)] {
+            let outlaw = Outlaw {
+                name: name.to_string(),
+                banned,
+                ..Default::default()
+            };
+            database.insert(table, &outlaw, &registry).unwrap();
+        }
+
+        // Booleans are stored as 0 and 1.
+        let stored: Vec<i64> = database
+            .query_column("SELECT banned FROM Outlaw ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(stored, vec![1, 0]);
+
+        let test: Vec<Outlaw> = database
+            .query(table, "SELECT * FROM Outlaw ORDER BY id;", &[])
+            .unwrap();
+        assert!(test[0].banned);
+        assert!(!test[1].banned);
+
+        assert!(database
+            .execute("UPDATE Outlaw SET banned = 2;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_bool() {
+        let mut app = setup();
+        app.register_type::<Outlaw>();
+        app.add_systems(PreStartup, update_database_path_54);
+        app.add_systems(Startup, run_test_54);
+
+        app.update();
+    }
+
+    // Test 55
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Ledger {
+        #[reflect(@Key)]
+        id: i32,
+        grade: char,
+        initial: Option<char>,
+        serial: u128,
+        balance: i128,
+    }
+
+    fn update_database_path_55(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_55.sqlite");
+        registry.register_type::<Ledger>(&app_registry);
+    }
+
+    fn run_test_55(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Ledger").unwrap();
+        database.create_table(table).unwrap();
+        let ledger = Ledger {
+            grade: 'ß',
+            initial: Some('A'),
+            serial: u128::MAX,
+            balance: i128::MIN,
+            ..Default::default()
+        };
+        database.insert(table, &ledger, &registry).unwrap();
+        database
+            .insert(table, &Ledger::default(), &registry)
+            .unwrap();
+
+        // 128 bit integers are stored as decimal text, so no precision is lost.
+        let stored: Vec<String> = database
+            .query_column("SELECT serial FROM Ledger ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(stored[0], u128::MAX.to_string());
+
+        let test: Vec<Ledger> = database
+            .query(table, "SELECT * FROM Ledger ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].grade, 'ß');
+        assert_eq!(test[0].initial, Some('A'));
+        assert_eq!(test[0].serial, u128::MAX);
+        assert_eq!(test[0].balance, i128::MIN);
+        assert_eq!(test[1].grade, '\0');
+        assert_eq!(test[1].initial, None);
+        assert_eq!(test[1].serial, 0);
+
+        database
+            .execute("UPDATE Ledger SET grade = 'ab';", &[])
+            .unwrap();
+        assert!(database
+            .query::<Ledger>(table, "SELECT * FROM Ledger;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_char_and_128_bit_integers() {
+        let mut app = setup();
+        app.register_type::<Ledger>();
+        app.add_systems(PreStartup, update_database_path_55);
+        app.add_systems(Startup, run_test_55);
+
+        app.update();
+    }
+
+    // Test 56
+    fn update_database_path_56(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_56.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_56(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for x in 0..250 {
+            let name = format!("Player {x}");
+            let email = format!("player{x}@test.com");
+            insert_player(table, &registry, &mut database, x, &name, &email);
+        }
+
+        let mut reports: Vec<(usize, usize)> = Vec::new();
+        let test: Vec<Player> = database
+            .query_with_progress(table, "SELECT * FROM Player;", &[], |read, total| {
+                reports.push((read, total))
+            })
+            .unwrap();
+        assert_eq!(test.len(), 250);
+        assert_eq!(reports, vec![(0, 250), (100, 250), (200, 250), (250, 250)]);
+
+        let mut reports: Vec<(usize, usize)> = Vec::new();
+        let test: Vec<Player> = database
+            .query_with_progress(
+                table,
+                "SELECT * FROM Player WHERE deaths < ?;",
+                &[&0],
+                |read, total| reports.push((read, total)),
+            )
+            .unwrap();
+        assert!(test.is_empty());
+        assert_eq!(reports, vec![(0, 0)]);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_with_progress() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_56);
+        app.add_systems(Startup, run_test_56);
+
+        app.update();
+    }
+
+    // Test 57
+    fn update_database_path_57(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_57.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_57(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for (name, email) in [
+            ("Runna vom Sofa", "runna@sofa.com"),
+            ("Anne Straße", "anne@strasse.com"),
+        ] {
+            insert_player(table, &registry, &mut database, 3, name, email);
+        }
+
+        let query = "SELECT * FROM Player WHERE id = ?;";
+        let test: Option<Player> = database.query_one(table, query, &[&1]).unwrap();
+        assert_eq!(test.unwrap().name, "Runna vom Sofa");
+
+        let test: Option<Player> = database.query_one(table, query, &[&3]).unwrap();
+        assert!(test.is_none());
+
+        let query = "SELECT * FROM Player WHERE deaths = 3;";
+        let Err(e) = database.query_one::<Player>(table, query, &[]) else {
+            panic!("The query returned more than one row.");
+        };
+        assert!(e.to_string().contains("returned 2"));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_one() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_57);
+        app.add_systems(Startup, run_test_57);
+
+        app.update();
+    }
+
+    // Test 58
+    fn update_database_path_58(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_58.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_58(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for (name, email) in [
+            ("Runna vom Sofa", "runna@sofa.com"),
+            ("Anne Straße", "anne@strasse.com"),
+            ("Karl Kopf", "karl@kopf.com"),
+        ] {
+            insert_player(table, &registry, &mut database, 0, name, email);
+        }
+
+        let test: Option<Player> = database.find(table, &2).unwrap();
+        let test = test.unwrap();
+        assert_eq!(test.id, 2);
+        assert_eq!(test.name, "Anne Straße");
+
+        let test: Option<Player> = database.find(table, &4).unwrap();
+        assert!(test.is_none());
+
+        let mut keyless = table.clone();
+        keyless.fields.remove("id");
+        let test = database.find::<Player>(&keyless, &2);
+        assert!(matches!(test, Err(SqliteDbError::NoKeyColumn(_))));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_find() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_58);
+        app.add_systems(Startup, run_test_58);
+
+        app.update();
+    }
+
+    // Test 59
+    #[derive(Reflect)]
+    struct Portal {
+        #[reflect(@Key)]
+        id: i32,
+        destination: String,
+        charges: u32,
+    }
+
+    impl Portal {
+        fn closed() -> Self {
+            Portal {
+                id: 0,
+                destination: "Nowhere".to_string(),
+                charges: 0,
+            }
+        }
+    }
+
+    fn update_database_path_59(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_59.sqlite");
+        registry.register_type::<Portal>(&app_registry);
+    }
+
+    fn run_test_59(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Portal").unwrap();
+        database.create_table(table).unwrap();
+        database
+            .execute(
+                "INSERT INTO Portal (destination, charges) VALUES ('Dagobah', 3);",
+                &[],
+            )
+            .unwrap();
+
+        let test: Vec<Portal> = database
+            .query_with(table, "SELECT * FROM Portal;", &[], Portal::closed)
+            .unwrap();
+        assert_eq!(test.len(), 1);
+        assert_eq!(test[0].id, 1);
+        assert_eq!(test[0].destination, "Dagobah");
+        assert_eq!(test[0].charges, 3);
+
+        // Fields without a column keep the value of the constructor.
+        let test: Vec<Portal> = database
+            .query_with(
+                table,
+                "SELECT id, charges FROM Portal;",
+                &[],
+                Portal::closed,
+            )
+            .unwrap();
+        assert_eq!(test[0].destination, "Nowhere");
+        assert_eq!(test[0].charges, 3);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_with() {
+        let mut app = setup();
+        app.register_type::<Portal>();
+        app.add_systems(PreStartup, update_database_path_59);
+        app.add_systems(Startup, run_test_59);
+
+        app.update();
+    }
+
+    // Test 60
+    fn update_database_path_60(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_60.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_60(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        let mut options = TableSqlOptions::new();
+        options.use_if_not_exists(true);
+        let sql = SqliteDatabase::get_table_sql_with_options(table, &options).unwrap();
+        assert!(sql.starts_with("CREATE TABLE IF NOT EXISTS 'Player'("));
+
+        database.create_table_if_not_exists(table).unwrap();
+        assert!(database.table_exists("Player").unwrap());
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            2,
+            "Runna vom Sofa",
+            "runna@sofa.com",
+        );
+
+        // The existing table and its rows are kept.
+        database.create_table_if_not_exists(table).unwrap();
+        let test: Vec<Player> = database.query(table, "SELECT * FROM Player;", &[]).unwrap();
+        assert_eq!(test.len(), 1);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_create_table_if_not_exists() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_60);
+        app.add_systems(Startup, run_test_60);
+
+        app.update();
+    }
+
+    // Test 61
+    fn update_database_path_61(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_61.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_61(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        database
+            .store_schema_hash(table, &TableSqlOptions::default())
+            .unwrap();
+        assert!(database.get_stored_schema_hash("Player").unwrap().is_some());
+
+        database.drop_table(table).unwrap();
+        assert!(!database.table_exists("Player").unwrap());
+        assert!(database.get_stored_schema_hash("Player").unwrap().is_none());
+
+        // Dropping a missing table is not an error.
+        database.drop_table(table).unwrap();
+
+        database
+            .execute("CREATE TABLE Scratch (value INTEGER);", &[])
+            .unwrap();
+        database.drop_table_by_name("Scratch").unwrap();
+        assert!(!database.table_exists("Scratch").unwrap());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_drop_table() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_61);
+        app.add_systems(Startup, run_test_61);
+
+        app.update();
+    }
+
+    // Test 62
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Joint {
+        #[reflect(@Key)]
+        id: i32,
+        basis: Mat3A,
+        local: Affine3A,
+        sprite: Affine2,
+        parent: Option<Affine3A>,
+    }
+
+    fn update_database_path_62(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_62.sqlite");
+        registry.register_type::<Joint>(&app_registry);
+    }
+
+    fn run_test_62(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Joint").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("basis BLOB NOT NULL"));
+        assert!(sql.contains("parent BLOB") && !sql.contains("parent BLOB NOT NULL"));
+        database.create_table(table).unwrap();
+
+        let basis = Mat3A::from_rotation_z(0.75);
+        let local = Affine3A::from_scale_rotation_translation(
+            Vec3::new(1.0, 2.0, 0.5),
+            Quat::from_rotation_x(1.5),
+            Vec3::new(-3.0, 4.0, 5.0),
+        );
+        let sprite = Affine2::from_angle_translation(0.25, Vec2::new(8.0, -2.0));
+        let joint = Joint {
+            basis,
+            local,
+            sprite,
+            parent: Some(local),
+            ..Default::default()
+        };
+        database.insert(table, &joint, &registry).unwrap();
+        database
+            .insert(table, &Joint::default(), &registry)
+            .unwrap();
+
+        // A Mat3A uses the layout of a Mat3.
+        let stored: Vec<Vec<u8>> = database
+            .query_column("SELECT basis FROM Joint ORDER BY id;", &[])
+            .unwrap();
+        let mat3 = Mat3::from(basis).to_cols_array();
+        let expected: Vec<u8> = mat3.iter().flat_map(|x| x.to_le_bytes()).collect();
+        assert_eq!(stored[0], expected);
+
+        let test: Vec<Joint> = database
+            .query(table, "SELECT * FROM Joint ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].basis, basis);
+        assert_eq!(test[0].local, local);
+        assert_eq!(test[0].sprite, sprite);
+        assert_eq!(test[0].parent, Some(local));
+        assert_eq!(test[1].basis, Mat3A::IDENTITY);
+        assert_eq!(test[1].parent, None);
+
+        database
+            .execute("UPDATE Joint SET basis = zeroblob(16);", &[])
+            .unwrap();
+        assert!(database
+            .query::<Joint>(table, "SELECT * FROM Joint;", &[])
+            .is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_matrices() {
+        let mut app = setup();
+        app.register_type::<Joint>();
+        app.add_systems(PreStartup, update_database_path_62);
+        app.add_systems(Startup, run_test_62);
+
+        app.update();
+    }
+
+    // Test 63
+    fn update_database_path_63(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_63.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_63(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for (name, email) in [
+            ("Runna vom Sofa", "runna@sofa.com"),
+            ("Anne Straße", "anne@strasse.com"),
+        ] {
+            insert_player(table, &registry, &mut database, 0, name, email);
+        }
+
+        database
+            .execute("CREATE TABLE Item (owner INTEGER, name TEXT);", &[])
+            .unwrap();
+        database
+            .execute(
+                "INSERT INTO Item VALUES (1, 'Sword'), (1, 'Shield'), (2, 'Bow'), (1, 'Helmet');",
+                &[],
+            )
+            .unwrap();
+
+        let query = "SELECT Player.* FROM Player JOIN Item ON Item.owner = Player.id \
+                     ORDER BY Item.rowid;";
+        let test: Vec<Player> = database.query(table, query, &[]).unwrap();
+        assert_eq!(test.len(), 4);
+
+        let test: Vec<Player> = database.query_dedup_by_key(table, query, &[]).unwrap();
+        let names: Vec<&str> = test.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["Runna vom Sofa", "Anne Straße"]);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_dedup_by_key() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_63);
+        app.add_systems(Startup, run_test_63);
+
+        app.update();
+    }
+
+    // Test 64
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Beacon {
+        #[reflect(@Key)]
+        id: i32,
+        position: Vec3,
+        target: Option<Vec3>,
+        facing: Option<Quat>,
+    }
+
+    fn update_database_path_64(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_64.sqlite");
+        registry.register_type::<Beacon>(&app_registry);
+    }
+
+    fn run_test_64(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Beacon").unwrap();
+        let options = TableSqlOptions::for_type::<Beacon>();
+        for sql in [
+            SqliteDatabase::get_table_sql(table).unwrap(),
+            SqliteDatabase::get_table_sql_with_options(table, &options).unwrap(),
+        ] {
+            assert!(sql.contains("position BLOB NOT NULL"));
+            assert!(sql.contains("target BLOB") && !sql.contains("target BLOB NOT NULL"));
+            assert!(sql.contains("facing BLOB") && !sql.contains("facing BLOB NOT NULL"));
+        }
+        database.create_table_with_options(table, &options).unwrap();
+
+        let waypoint = Beacon {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            target: Some(Vec3::new(-1.0, 0.5, 8.0)),
+            facing: Some(Quat::from_rotation_z(0.5)),
+            ..Default::default()
+        };
+        database.insert(table, &waypoint, &registry).unwrap();
+        database
+            .insert(table, &Beacon::default(), &registry)
+            .unwrap();
+
+        // None is stored as NULL.
+        let stored: Vec<Option<Vec<u8>>> = database
+            .query_column("SELECT target FROM Beacon ORDER BY id;", &[])
+            .unwrap();
+        assert!(stored[0].is_some());
+        assert_eq!(stored[1], None);
+
+        let test: Vec<Beacon> = database
+            .query(table, "SELECT * FROM Beacon ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].target, Some(Vec3::new(-1.0, 0.5, 8.0)));
+        assert_eq!(test[0].facing, Some(Quat::from_rotation_z(0.5)));
+        assert_eq!(test[1].target, None);
+        assert_eq!(test[1].facing, None);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_optional_blobs() {
+        let mut app = setup();
+        app.register_type::<Beacon>();
+        app.add_systems(PreStartup, update_database_path_64);
+        app.add_systems(Startup, run_test_64);
+
+        app.update();
+    }
+
+    // Test 65
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct LeaderboardEntry {
+        name: String,
+        deaths: i32,
+        rank: i64,
+    }
+
+    fn update_database_path_65(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_65.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_65(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            3,
+            "Runna",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            12,
+            "Bernd",
+            "test_2@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            7,
+            "Hugo",
+            "test_3@testen.com",
+        );
+
+        // The rank is computed by a window function and not a column of the table.
+        let test: Vec<LeaderboardEntry> = database
+            .query(
+                table,
+                "SELECT name, deaths, ROW_NUMBER() OVER (ORDER BY deaths DESC) AS rank \
+                 FROM Player ORDER BY rank;",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(test.len(), 3);
+        assert_eq!(test[0].name, "Bernd");
+        assert_eq!(test[0].rank, 1);
+        assert_eq!(test[1].name, "Hugo");
+        assert_eq!(test[1].rank, 2);
+        assert_eq!(test[2].name, "Runna");
+        assert_eq!(test[2].deaths, 3);
+        assert_eq!(test[2].rank, 3);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_window_functions() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_65);
+        app.add_systems(Startup, run_test_65);
+
+        app.update();
+    }
+
+    // Test 66
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct PlayerStats {
+        name: String,
+        total: i64,
+        most_deaths: i32,
+        average: Option<f64>,
+        badge: Vec<u8>,
+    }
+
+    fn update_database_path_66(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_66.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_66(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+
+        // Aggregates over no rows are NULL.
+        let query = "SELECT COUNT(*) AS total, MAX(deaths) AS most_deaths, \
+                     AVG(deaths) AS average, x'0102' AS badge FROM Player;";
+        let test: Vec<PlayerStats> = database.query(table, query, &[]).unwrap();
+        assert_eq!(test[0].total, 0);
+        assert_eq!(test[0].most_deaths, 0);
+        assert_eq!(test[0].average, None);
+        assert_eq!(test[0].badge, vec![1, 2]);
+
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            4,
+            "Runna",
+            "test_1@testen.com",
+        );
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            8,
+            "Bernd",
+            "test_2@testen.com",
+        );
+
+        let test: Vec<PlayerStats> = database.query(table, query, &[]).unwrap();
+        assert_eq!(test[0].total, 2);
+        assert_eq!(test[0].most_deaths, 8);
+        assert_eq!(test[0].average, Some(6.0));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_aggregate_columns() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_66);
+        app.add_systems(Startup, run_test_66);
+
+        app.update();
+    }
+
+    // Test 67
+    fn update_database_path_67(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_67.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_67(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        insert_player(
+            table,
+            &registry,
+            &mut database,
+            4,
+            "Runna",
+            "test_1@testen.com",
+        );
+
+        // The parameter of the query is missing.
+        let result: Result<Vec<Player>, SqliteDbError> =
+            database.query(table, "SELECT * FROM Player WHERE id = ?1;", &[]);
+        let Err(SqliteDbError::Execute(_)) = result else {
+            panic!("The query must fail without its parameter.");
+        };
+
+        // A row which cannot be mapped is an error as well.
+        database
+            .execute("UPDATE Player SET deaths = 'many';", &[])
+            .unwrap();
+        let result: Result<Vec<Player>, SqliteDbError> =
+            database.query(table, "SELECT * FROM Player;", &[]);
+        assert!(result.is_err());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_67);
+        app.add_systems(Startup, run_test_67);
+
+        app.update();
+    }
+
+    // Test 68
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Ability {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        cooldown: Timer,
+    }
+
+    fn update_database_path_68(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_68.sqlite");
+        registry.register_type::<Ability>(&app_registry);
+    }
+
+    fn run_test_68(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Ability").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("cooldown REAL NOT NULL"));
+        database.create_table(table).unwrap();
+
+        let mut ability = Ability {
+            name: "Fireball".to_string(),
+            cooldown: Timer::from_seconds(4.0, TimerMode::Once),
+            ..Default::default()
+        };
+        ability.cooldown.tick(Duration::from_secs_f32(1.5));
+        database.insert(table, &ability, &registry).unwrap();
+
+        let stored = database
+            .query_scalar::<f64>("SELECT cooldown FROM Ability;", &[])
+            .unwrap();
+        assert_eq!(stored, Some(2.5));
+
+        let test: Vec<Ability> = database
+            .query(table, "SELECT * FROM Ability;", &[])
+            .unwrap();
+        assert_eq!(test[0].cooldown.remaining_secs(), 2.5);
+        assert_eq!(test[0].cooldown.elapsed_secs(), 0.0);
+        assert!(!test[0].cooldown.finished());
+
+        // Negative values are clamped to zero.
+        database
+            .execute("UPDATE Ability SET cooldown = -3.0;", &[])
+            .unwrap();
+        let test: Vec<Ability> = database
+            .query(table, "SELECT * FROM Ability;", &[])
+            .unwrap();
+        assert_eq!(test[0].cooldown.remaining_secs(), 0.0);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_timer_cooldown() {
+        let mut app = setup();
+        app.register_type::<Ability>();
+        app.add_systems(PreStartup, update_database_path_68);
+        app.add_systems(Startup, run_test_68);
+
+        app.update();
+    }
+
+    // Test 69
+    fn update_database_path_69(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_69.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_69(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        for x in 1..=6 {
+            let name = format!("Player {x}");
+            let email = format!("test_{x}@testen.com");
+            insert_player(table, &registry, &mut database, x, &name, &email);
+        }
+
+        // The limit and offset are bound after the parameter of the query.
+        let mut names: Vec<String> = Vec::new();
+        for page in 0..4 {
+            let test: Vec<Player> = database
+                .query_paged(
+                    table,
+                    "SELECT * FROM Player WHERE deaths >= ?1 ORDER BY id;",
+                    &[&1],
+                    2,
+                    page * 2,
+                )
+                .unwrap();
+            if page < 3 {
+                assert_eq!(test.len(), 2);
+            } else {
+                assert!(test.is_empty());
+            }
+            names.extend(test.into_iter().map(|x| x.name));
+        }
+        assert_eq!(
+            names,
+            vec!["Player 1", "Player 2", "Player 3", "Player 4", "Player 5", "Player 6"]
+        );
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_query_paged() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_69);
+        app.add_systems(Startup, run_test_69);
+
+        app.update();
+    }
+
+    // Test 70
+    fn update_database_path_70(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_70.sqlite");
+        registry.register_type::<Player>(&app_registry);
+    }
+
+    fn run_test_70(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
 
-            columns.push(column);
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
+        assert_eq!(database.count(table, None, &[]).unwrap(), 0);
+
+        for (x, name) in ["Runna", "Bernd", "Hugo", "Runna"].iter().enumerate() {
+            let email = format!("test_{x}@testen.com");
+            insert_player(table, &registry, &mut database, 1, name, &email);
         }
 
-        let table_name = table.sql_name.clone();
-        let column_defs = columns.join(",\n");
-        let sql = format!("CREATE TABLE '{table_name}'({column_defs});");
+        assert_eq!(database.count(table, None, &[]).unwrap(), 4);
+        assert_eq!(
+            database
+                .count(table, Some("name = ?1"), &[&"Runna"])
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            database
+                .count(table, Some("name = ?1"), &[&"Gandalf"])
+                .unwrap(),
+            0
+        );
 
-        Ok(sql)
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
     }
 
-    /// Create a new table from the given table definition. If the table already exists,
-    /// it will not be created. This method prints an info instead and returns ok.
-    pub fn create_table(&mut self, def: &TableDefinition) -> Result<(), String> {
-        let table_name = def.sql_name.clone();
-        if self.table_exists(&table_name) {
-            info!("A table with the name {table_name} already exists");
-            return Ok(());
-        }
+    #[test]
+    fn test_count() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_70);
+        app.add_systems(Startup, run_test_70);
 
-        let Ok(table_sql) = Self::get_table_sql(def) else {
-            return Err("Could not generate SQL command to create the table.".to_string());
-        };
+        app.update();
+    }
 
-        match self.execute(&table_sql, &[]) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
-        }
+    // Test 71
+    fn update_database_path_71(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_71.sqlite");
+        registry.register_type::<Player>(&app_registry);
     }
 
-    pub fn insert<T: Reflect + Default + TypePath + bevy::prelude::Struct>(
-        &mut self,
-        def: &TableDefinition,
-        value: &T,
-        registry: &AppTypeRegistry,
-    ) -> Result<usize, String> {
-        let table_name = def.sql_name.clone();
-        assert_eq!(table_name, Type::of::<T>().short_path());
+    fn run_test_71(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
 
-        let mut names_vec: Vec<String> = Vec::new();
-        let mut params_vec: Vec<String> = Vec::new();
-        let mut wrapped_values: Vec<ValueWrapper> = Vec::new();
+        let table = erm_registry.get_table_definition("Player").unwrap();
+        database.create_table(table).unwrap();
 
-        for x in def.fields.values() {
-            if x.is_key() {
-                continue;
-            }
+        let id = database.insert_default::<Player>(table, &registry).unwrap();
+        assert_eq!(id, 1);
+        let id = database.insert_default::<Player>(table, &registry).unwrap();
+        assert_eq!(id, 2);
 
-            names_vec.push(x.sql_name.clone());
-            params_vec.push("?".to_owned());
+        let test: Option<Player> = database.find(table, &id).unwrap();
+        let test = test.unwrap();
+        assert_eq!(test.id, 2);
+        assert_eq!(test.deaths, 0);
+        assert_eq!(test.name, "");
 
-            let wrapped_value = ValueWrapper::build(value, &x.rust_name, registry);
-            wrapped_values.push(wrapped_value);
-        }
+        // find_or_create does not insert the default row again.
+        let test = database
+            .find_or_create(table, &1, Player::default, &registry)
+            .unwrap();
+        assert_eq!(test.id, 1);
+        assert_eq!(database.count(table, None, &[]).unwrap(), 2);
 
-        let column_names = names_vec.join(", ");
-        let parameter = params_vec.join(", ");
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
 
-        let query = format!(
-            "INSERT INTO {} ({}) VALUES ({});",
-            table_name, column_names, parameter
-        );
+        database.close().unwrap();
+    }
 
-        let wrapped_links: Vec<&dyn ToSql> =
-            wrapped_values.iter().map(|x| x as &dyn ToSql).collect();
+    #[test]
+    fn test_insert_default() {
+        let mut app = setup();
+        app.add_systems(PreStartup, update_database_path_71);
+        app.add_systems(Startup, run_test_71);
 
-        self.execute(&query, &wrapped_links)
+        app.update();
     }
-}
 
-impl Plugin for SqliteDatabase {
-    fn build(&self, app: &mut App) {
-        app.add_plugins(BevyERMPlugin);
+    // Test 72
+    #[derive(Reflect)]
+    #[reflect(Default)]
+    struct Snapshot {
+        #[reflect(@Key)]
+        id: i32,
+        owner: Entity,
+        target: Option<Entity>,
+    }
 
-        app.insert_resource(SqliteConnectionSettings::default());
-        app.insert_resource(SqliteDatabase::default());
+    impl Default for Snapshot {
+        fn default() -> Self {
+            Snapshot {
+                id: 0,
+                owner: Entity::PLACEHOLDER,
+                target: None,
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::SqliteDatabase;
-    use crate::prelude::SqliteConnectionSettings;
-    use bevy::prelude::*;
-    use bevy_erm::prelude::{ErmTypesRegistry, Key, TableDefinition};
+    fn update_database_path_72(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_72.sqlite");
+        registry.register_type::<Snapshot>(&app_registry);
+    }
+
+    fn run_test_72(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Snapshot").unwrap();
+        let sql = SqliteDatabase::get_table_sql(table).unwrap();
+        assert!(sql.contains("owner INTEGER NOT NULL"));
+        database.create_table(table).unwrap();
+
+        let mut world = World::new();
+        let owner = world.spawn_empty().id();
+        let target = world.spawn_empty().id();
+        let snapshot = Snapshot {
+            owner,
+            target: Some(target),
+            ..Default::default()
+        };
+        database.insert(table, &snapshot, &registry).unwrap();
+
+        let stored = database
+            .query_scalar::<i64>("SELECT owner FROM Snapshot;", &[])
+            .unwrap();
+        assert_eq!(stored, Some(owner.to_bits() as i64));
+
+        let test: Vec<Snapshot> = database
+            .query(table, "SELECT * FROM Snapshot;", &[])
+            .unwrap();
+        assert_eq!(test[0].owner, owner);
+        assert_eq!(test[0].target, Some(target));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_entity() {
+        let mut app = setup();
+        app.register_type::<Snapshot>();
+        app.add_systems(PreStartup, update_database_path_72);
+        app.add_systems(Startup, run_test_72);
+
+        app.update();
+    }
 
+    // Test 73
     #[derive(Default, Reflect)]
     #[reflect(Default)]
-    struct Player {
+    struct Item {
         #[reflect(@Key)]
         id: i32,
         name: String,
-        deaths: i32,
-        email: String,
+        owner: i32,
     }
 
-    fn setup() -> App {
-        let mut app = App::new();
-        app.insert_resource(AppTypeRegistry::default());
-        app.add_plugins(SqliteDatabase::default());
-        app.register_type::<Player>();
-
-        app
+    fn update_database_path_73(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_73.sqlite");
+        registry.register_type::<Player>(&app_registry);
+        registry.register_type::<Item>(&app_registry);
     }
 
-    // Test 1
-    fn update_database_path_1(mut settings: ResMut<SqliteConnectionSettings>) {
-        settings.set_data_source("test_1.sqlite");
-    }
-    fn run_test_1(mut database: ResMut<SqliteDatabase>, settings: Res<SqliteConnectionSettings>) {
+    fn run_test_73(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
         database.open(&settings).unwrap();
-        assert!(!database.table_exists("Player"));
 
-        let rows = database
-            .execute(
-                "
-            CREATE TABLE Player 
-            (
-                id INTEGER PRIMARY KEY, 
-                name TEXT NOT NULL, 
-                deaths INTEGER NOT NULL DEFAULT 0
-            );",
+        let player_table = erm_registry.get_table_definition("Player").unwrap();
+        let item_table = erm_registry.get_table_definition("Item").unwrap();
+        database.create_table(player_table).unwrap();
+        database.create_table(item_table).unwrap();
+        insert_player(
+            player_table,
+            &registry,
+            &mut database,
+            1,
+            "Runna",
+            "test_1@testen.com",
+        );
+        insert_player(
+            player_table,
+            &registry,
+            &mut database,
+            2,
+            "Bernd",
+            "test_2@testen.com",
+        );
+        for (name, owner) in [("Sword", 1), ("Shield", 2), ("Potion", 1)] {
+            let item = Item {
+                name: name.to_string(),
+                owner,
+                ..Default::default()
+            };
+            database.insert(item_table, &item, &registry).unwrap();
+        }
+
+        // The items are grouped by the name of their owner, which is not a field of Item.
+        let test: HashMap<String, Vec<Item>> = database
+            .query_grouped(
+                item_table,
+                "player_name",
+                "SELECT Item.*, Player.name AS player_name FROM Item \
+                 JOIN Player ON Player.id = Item.owner ORDER BY Item.id;",
                 &[],
             )
             .unwrap();
-        assert_eq!(rows, 0);
-        assert!(database.table_exists("Player"));
+        assert_eq!(test.len(), 2);
+        let names: Vec<&str> = test["Runna"].iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["Sword", "Potion"]);
+        assert_eq!(test["Bernd"][0].name, "Shield");
+
+        // The group column has to be part of the result.
+        let result: Result<HashMap<i32, Vec<Item>>, SqliteDbError> =
+            database.query_grouped(item_table, "player", "SELECT * FROM Item;", &[]);
+        assert!(result.is_err());
 
         // Delete the file, so we can rerun the test
         std::fs::remove_file(settings.get_data_source()).unwrap();
@@ -488,37 +7929,70 @@ mod tests {
     }
 
     #[test]
-    fn test_database_connection() {
+    fn test_query_grouped() {
         let mut app = setup();
-        app.add_systems(PreStartup, update_database_path_1);
-        app.add_systems(Startup, run_test_1);
+        app.register_type::<Item>();
+        app.add_systems(PreStartup, update_database_path_73);
+        app.add_systems(Startup, run_test_73);
 
         app.update();
     }
 
-    // Test 2
-    fn update_database_path_2(
+    // Test 74
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Purse {
+        #[reflect(@Key)]
+        id: i32,
+        owner: String,
+        gold: i32,
+        note: Option<String>,
+        #[reflect(@Checksum)]
+        checksum: i64,
+    }
+
+    fn update_database_path_74(
         mut settings: ResMut<SqliteConnectionSettings>,
         app_registry: Res<AppTypeRegistry>,
         mut registry: ResMut<ErmTypesRegistry>,
     ) {
-        settings.set_data_source("test_2.sqlite");
-        registry.register_type::<Player>(&app_registry);
+        settings.set_data_source("test_74.sqlite");
+        registry.register_type::<Purse>(&app_registry);
     }
-    fn run_test_2(
-        registry: Res<ErmTypesRegistry>,
+
+    fn run_test_74(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
         mut database: ResMut<SqliteDatabase>,
         settings: Res<SqliteConnectionSettings>,
     ) {
         database.open(&settings).unwrap();
-        assert!(!database.table_exists("Player"));
 
-        assert!(registry.get_table_definition("Player").is_some());
-        assert!(database
-            .create_table(registry.get_table_definition("Player").unwrap())
-            .is_ok());
+        let table = erm_registry.get_table_definition("Purse").unwrap();
+        database.create_table(table).unwrap();
 
-        assert!(database.table_exists("Player"));
+        let purse = Purse {
+            owner: "Timo".to_string(),
+            gold: 100,
+            note: Some("Not stored".to_string()),
+            ..Default::default()
+        };
+        database
+            .insert_set(table, &purse, &["owner", "gold", "note"], &registry)
+            .unwrap();
+
+        // The note is left out, so the stored row differs from the given value.
+        database
+            .insert_set(table, &purse, &["owner", "gold"], &registry)
+            .unwrap();
+
+        let test: Vec<Purse> = database
+            .query(table, "SELECT * FROM Purse ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[1].note, None);
+        assert_ne!(test[0].checksum, test[1].checksum);
+        assert!(database.verify_row(table, &test[0], &registry).unwrap());
+        assert!(database.verify_row(table, &test[1], &registry).unwrap());
 
         // Delete the file, so we can rerun the test
         std::fs::remove_file(settings.get_data_source()).unwrap();
@@ -527,72 +8001,106 @@ mod tests {
     }
 
     #[test]
-    fn test_simple_table_generation() {
+    fn test_insert_set_checksum() {
         let mut app = setup();
-        app.add_systems(PreStartup, update_database_path_2);
-        app.add_systems(Startup, run_test_2);
+        app.register_type::<Purse>();
+
+        app.add_systems(PreStartup, update_database_path_74);
+        app.add_systems(Startup, run_test_74);
 
         app.update();
     }
 
-    // Test 3
-    fn update_database_path_3(
+    // Test 75
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Rune {
+        #[reflect(@Key)]
+        id: i32,
+        strokes: (u8, u8),
+    }
+
+    fn update_database_path_75(
         mut settings: ResMut<SqliteConnectionSettings>,
         app_registry: Res<AppTypeRegistry>,
         mut registry: ResMut<ErmTypesRegistry>,
     ) {
-        settings.set_data_source("test_3.sqlite");
-        registry.register_type::<Player>(&app_registry);
+        settings.set_data_source("test_75.sqlite");
+        registry.register_type::<Rune>(&app_registry);
     }
 
-    fn insert_player(
-        table : &TableDefinition, 
-        registry : &AppTypeRegistry,
-        database : &mut SqliteDatabase,
-        deaths : i32, 
-        name : &str,
-        email : &str) {
-        let test = Player {
-            deaths,
-            name: name.to_string(),
-            email : email.to_string(),
+    fn run_test_75(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
 
-            ..Default::default()
-        };
+        // The error of the generated SQL is returned as is.
+        let table = erm_registry.get_table_definition("Rune").unwrap();
+        assert!(matches!(
+            database.create_table(table),
+            Err(SqliteDbError::UnsupportedType(_))
+        ));
+        assert!(!database.table_exists("Rune").unwrap());
 
-        match database.insert(table, &test, registry) {
-            Ok(_) => {}
-            Err(e) => {
-                println!("Could not insert item: {}", e);
-                panic!("Could not insert item: {}", e);
-            }
-        }
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
     }
 
-    fn run_test_3(
-        registry: Res<AppTypeRegistry>,
+    #[test]
+    fn test_create_table_error() {
+        let mut app = setup();
+        app.register_type::<Rune>();
+
+        app.add_systems(PreStartup, update_database_path_75);
+        app.add_systems(Startup, run_test_75);
+
+        app.update();
+    }
+
+    // Test 76
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Totem {
+        #[reflect(@Key)]
+        id: i32,
+        power: i64,
+    }
+
+    fn update_database_path_76(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_76.sqlite");
+        registry.register_type::<Totem>(&app_registry);
+    }
+
+    fn run_test_76(
         erm_registry: Res<ErmTypesRegistry>,
         mut database: ResMut<SqliteDatabase>,
         settings: Res<SqliteConnectionSettings>,
     ) {
         database.open(&settings).unwrap();
 
-        assert!(erm_registry.get_table_definition("Player").is_some());
-        assert!(database
-            .create_table(erm_registry.get_table_definition("Player").unwrap())
-            .is_ok());
-
-        let table = erm_registry.get_table_definition("Player").unwrap();
-        insert_player(table, &registry, &mut database, 10, "Runna vom Sofa", "test_1@testen.com");
-        insert_player(table, &registry, &mut database, 30, "Anne Straße", "test_2@testen.com");
-        insert_player(table, &registry, &mut database, 100, "Timo Beil", "test_3@testen.com");
-        insert_player(table, &registry, &mut database, 24, "Rainer Szuvall", "test_4@testen.com");
+        let table = erm_registry.get_table_definition("Totem").unwrap();
+        database.create_table(table).unwrap();
+        database
+            .execute("INSERT INTO Totem (id, power) VALUES (1, 9);", &[])
+            .unwrap();
 
-        let test : Vec<Player> = database.query(table, "SELECT * FROM 'Player' WHERE name LIKE 'Timo%';", &[]).unwrap();
-        assert!(!test.is_empty());
-        assert_eq!(test[0].deaths, 100);
-        assert_eq!(test[0].name, "Timo Beil".to_string());
-        assert_eq!(test[0].email, "test_3@testen.com".to_string());
+        // Columns of an unreadable type fail the row instead of panicking.
+        let mut table: TableDefinition = table.clone();
+        table.fields.get_mut("power").unwrap().sql_type = SqlType::Integer(128, true);
+        let result = database.query::<Totem>(&table, "SELECT * FROM Totem;", &[]);
+        let Err(SqliteDbError::MapRow(0, rusqlite::Error::InvalidColumnType(_, name, _))) = result
+        else {
+            panic!("The row must not be mapped.");
+        };
+        assert_eq!(name, "power");
 
         // Delete the file, so we can rerun the test
         std::fs::remove_file(settings.get_data_source()).unwrap();
@@ -601,10 +8109,12 @@ mod tests {
     }
 
     #[test]
-    fn test_insert_item() {
+    fn test_read_row_invalid_type() {
         let mut app = setup();
-        app.add_systems(PreStartup, update_database_path_3);
-        app.add_systems(Startup, run_test_3);
+        app.register_type::<Totem>();
+
+        app.add_systems(PreStartup, update_database_path_76);
+        app.add_systems(Startup, run_test_76);
 
         app.update();
     }
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy::reflect::ReflectMut;
+use bevy_erm::prelude::{ColumnDefinition, TableDefinition};
+use rusqlite::types::Value;
+use rusqlite::Row;
+use std::any::Any;
+use std::ops::{Range, RangeInclusive};
+
+/// The suffix of the column storing the start of a range.
+pub const RANGE_MIN_SUFFIX: &str = "_min";
+
+/// The suffix of the column storing the end of a range. The end of a `Range` is exclusive,
+/// the end of a `RangeInclusive` inclusive.
+pub const RANGE_MAX_SUFFIX: &str = "_max";
+
+/// Numeric ranges are stored in two columns, named after the field with the suffixes
+/// `RANGE_MIN_SUFFIX` and `RANGE_MAX_SUFFIX`, e.g. `damage_min` and `damage_max`.
+macro_rules! ranges {
+    ($($t:ty => $sql:literal),* $(,)?) => {
+        /// The column type of the bounds of the range stored in the given column, if it is a
+        /// supported range.
+        pub(crate) fn get_range_sql_type(col: &ColumnDefinition) -> Option<&'static str> {
+            $(
+                if col.ty.is::<Range<$t>>() || col.ty.is::<RangeInclusive<$t>>() {
+                    return Some($sql);
+                }
+            )*
+
+            None
+        }
+
+        /// The values of the bounds of a range.
+        pub(crate) fn get_range_values(value: &dyn Reflect) -> Option<[Value; 2]> {
+            $(
+                if let Some(v) = value.downcast_ref::<Range<$t>>() {
+                    return Some([Value::from(v.start), Value::from(v.end)]);
+                }
+
+                if let Some(v) = value.downcast_ref::<RangeInclusive<$t>>() {
+                    return Some([Value::from(*v.start()), Value::from(*v.end())]);
+                }
+            )*
+
+            None
+        }
+
+        /// Read the bounds stored in the given columns and assign them to a range field.
+        fn assign_range(
+            field: &mut dyn Any,
+            row: &Row,
+            min: usize,
+            max: usize,
+        ) -> rusqlite::Result<()> {
+            $(
+                if let Some(f) = field.downcast_mut::<Range<$t>>() {
+                    *f = row.get::<usize, $t>(min)?..row.get::<usize, $t>(max)?;
+                    return Ok(());
+                }
+
+                if let Some(f) = field.downcast_mut::<RangeInclusive<$t>>() {
+                    *f = row.get::<usize, $t>(min)?..=row.get::<usize, $t>(max)?;
+                    return Ok(());
+                }
+            )*
+
+            Ok(())
+        }
+    };
+}
+
+ranges!(
+    i8 => "INTEGER",
+    i16 => "INTEGER",
+    i32 => "INTEGER",
+    i64 => "INTEGER",
+    u8 => "INTEGER",
+    u16 => "INTEGER",
+    u32 => "INTEGER",
+    f32 => "REAL",
+    f64 => "REAL",
+);
+
+/// The names of the columns storing the bounds of a range, e.g. `damage_min`, `damage_max`.
+pub(crate) fn get_range_column_names(col: &ColumnDefinition) -> [String; 2] {
+    [
+        format!("{}{}", col.sql_name, RANGE_MIN_SUFFIX),
+        format!("{}{}", col.sql_name, RANGE_MAX_SUFFIX),
+    ]
+}
+
+/// Read the bounds of all ranges and assign them to their fields. Ranges with missing bound
+/// columns keep their value.
+pub(crate) fn read_range_columns(
+    table_def: &TableDefinition,
+    names: &[String],
+    row: &Row,
+    value: &mut dyn PartialReflect,
+) -> rusqlite::Result<()> {
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return Ok(());
+    };
+
+    for col in table_def.fields.values() {
+        if get_range_sql_type(col).is_none() {
+            continue;
+        }
+
+        let [min, max] = get_range_column_names(col);
+        let (Some(min), Some(max)) = (
+            names.iter().position(|n| *n == min),
+            names.iter().position(|n| *n == max),
+        ) else {
+            continue;
+        };
+
+        let Some(field) = target
+            .field_mut(&col.rust_name)
+            .and_then(|f| f.try_as_reflect_mut())
+        else {
+            continue;
+        };
+
+        assign_range(field.as_any_mut(), row, min, max)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_range_values;
+    use rusqlite::types::Value;
+
+    #[test]
+    fn test_get_range_values() {
+        assert_eq!(
+            get_range_values(&(10..20)),
+            Some([Value::Integer(10), Value::Integer(20)])
+        );
+        assert_eq!(
+            get_range_values(&(0.5f32..=1.5)),
+            Some([Value::Real(0.5), Value::Real(1.5)])
+        );
+        assert_eq!(get_range_values(&10u64), None);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::prelude::{SqliteDatabase, SqliteDbError, TableSqlOptions};
+use bevy::prelude::*;
+use bevy::reflect::{
+    DynamicEnum, DynamicStruct, DynamicTuple, DynamicVariant, TypeInfo, VariantInfo,
+};
+use bevy_erm::prelude::{ColumnDefinition, Key};
+use rusqlite::types::ValueRef;
+use rusqlite::Row;
+use std::any::TypeId;
+
+/// One to one relations (`SqlType::One2One`) are stored as the key of the related struct, in an
+/// INTEGER column referencing the key column of the related table. Reading a row restores only
+/// the key of the related struct, all other fields keep their default value. Use `find` with
+/// that key to load the related row.
+pub(crate) fn get_one_to_one_sql(
+    def: &ColumnDefinition,
+    type_id: TypeId,
+    not_null: bool,
+    options: &TableSqlOptions,
+) -> Result<String, SqliteDbError> {
+    let not_null = if not_null { " NOT NULL" } else { "" };
+    let (related, related_key) = get_related_table(options, def, type_id)?;
+
+    Ok(format!(
+        "{} INTEGER{not_null} REFERENCES '{related}'({related_key})",
+        def.sql_name
+    ))
+}
+
+/// The table name and key column of the related type of a relation, looked up by its type id
+/// in the type registry of the options.
+pub(crate) fn get_related_table(
+    options: &TableSqlOptions,
+    col: &ColumnDefinition,
+    type_id: TypeId,
+) -> Result<(String, String), SqliteDbError> {
+    let Some(registry) = options.get_type_registry() else {
+        return Err(format!(
+            "A type registry is required to create the relation {}.",
+            col.sql_name
+        )
+        .into());
+    };
+
+    let registry = registry.read();
+    let Some(registration) = registry.get(type_id) else {
+        return Err(format!(
+            "The related type of the relation {} is not registered.",
+            col.sql_name
+        )
+        .into());
+    };
+
+    let info = registration.type_info();
+    let table = info.type_path_table().short_path().to_string();
+    let TypeInfo::Struct(fields) = info else {
+        return Err(SqliteDbError::NoKeyColumn(table));
+    };
+    let Some(key) = fields.iter().find(|x| x.has_attribute::<Key>()) else {
+        return Err(SqliteDbError::NoKeyColumn(table));
+    };
+
+    Ok((table, key.name().to_string()))
+}
+
+/// The value of the key field of a struct, if it has one.
+pub(crate) fn get_related_key(value: &dyn Struct) -> Option<&dyn Reflect> {
+    let info = value.get_represented_struct_info()?;
+    let key = info.iter().find(|x| x.has_attribute::<Key>())?;
+
+    value.field(key.name())?.try_as_reflect()
+}
+
+/// Read the key stored in the given column into the related struct of field `name`. NULL is
+/// read as `None` for optional relations and skipped otherwise.
+pub(crate) fn read_related_key(
+    type_info: Option<&TypeInfo>,
+    row: &Row,
+    x: usize,
+    name: &str,
+    dyn_type: &mut DynamicStruct,
+) -> rusqlite::Result<()> {
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return Ok(());
+    };
+
+    let Some(field_info) = info.field(name).and_then(|x| x.type_info()) else {
+        return Ok(());
+    };
+
+    let is_null = matches!(row.get_ref(x)?, ValueRef::Null);
+    match field_info {
+        TypeInfo::Struct(_) if is_null => {}
+        TypeInfo::Struct(_) => {
+            if let Some(key) = read_key(field_info, row, x)? {
+                dyn_type.insert(name, key);
+            }
+        }
+        TypeInfo::Enum(option) if option.type_path().starts_with("core::option::Option<") => {
+            if is_null {
+                dyn_type.insert(name, DynamicEnum::new("None", DynamicVariant::Unit));
+                return Ok(());
+            }
+
+            let Some(VariantInfo::Tuple(some)) = option.variant("Some") else {
+                return Ok(());
+            };
+            let Some(related) = some.field_at(0).and_then(|x| x.type_info()) else {
+                return Ok(());
+            };
+
+            if let Some(key) = read_key(related, row, x)? {
+                let mut inner = DynamicTuple::default();
+                inner.insert(key);
+                dyn_type.insert(name, DynamicEnum::new("Some", DynamicVariant::Tuple(inner)));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// A dynamic struct of the related type, which contains only its key.
+fn read_key(related: &TypeInfo, row: &Row, x: usize) -> rusqlite::Result<Option<DynamicStruct>> {
+    let TypeInfo::Struct(info) = related else {
+        return Ok(None);
+    };
+
+    let Some(key) = info.iter().find(|x| x.has_attribute::<Key>()) else {
+        return Ok(None);
+    };
+
+    let mut value = DynamicStruct::default();
+    if !SqliteDatabase::read_unmapped_column(Some(related), row, x, key.name(), &mut value)? {
+        return Ok(None);
+    }
+
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, TableSqlOptions};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Default, Reflect, Clone, Debug, PartialEq)]
+    #[reflect(Default)]
+    struct Kingdom {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Castle {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        kingdom: Kingdom,
+        conquered_by: Option<Kingdom>,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_relation.sqlite");
+        registry.register_type::<Kingdom>(&app_registry);
+        registry.register_type::<Castle>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let kingdom_table = erm_registry.get_table_definition("Kingdom").unwrap();
+        let castle_table = erm_registry.get_table_definition("Castle").unwrap();
+
+        let mut options = TableSqlOptions::for_type::<Castle>();
+        options.set_type_registry(Some(&registry));
+        let sql = SqliteDatabase::get_table_sql_with_options(castle_table, &options).unwrap();
+        assert!(sql.contains("kingdom INTEGER NOT NULL REFERENCES 'Kingdom'(id)"));
+        assert!(sql.contains("conquered_by INTEGER REFERENCES 'Kingdom'(id)"));
+
+        // The related table can only be resolved through the type registry.
+        assert!(SqliteDatabase::get_table_sql(castle_table).is_err());
+
+        database.create_table(kingdom_table).unwrap();
+        database.create_table(castle_table).unwrap();
+        for name in ["Gondor", "Mordor"] {
+            let kingdom = Kingdom {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            database.insert(kingdom_table, &kingdom, &registry).unwrap();
+        }
+
+        let castles = [
+            Castle {
+                name: "Minas Tirith".to_string(),
+                kingdom: Kingdom {
+                    id: 1,
+                    name: "Gondor".to_string(),
+                },
+                conquered_by: None,
+                ..Default::default()
+            },
+            Castle {
+                name: "Minas Morgul".to_string(),
+                kingdom: Kingdom {
+                    id: 1,
+                    name: "Gondor".to_string(),
+                },
+                conquered_by: Some(Kingdom {
+                    id: 2,
+                    name: "Mordor".to_string(),
+                }),
+                ..Default::default()
+            },
+        ];
+        for castle in castles.iter() {
+            database.insert(castle_table, castle, &registry).unwrap();
+        }
+
+        let stored: Vec<Option<i64>> = database
+            .query_column("SELECT conquered_by FROM Castle ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(stored, vec![None, Some(2)]);
+
+        // Only the keys of the related kingdoms are read.
+        let test: Vec<Castle> = database
+            .query(castle_table, "SELECT * FROM Castle ORDER BY id;", &[])
+            .unwrap();
+        assert_eq!(test[0].kingdom.id, 1);
+        assert_eq!(test[0].kingdom.name, "");
+        assert_eq!(test[0].conquered_by, None);
+        assert_eq!(test[1].conquered_by.as_ref().map(|x| x.id), Some(2));
+
+        let kingdom: Option<Kingdom> = database.find(kingdom_table, &test[1].kingdom.id).unwrap();
+        assert_eq!(kingdom.unwrap().name, "Gondor");
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_one_to_one() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Kingdom>();
+        app.register_type::<Castle>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+/// Describes how often opening a database is attempted before giving up. Between two attempts
+/// the caller waits for an exponentially growing backoff, starting with the initial backoff.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    attempts: u32,
+    initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(attempts: u32, initial_backoff: Duration) -> Self {
+        RetryPolicy {
+            attempts: attempts.max(1),
+            initial_backoff,
+        }
+    }
+
+    /// The total number of attempts, including the first one. Always at least 1.
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn get_initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+
+    /// The time to wait after the given (zero based) failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(5, Duration::from_millis(50))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(10));
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(1), Duration::from_millis(20));
+        assert_eq!(policy.backoff(2), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_at_least_one_attempt() {
+        let policy = RetryPolicy::new(0, Duration::from_millis(10));
+        assert_eq!(policy.get_attempts(), 1);
+    }
+}
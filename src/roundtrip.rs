@@ -0,0 +1,118 @@
+use crate::prelude::{SqliteConnectionSettings, SqliteDatabase, TableSqlOptions};
+use bevy::prelude::*;
+use bevy::reflect::{GetTypeRegistration, Typed};
+use bevy_erm::prelude::ErmTypesRegistry;
+
+impl SqliteDatabase {
+    /// Insert the value into a table of an in-memory database, read it back and compare both
+    /// field by field. Panics with the name of the first field which differs. Key columns are
+    /// skipped, because their values are assigned by the database. Use this in the tests of
+    /// your crate to make sure that your types survive the round trip.
+    pub fn assert_roundtrip<T>(value: &T)
+    where
+        T: Struct + Reflect + Typed + TypePath + GetTypeRegistration + Default,
+    {
+        let app_registry = AppTypeRegistry::default();
+        app_registry.write().register::<T>();
+        let mut registry = ErmTypesRegistry::default();
+        registry.register_type::<T>(&app_registry);
+        let type_name = T::short_type_path();
+        let Some(table) = registry.get_table_definition(type_name) else {
+            panic!("{type_name} could not be registered.");
+        };
+
+        let mut settings = SqliteConnectionSettings::new();
+        settings.use_in_memory(true);
+        let mut database = SqliteDatabase::default();
+        database.open(&settings).unwrap();
+        database
+            .create_table_with_options(table, &TableSqlOptions::for_type::<T>())
+            .unwrap_or_else(|e| panic!("Could not create the table of {type_name}: {e}"));
+        database
+            .insert(table, value, &app_registry)
+            .unwrap_or_else(|e| panic!("Could not insert {type_name}: {e}"));
+
+        let query = format!("SELECT * FROM '{}';", table.sql_name);
+        let rows: Vec<T> = database
+            .query(table, &query, &[])
+            .unwrap_or_else(|e| panic!("Could not read {type_name}: {e}"));
+        database.close().unwrap();
+        let [read] = rows.as_slice() else {
+            panic!("Expected one row of {type_name}, but got {}.", rows.len());
+        };
+
+        for (x, written) in value.iter_fields().enumerate() {
+            let name = value.name_at(x).unwrap();
+            if table.get(name).is_some_and(|col| col.is_key()) {
+                continue;
+            }
+
+            let read = read.field_at(x).unwrap();
+            let equal = written
+                .reflect_partial_eq(read)
+                .unwrap_or_else(|| format!("{written:?}") == format!("{read:?}"));
+            assert!(
+                equal,
+                "Field {name} of {type_name} did not survive the round trip: wrote {written:?}, \
+                 read {read:?}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::SqliteDatabase;
+    use bevy::prelude::*;
+    use bevy_erm::prelude::Key;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Ship {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+        crew: u16,
+        speed: f32,
+        docked: bool,
+        captain: Option<String>,
+        position: Vec3,
+        cell: IVec2,
+        heading: Quat,
+        color: Srgba,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Wreck {
+        #[reflect(@Key)]
+        id: i32,
+        cargo: Vec<u32>,
+    }
+
+    #[test]
+    fn test_assert_roundtrip() {
+        SqliteDatabase::assert_roundtrip(&Ship {
+            id: 12,
+            name: "Black Pearl".to_string(),
+            crew: 40,
+            speed: 12.5,
+            docked: true,
+            captain: Some("Jack".to_string()),
+            position: Vec3::new(1.0, -2.0, 3.0),
+            cell: IVec2::new(-7, 9),
+            heading: Quat::from_rotation_z(0.75),
+            color: Srgba::rgb(0.5, 0.25, 1.0),
+        });
+        SqliteDatabase::assert_roundtrip(&Ship::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "Field cargo of Wreck")]
+    fn test_assert_roundtrip_fails() {
+        SqliteDatabase::assert_roundtrip(&Wreck {
+            cargo: vec![1, 2, 3],
+            ..Default::default()
+        });
+    }
+}
@@ -0,0 +1,167 @@
+use crate::plugin::sort_columns;
+use crate::prelude::SqliteDatabase;
+use bevy::prelude::*;
+use bevy_erm::prelude::{ErmTypesRegistry, SqlType, TableDefinition};
+use std::any::TypeId;
+
+impl SqliteDatabase {
+    /// Export all registered tables and their relations as a Graphviz DOT graph. Tables are
+    /// rendered as records listing their columns, relations as edges between the tables.
+    /// Only the registered definitions are used, so no connection is required.
+    pub fn schema_dot(registry: &ErmTypesRegistry, app_registry: &AppTypeRegistry) -> String {
+        let mut tables: Vec<&TableDefinition> = app_registry
+            .read()
+            .iter()
+            .filter_map(|x| {
+                registry.get_table_definition(x.type_info().type_path_table().short_path())
+            })
+            .collect();
+        tables.sort_by(|a, b| a.sql_name.cmp(&b.sql_name));
+
+        let mut nodes: Vec<String> = Vec::new();
+        let mut edges: Vec<String> = Vec::new();
+        for table in tables {
+            let columns = sort_columns(table);
+
+            let mut rows: Vec<String> = Vec::new();
+            for column in columns {
+                let key = if column.is_key() { " (key)" } else { "" };
+                rows.push(format!(
+                    "{} : {}{}\\l",
+                    escape(&column.sql_name),
+                    sql_type_name(&column.sql_type),
+                    key
+                ));
+
+                match column.sql_type {
+                    SqlType::One2One(type_id, _) => {
+                        let target = related_table_name(app_registry, type_id);
+                        edges.push(format!(
+                            "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                            table.sql_name, target, column.sql_name
+                        ));
+                    }
+                    SqlType::Many2Many(type_id, _) => {
+                        let target = related_table_name(app_registry, type_id);
+                        edges.push(format!(
+                            "    \"{}\" -> \"{}\" [label=\"{}\", dir=both];",
+                            table.sql_name, target, column.sql_name
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+
+            nodes.push(format!(
+                "    \"{}\" [label=\"{{{}|{}}}\"];",
+                table.sql_name,
+                escape(&table.sql_name),
+                rows.join("")
+            ));
+        }
+
+        let mut dot = String::from("digraph schema {\n    node [shape=record];\n");
+        for line in nodes.iter().chain(edges.iter()) {
+            dot.push_str(line);
+            dot.push('\n');
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+/// The name of the table storing the type with the given id.
+fn related_table_name(app_registry: &AppTypeRegistry, type_id: TypeId) -> String {
+    match app_registry.read().get(type_id) {
+        Some(registration) => registration
+            .type_info()
+            .type_path_table()
+            .short_path()
+            .to_string(),
+        None => format!("{:?}", type_id),
+    }
+}
+
+fn sql_type_name(sql_type: &SqlType) -> &'static str {
+    match sql_type {
+        SqlType::None => "NONE",
+        SqlType::Integer(_, _) => "INTEGER",
+        SqlType::UnsingedInteger(_, _) => "UNSIGNED INTEGER",
+        SqlType::Float(_, _) => "REAL",
+        SqlType::Text(_) => "TEXT",
+        SqlType::Date(_) => "DATE",
+        SqlType::Time(_) => "TIME",
+        SqlType::DateTime(_) => "DATETIME",
+        SqlType::Blob(_) => "BLOB",
+        SqlType::Boolean(_) => "BOOLEAN",
+        SqlType::One2One(_, _) => "ONE TO ONE",
+        SqlType::Many2Many(_, _) => "MANY TO MANY",
+    }
+}
+
+/// Escape characters with a special meaning in record labels.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::SqliteDatabase;
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Player {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Guild {
+        #[reflect(@Key)]
+        id: i32,
+        motto: String,
+    }
+
+    fn register_types(app_registry: Res<AppTypeRegistry>, mut registry: ResMut<ErmTypesRegistry>) {
+        registry.register_type::<Player>(&app_registry);
+        registry.register_type::<Guild>(&app_registry);
+    }
+
+    fn run_test(app_registry: Res<AppTypeRegistry>, registry: Res<ErmTypesRegistry>) {
+        let dot = SqliteDatabase::schema_dot(&registry, &app_registry);
+
+        assert!(dot.starts_with("digraph schema {"));
+        assert!(dot.contains("\"Guild\" [label=\"{Guild|id : INTEGER (key)\\lmotto : TEXT\\l}\"];"));
+        assert!(
+            dot.contains("\"Player\" [label=\"{Player|id : INTEGER (key)\\lname : TEXT\\l}\"];")
+        );
+        // Tables are sorted by name.
+        assert!(dot.find("\"Guild\"").unwrap() < dot.find("\"Player\"").unwrap());
+    }
+
+    #[test]
+    fn test_schema_dot() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Player>();
+        app.register_type::<Guild>();
+        app.add_systems(PreStartup, register_types);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
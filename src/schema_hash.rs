@@ -0,0 +1,266 @@
+//! Schema hashes allow to detect changed table definitions without comparing every column
+//! of the database. Hashes are only recorded by the migration API (`migrate_changed` and
+//! `open_and_migrate`), plain `create_table` does not touch the hash table.
+
+use crate::checksum::Fnv1a;
+use crate::prelude::{
+    MigrationStep, SqliteConnectionSettings, SqliteDatabase, SqliteDbError, TableSqlOptions,
+};
+use bevy_erm::prelude::{ErmTypesRegistry, TableDefinition};
+use rusqlite::ToSql;
+
+/// The table storing the schema hash of every table created by this crate.
+pub const SCHEMA_HASH_TABLE: &str = "_erm_schema_hashes";
+
+impl SqliteDatabase {
+    /// A deterministic hash of the SQL generated to create the table.
+    pub fn schema_hash(&self, def: &TableDefinition) -> Result<u64, SqliteDbError> {
+        self.schema_hash_with_options(def, &TableSqlOptions::default())
+    }
+
+    /// Same as schema_hash, but generates the SQL using the given options. Like create_table,
+    /// the converters and the type registry of the database are taken into account.
+    pub fn schema_hash_with_options(
+        &self,
+        def: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<u64, SqliteDbError> {
+        let sql = Self::get_table_sql_with_options(def, &self.get_sql_options(options))?;
+
+        let mut hasher = Fnv1a::new();
+        hasher.write(sql.as_bytes());
+
+        Ok(hasher.finish())
+    }
+
+    /// Store the current schema hash of the given table.
+    pub fn store_schema_hash(
+        &mut self,
+        def: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<(), SqliteDbError> {
+        self.create_schema_hash_table()?;
+
+        let hash = self.schema_hash_with_options(def, options)? as i64;
+        let query = format!(
+            "INSERT INTO {SCHEMA_HASH_TABLE} (table_name, hash) VALUES (?1, ?2) \
+             ON CONFLICT(table_name) DO UPDATE SET hash = excluded.hash;"
+        );
+        self.execute(&query, &[&def.sql_name as &dyn ToSql, &hash])?;
+
+        Ok(())
+    }
+
+    /// Get the stored schema hash of the given table, if there is one.
+    pub fn get_stored_schema_hash(
+        &mut self,
+        table_name: &str,
+    ) -> Result<Option<u64>, SqliteDbError> {
+        if !self.table_exists(SCHEMA_HASH_TABLE)? {
+            return Ok(None);
+        }
+
+        let query = format!("SELECT hash FROM {SCHEMA_HASH_TABLE} WHERE table_name = ?1;");
+        let hashes: Vec<i64> = self.query_column(&query, &[&table_name])?;
+
+        Ok(hashes.first().map(|x| *x as u64))
+    }
+
+    /// Returns true, if the stored schema hash of the table differs from the hash of the given
+    /// definition or if no hash was stored yet.
+    pub fn schema_changed(
+        &mut self,
+        def: &TableDefinition,
+        options: &TableSqlOptions,
+    ) -> Result<bool, SqliteDbError> {
+        let current = self.schema_hash_with_options(def, options)?;
+        let stored = self.get_stored_schema_hash(&def.sql_name)?;
+
+        Ok(stored != Some(current))
+    }
+
+    /// Migrate the tables of the given types whose schema hash changed since the last call and
+    /// record their new hashes. Tables with an unchanged hash are skipped without comparing
+    /// their columns, so this is cheap when nothing changed. Returns the applied steps. The
+    /// migration has the limits of `plan_migration`.
+    pub fn migrate_changed(
+        &mut self,
+        registry: &ErmTypesRegistry,
+        type_names: &[&str],
+    ) -> Result<Vec<MigrationStep>, SqliteDbError> {
+        let options = TableSqlOptions::default();
+
+        let mut changed: Vec<&TableDefinition> = Vec::new();
+        for name in type_names {
+            let Some(def) = registry.get_table_definition(name) else {
+                return Err(SqliteDbError::UnknownType(name.to_string()));
+            };
+
+            if self.schema_changed(def, &options)? {
+                changed.push(def);
+            }
+        }
+
+        let names: Vec<&str> = changed.iter().map(|x| x.sql_name.as_str()).collect();
+        let steps = self.plan_migration(registry, &names)?;
+        self.apply_migration(&steps)?;
+
+        for def in changed {
+            self.store_schema_hash(def, &options)?;
+        }
+
+        Ok(steps)
+    }
+
+    /// Open the database and migrate the tables of the given types whose schema changed
+    /// (see `migrate_changed`).
+    pub fn open_and_migrate(
+        &mut self,
+        settings: &SqliteConnectionSettings,
+        registry: &ErmTypesRegistry,
+        type_names: &[&str],
+    ) -> Result<Vec<MigrationStep>, SqliteDbError> {
+        self.open(settings)?;
+        self.migrate_changed(registry, type_names)
+    }
+
+    /// Remove the stored schema hash of the given table, e.g. after dropping it.
+    pub(crate) fn remove_schema_hash(&mut self, table_name: &str) -> Result<(), SqliteDbError> {
+        if !self.table_exists(SCHEMA_HASH_TABLE)? {
+            return Ok(());
+        }
+
+        let query = format!("DELETE FROM {SCHEMA_HASH_TABLE} WHERE table_name = ?1;");
+        self.execute(&query, &[&table_name])?;
+
+        Ok(())
+    }
+
+    fn create_schema_hash_table(&mut self) -> Result<(), SqliteDbError> {
+        self.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {SCHEMA_HASH_TABLE} \
+                 (table_name TEXT PRIMARY KEY, hash INTEGER NOT NULL);"
+            ),
+            &[],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{
+        SqliteConnectionSettings, SqliteDatabase, TableSqlOptions, SCHEMA_HASH_TABLE,
+    };
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Player {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Guild {
+        #[reflect(@Key)]
+        id: i32,
+        motto: String,
+        members: i32,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_schema_hash.sqlite");
+        registry.register_type::<Player>(&app_registry);
+        registry.register_type::<Guild>(&app_registry);
+    }
+
+    fn run_test(
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let player = erm_registry.get_table_definition("Player").unwrap();
+        let guild = erm_registry.get_table_definition("Guild").unwrap();
+
+        let options = TableSqlOptions::default();
+        let hash = database.schema_hash(player).unwrap();
+        assert_eq!(hash, database.schema_hash(player).unwrap());
+        assert_ne!(hash, database.schema_hash(guild).unwrap());
+
+        // Creating a table does not record its hash.
+        database.create_table(player).unwrap();
+        assert!(!database.table_exists(SCHEMA_HASH_TABLE).unwrap());
+        assert!(database.schema_changed(player, &options).unwrap());
+
+        // Changed tables are migrated and their hashes recorded.
+        let steps = database
+            .migrate_changed(&erm_registry, &["Player", "Guild"])
+            .unwrap();
+        assert_eq!(steps.len(), 1);
+        assert!(database.table_exists("Guild").unwrap());
+        assert_eq!(
+            database.get_stored_schema_hash("Player").unwrap(),
+            Some(hash)
+        );
+        assert!(!database.schema_changed(player, &options).unwrap());
+        assert!(!database.schema_changed(guild, &options).unwrap());
+
+        // The options are part of the schema.
+        let mut strict = TableSqlOptions::new();
+        strict.use_strict(true);
+        assert!(database.schema_changed(player, &strict).unwrap());
+
+        // A different definition stored under the same name is detected on open.
+        database
+            .execute(
+                "UPDATE _erm_schema_hashes SET hash = 1 WHERE table_name = 'Player';",
+                &[],
+            )
+            .unwrap();
+        database.close().unwrap();
+        let steps = database
+            .open_and_migrate(&settings, &erm_registry, &["Player", "Guild"])
+            .unwrap();
+        assert!(steps.is_empty());
+        assert!(!database.schema_changed(player, &options).unwrap());
+
+        // Nothing is migrated, if no hash changed.
+        database
+            .execute("ALTER TABLE Guild DROP COLUMN members;", &[])
+            .unwrap();
+        let steps = database
+            .migrate_changed(&erm_registry, &["Player", "Guild"])
+            .unwrap();
+        assert!(steps.is_empty());
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_schema_hash() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Player>();
+        app.register_type::<Guild>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
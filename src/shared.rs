@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use bevy::reflect::Type;
+use bevy_erm::prelude::{ColumnDefinition, SqlType};
+use rusqlite::Row;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Shared values (`Arc<T>`) are stored like the value they point to. Reflection treats an
+/// `Arc` as an opaque value, so the supported inner types have to be listed explicitly.
+/// `Box<T>` does not implement `Reflect` and cannot be used as a field.
+macro_rules! shared {
+    ($($t:ty => $sql:expr),* $(,)?) => {
+        /// The column definition of the value inside a shared value, if the column stores one.
+        pub(crate) fn get_shared_column(col: &ColumnDefinition) -> Option<ColumnDefinition> {
+            $(
+                if col.ty.is::<Arc<$t>>() {
+                    let mut inner = col.clone();
+                    inner.sql_type = $sql;
+                    inner.ty = Type::of::<$t>();
+                    return Some(inner);
+                }
+            )*
+
+            None
+        }
+
+        /// The value inside a shared value, if the given value is one.
+        pub(crate) fn deref_shared(value: &dyn Reflect) -> Option<&dyn Reflect> {
+            $(
+                if let Some(v) = value.downcast_ref::<Arc<$t>>() {
+                    return Some(v.as_ref());
+                }
+            )*
+
+            None
+        }
+
+        /// Read the value stored in the given column and assign it to a shared value field.
+        pub(crate) fn assign_shared(
+            field: &mut dyn Any,
+            row: &Row,
+            x: usize,
+        ) -> rusqlite::Result<()> {
+            $(
+                if let Some(f) = field.downcast_mut::<Arc<$t>>() {
+                    *f = Arc::new(row.get::<usize, $t>(x)?);
+                    return Ok(());
+                }
+            )*
+
+            Ok(())
+        }
+    };
+}
+
+shared!(
+    i8 => SqlType::Integer(8, true),
+    i16 => SqlType::Integer(16, true),
+    i32 => SqlType::Integer(32, true),
+    i64 => SqlType::Integer(64, true),
+    u8 => SqlType::UnsingedInteger(8, true),
+    u16 => SqlType::UnsingedInteger(16, true),
+    u32 => SqlType::UnsingedInteger(32, true),
+    u64 => SqlType::UnsingedInteger(64, true),
+    f32 => SqlType::Float(32, true),
+    f64 => SqlType::Float(64, true),
+    bool => SqlType::Boolean(true),
+    String => SqlType::Text(true),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::deref_shared;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_deref_shared() {
+        let value = Arc::new("Shared".to_string());
+        let inner = deref_shared(&value).unwrap();
+        assert_eq!(inner.downcast_ref::<String>(), Some(&"Shared".to_string()));
+        assert!(deref_shared(&"Plain".to_string()).is_none());
+    }
+}
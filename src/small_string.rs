@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+use bevy_erm::prelude::ColumnDefinition;
+use rusqlite::types::Value;
+use rusqlite::Row;
+use std::any::Any;
+
+#[cfg(any(feature = "smol_str", feature = "compact_str"))]
+use crate::plugin::assign_parsed;
+#[cfg(feature = "compact_str")]
+use compact_str::CompactString;
+#[cfg(feature = "smol_str")]
+use smol_str::SmolStr;
+
+/// A `CompactString` which can be used as a field of a reflected struct. `bevy_reflect` does not
+/// implement `Reflect` for `CompactString`, so fields have to use this wrapper instead. It
+/// dereferences to the wrapped string. Requires the `compact_str` feature.
+#[cfg(feature = "compact_str")]
+#[derive(Reflect, Clone, Default, Debug, PartialEq, Eq, Hash)]
+#[reflect(opaque)]
+#[reflect(Default, Debug, PartialEq, Hash)]
+pub struct CompactText(pub CompactString);
+
+#[cfg(feature = "compact_str")]
+impl std::ops::Deref for CompactText {
+    type Target = CompactString;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl std::ops::DerefMut for CompactText {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl From<&str> for CompactText {
+    fn from(value: &str) -> Self {
+        CompactText(CompactString::new(value))
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl From<CompactString> for CompactText {
+    fn from(value: CompactString) -> Self {
+        CompactText(value)
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl std::str::FromStr for CompactText {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CompactText::from(s))
+    }
+}
+
+/// Small strings (`smol_str::SmolStr` and `CompactText`) are stored as text like `String` and
+/// rebuilt from the text on read. Requires the `smol_str` or `compact_str` feature.
+#[cfg(any(feature = "smol_str", feature = "compact_str"))]
+pub(crate) fn is_small_string_column(col: &ColumnDefinition) -> bool {
+    #[cfg(feature = "smol_str")]
+    if col.ty.is::<SmolStr>() || col.ty.is::<Option<SmolStr>>() {
+        return true;
+    }
+
+    #[cfg(feature = "compact_str")]
+    if col.ty.is::<CompactText>() || col.ty.is::<Option<CompactText>>() {
+        return true;
+    }
+
+    false
+}
+
+#[cfg(not(any(feature = "smol_str", feature = "compact_str")))]
+pub(crate) fn is_small_string_column(_col: &ColumnDefinition) -> bool {
+    false
+}
+
+/// The text of a small string, NULL for an empty optional small string.
+#[cfg(any(feature = "smol_str", feature = "compact_str"))]
+pub(crate) fn get_small_string_value(value: &dyn Reflect) -> Option<Value> {
+    let value = value.as_any();
+
+    #[cfg(feature = "smol_str")]
+    {
+        if let Some(v) = value.downcast_ref::<SmolStr>() {
+            return Some(Value::Text(v.to_string()));
+        }
+
+        if let Some(v) = value.downcast_ref::<Option<SmolStr>>() {
+            return Some(
+                v.as_ref()
+                    .map_or(Value::Null, |v| Value::Text(v.to_string())),
+            );
+        }
+    }
+
+    #[cfg(feature = "compact_str")]
+    {
+        if let Some(v) = value.downcast_ref::<CompactText>() {
+            return Some(Value::Text(v.to_string()));
+        }
+
+        if let Some(v) = value.downcast_ref::<Option<CompactText>>() {
+            return Some(
+                v.as_ref()
+                    .map_or(Value::Null, |v| Value::Text(v.to_string())),
+            );
+        }
+    }
+
+    None
+}
+
+#[cfg(not(any(feature = "smol_str", feature = "compact_str")))]
+pub(crate) fn get_small_string_value(_value: &dyn Reflect) -> Option<Value> {
+    None
+}
+
+/// Read the text stored in the given column into a small string field.
+#[cfg(any(feature = "smol_str", feature = "compact_str"))]
+pub(crate) fn assign_small_string(
+    field: &mut dyn Any,
+    row: &Row,
+    x: usize,
+) -> rusqlite::Result<()> {
+    #[cfg(feature = "smol_str")]
+    assign_parsed::<SmolStr>(field, row, x)?;
+    #[cfg(feature = "compact_str")]
+    assign_parsed::<CompactText>(field, row, x)?;
+
+    Ok(())
+}
+
+#[cfg(not(any(feature = "smol_str", feature = "compact_str")))]
+pub(crate) fn assign_small_string(
+    _field: &mut dyn Any,
+    _row: &Row,
+    _x: usize,
+) -> rusqlite::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, feature = "smol_str"))]
+mod tests {
+    use super::{assign_small_string, get_small_string_value};
+    use rusqlite::types::Value;
+    use rusqlite::Connection;
+    use smol_str::SmolStr;
+
+    #[test]
+    fn test_assign_small_string() {
+        let connection = Connection::open_in_memory().unwrap();
+        let (value, empty) = connection
+            .query_row("SELECT 'Runna vom Sofa', NULL;", [], |row| {
+                let mut value = SmolStr::default();
+                assign_small_string(&mut value, row, 0)?;
+                let mut empty = Some(SmolStr::new("Bernd"));
+                assign_small_string(&mut empty, row, 1)?;
+                Ok((value, empty))
+            })
+            .unwrap();
+
+        assert_eq!(value, "Runna vom Sofa");
+        assert_eq!(empty, None);
+
+        assert_eq!(
+            get_small_string_value(&SmolStr::new("Hugo")),
+            Some(Value::Text("Hugo".to_string()))
+        );
+        assert_eq!(get_small_string_value(&None::<SmolStr>), Some(Value::Null));
+        assert_eq!(get_small_string_value(&"Hugo".to_string()), None);
+    }
+}
+
+#[cfg(all(test, feature = "compact_str"))]
+mod compact_tests {
+    use super::{assign_small_string, get_small_string_value, CompactText};
+    use rusqlite::types::Value;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_assign_compact_text() {
+        let connection = Connection::open_in_memory().unwrap();
+        let (value, empty) = connection
+            .query_row("SELECT 'Runna vom Sofa', NULL;", [], |row| {
+                let mut value = CompactText::default();
+                assign_small_string(&mut value, row, 0)?;
+                let mut empty = Some(CompactText::from("Bernd"));
+                assign_small_string(&mut empty, row, 1)?;
+                Ok((value, empty))
+            })
+            .unwrap();
+
+        assert_eq!(value.as_str(), "Runna vom Sofa");
+        assert_eq!(empty, None);
+
+        assert_eq!(
+            get_small_string_value(&CompactText::from("Hugo")),
+            Some(Value::Text("Hugo".to_string()))
+        );
+        assert_eq!(
+            get_small_string_value(&None::<CompactText>),
+            Some(Value::Null)
+        );
+    }
+}
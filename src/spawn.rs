@@ -0,0 +1,155 @@
+//! Rows can be turned into entities directly, so big worlds can be loaded without building all
+//! values first. Every component is handed to `Commands` as soon as its row is read.
+
+use crate::prelude::{SqliteDatabase, SqliteDbError};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_erm::prelude::TableDefinition;
+use rusqlite::ToSql;
+
+impl SqliteDatabase {
+    /// Spawn an entity with a component of type `T` for every row of the query. Rows whose key
+    /// is contained in `entities` are inserted into the existing entity instead, replacing its
+    /// component of type `T`. Returns the number of rows read.
+    pub fn spawn_rows<T: Component + Default + Reflect + Struct>(
+        &mut self,
+        commands: &mut Commands,
+        def: &TableDefinition,
+        query: &str,
+        parameter: &[&dyn ToSql],
+        entities: &HashMap<i64, Entity>,
+    ) -> Result<usize, SqliteDbError> {
+        let key = def
+            .fields
+            .values()
+            .find(|x| x.is_key())
+            .map(|x| &x.rust_name);
+
+        let mut count = 0;
+        self.query_each(def, query, parameter, |value: T| {
+            count += 1;
+
+            let entity = key
+                .and_then(|key| get_key_value(&value, key))
+                .and_then(|key| entities.get(&key));
+            match entity {
+                Some(entity) => {
+                    commands.entity(*entity).insert(value);
+                }
+                None => {
+                    commands.spawn(value);
+                }
+            }
+        })?;
+
+        Ok(count)
+    }
+}
+
+/// The value of an integer key field.
+pub(crate) fn get_key_value(value: &dyn Struct, key: &str) -> Option<i64> {
+    let field = value.field(key)?.try_as_reflect()?;
+
+    macro_rules! key_as {
+        ($($t:ty),*) => {
+            $(
+                if let Some(v) = field.downcast_ref::<$t>() {
+                    return Some(*v as i64);
+                }
+            )*
+        };
+    }
+
+    key_as!(i8, i16, i32, i64, u8, u16, u32);
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase};
+    use bevy::prelude::*;
+    use bevy::utils::HashMap;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+
+    #[derive(Component, Default, Reflect)]
+    #[reflect(Default)]
+    struct Villager {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Resource)]
+    struct Existing(Entity);
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_spawn.sqlite");
+        registry.register_type::<Villager>(&app_registry);
+    }
+
+    fn run_test(
+        mut commands: Commands,
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Villager").unwrap();
+        database.create_table(table).unwrap();
+        for name in ["Baker", "Smith", "Miller"] {
+            let villager = Villager {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            database.insert(table, &villager, &registry).unwrap();
+        }
+
+        let existing = commands.spawn(Villager::default()).id();
+        commands.insert_resource(Existing(existing));
+
+        let mut entities: HashMap<i64, Entity> = HashMap::default();
+        entities.insert(2, existing);
+        let count = database
+            .spawn_rows::<Villager>(
+                &mut commands,
+                table,
+                "SELECT * FROM Villager;",
+                &[],
+                &entities,
+            )
+            .unwrap();
+        assert_eq!(count, 3);
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_spawn_rows() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Villager>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+
+        let existing = app.world().resource::<Existing>().0;
+        let world = app.world_mut();
+        assert_eq!(world.query::<&Villager>().iter(world).count(), 3);
+
+        let villager = world.get::<Villager>(existing).unwrap();
+        assert_eq!(villager.id, 2);
+        assert_eq!(villager.name, "Smith");
+    }
+}
@@ -1,11 +1,18 @@
+use crate::prelude::{RetryPolicy, SqliteDbError};
 use bevy::prelude::*;
 use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+#[cfg(feature = "dirs")]
+use std::path::PathBuf;
 
 #[derive(Resource, Clone)]
 pub struct SqliteConnectionSettings {
     data_source: String,
     version: i32,
     utf_16_encoding: bool,
+    retry_policy: Option<RetryPolicy>,
+    in_memory: bool,
 }
 
 impl SqliteConnectionSettings {
@@ -14,9 +21,45 @@ impl SqliteConnectionSettings {
             data_source: "database.sqlite".to_owned(),
             version: 3,
             utf_16_encoding: false,
+            retry_policy: None,
+            in_memory: false,
         }
     }
 
+    /// Store the database in the data directory of the platform, e.g.
+    /// `~/.local/share/<app_name>/<file_name>` on Linux or
+    /// `%APPDATA%\<app_name>\<file_name>` on Windows. The directory is created if it does not
+    /// exist. The working directory is no good place for saves, because the directory of the
+    /// executable is often not writable.
+    #[cfg(feature = "dirs")]
+    pub fn in_save_dir(app_name: &str, file_name: &str) -> Result<Self, SqliteDbError> {
+        Self::at_path(&Self::get_save_path(app_name, file_name)?)
+    }
+
+    /// The path `in_save_dir` stores the database at. Nothing is created.
+    #[cfg(feature = "dirs")]
+    pub fn get_save_path(app_name: &str, file_name: &str) -> Result<PathBuf, SqliteDbError> {
+        let Some(data_dir) = dirs::data_dir() else {
+            return Err("Could not find the data directory of the platform.".into());
+        };
+
+        Ok(data_dir.join(app_name).join(file_name))
+    }
+
+    /// Store the database at the given path. The parent directory is created if it does not
+    /// exist.
+    pub fn at_path(path: &Path) -> Result<Self, SqliteDbError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Could not create {}: {e}", dir.display()))?;
+        }
+
+        let mut settings = Self::new();
+        settings.set_data_source(&path.to_string_lossy());
+
+        Ok(settings)
+    }
+
     pub fn set_data_source(&mut self, data_source: &str) {
         self.data_source = data_source.to_owned();
     }
@@ -32,6 +75,26 @@ impl SqliteConnectionSettings {
     pub fn use_utf_16_encoding(&mut self, value: bool) {
         self.utf_16_encoding = value;
     }
+
+    /// Retry opening the database according to the given policy. This helps if the file is still
+    /// locked by another process on startup. `None` tries exactly once.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry_policy = policy;
+    }
+
+    pub fn get_retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// Keep the database in memory instead of the data source file. The data lives as long as
+    /// the connection, so closing the database destroys it. Useful for tests and scratch data.
+    pub fn use_in_memory(&mut self, value: bool) {
+        self.in_memory = value;
+    }
+
+    pub fn is_in_memory(&self) -> bool {
+        self.in_memory
+    }
 }
 
 impl Default for SqliteConnectionSettings {
@@ -60,6 +123,7 @@ impl Display for SqliteConnectionSettings {
 #[cfg(test)]
 mod tests {
     use super::SqliteConnectionSettings;
+    use crate::prelude::RetryPolicy;
 
     #[test]
     fn test_default_connection_string() {
@@ -67,6 +131,8 @@ mod tests {
         assert_eq!(cs.data_source, "database.sqlite");
         assert_eq!(cs.version, 3);
         assert!(!cs.utf_16_encoding);
+        assert!(cs.retry_policy.is_none());
+        assert!(!cs.in_memory);
     }
 
     #[test]
@@ -75,9 +141,32 @@ mod tests {
         cs.set_data_source("test.sqlite");
         cs.set_version(2);
         cs.use_utf_16_encoding(true);
+        cs.set_retry_policy(Some(RetryPolicy::default()));
+        cs.use_in_memory(true);
         assert_eq!(cs.data_source, "test.sqlite");
         assert_eq!(cs.version, 2);
         assert!(cs.utf_16_encoding);
+        assert_eq!(cs.get_retry_policy(), Some(&RetryPolicy::default()));
+        assert!(cs.is_in_memory());
+    }
+
+    #[test]
+    fn test_at_path() {
+        let dir = std::env::temp_dir().join("bevy_erm_sqlite_test_at_path");
+        let cs = SqliteConnectionSettings::at_path(&dir.join("save.sqlite")).unwrap();
+        let path = std::path::Path::new(cs.get_data_source());
+        assert!(path.ends_with("bevy_erm_sqlite_test_at_path/save.sqlite"));
+        assert!(dir.is_dir());
+
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[cfg(feature = "dirs")]
+    #[test]
+    fn test_get_save_path() {
+        let path =
+            SqliteConnectionSettings::get_save_path("bevy_erm_sqlite_test", "save.sqlite").unwrap();
+        assert!(path.ends_with("bevy_erm_sqlite_test/save.sqlite"));
     }
 
     #[test]
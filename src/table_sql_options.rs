@@ -0,0 +1,148 @@
+use crate::attributes::get_field_attribute;
+use bevy::prelude::*;
+use bevy::reflect::{TypeInfo, TypeRegistryArc, Typed};
+use std::any::TypeId;
+
+/// Options controlling the SQL generated to create a table.
+#[derive(Clone, Debug, Default)]
+pub struct TableSqlOptions {
+    strict: bool,
+    unique_orders: bool,
+    if_not_exists: bool,
+    type_info: Option<&'static TypeInfo>,
+    type_registry: Option<TypeRegistryArc>,
+    converted_types: Vec<TypeId>,
+}
+
+impl TableSqlOptions {
+    pub fn new() -> Self {
+        TableSqlOptions::default()
+    }
+
+    /// Options reading column attributes (e.g. `Collate`) from the fields of the given type.
+    pub fn for_type<T: Typed>() -> Self {
+        TableSqlOptions {
+            type_info: Some(T::type_info()),
+            ..Default::default()
+        }
+    }
+
+    /// Create `STRICT` tables. Sqlite then enforces the declared column types, so inserting
+    /// e.g. text into an integer column fails instead of being stored silently.
+    /// Column types are restricted to INTEGER, REAL, TEXT, BLOB and ANY.
+    pub fn use_strict(&mut self, value: bool) {
+        self.strict = value;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Fail if two columns share the same `order`. Otherwise such columns are sorted by the
+    /// name of their field.
+    pub fn use_unique_orders(&mut self, value: bool) {
+        self.unique_orders = value;
+    }
+
+    pub fn is_unique_orders(&self) -> bool {
+        self.unique_orders
+    }
+
+    /// Generate `CREATE TABLE IF NOT EXISTS`, so sqlite skips existing tables instead of
+    /// failing.
+    pub fn use_if_not_exists(&mut self, value: bool) {
+        self.if_not_exists = value;
+    }
+
+    pub fn is_if_not_exists(&self) -> bool {
+        self.if_not_exists
+    }
+
+    /// Set the type whose field attributes are used to generate the columns.
+    pub fn set_type_info(&mut self, type_info: Option<&'static TypeInfo>) {
+        self.type_info = type_info;
+    }
+
+    pub fn get_type_info(&self) -> Option<&'static TypeInfo> {
+        self.type_info
+    }
+
+    /// Set the registry used to look up the tables of related types, e.g. the table referenced
+    /// by a one to one relation. Tables created by `SqliteDatabase::create_table` use the
+    /// registry of the database, unless one is set here.
+    pub fn set_type_registry(&mut self, registry: Option<&AppTypeRegistry>) {
+        self.type_registry = registry.map(|x| x.0.clone());
+    }
+
+    pub fn get_type_registry(&self) -> Option<&TypeRegistryArc> {
+        self.type_registry.as_ref()
+    }
+
+    /// Declare columns of the given types as converted (see `SqliteDatabase::register_converter`).
+    pub(crate) fn with_converted_types(mut self, types: Vec<TypeId>) -> Self {
+        self.converted_types = types;
+        self
+    }
+
+    /// True, if columns of the given type are stored by a custom converter.
+    pub(crate) fn is_converted(&self, type_id: TypeId) -> bool {
+        self.converted_types.contains(&type_id)
+    }
+
+    /// Get the type info of the given field, if a type is set.
+    pub(crate) fn get_field_type_info(&self, field: &str) -> Option<&'static TypeInfo> {
+        match self.type_info? {
+            TypeInfo::Struct(info) => info.field(field)?.type_info(),
+            _ => None,
+        }
+    }
+
+    /// Get the attribute of type `A` of the given field, if a type is set.
+    pub fn get_field_attribute<A: Reflect>(&self, field: &str) -> Option<&'static A> {
+        get_field_attribute::<A>(self.type_info?, field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TableSqlOptions;
+    use crate::prelude::Collate;
+    use bevy::prelude::*;
+
+    #[derive(Default, Reflect)]
+    struct Player {
+        #[reflect(@Collate::NoCase)]
+        name: String,
+    }
+
+    #[test]
+    fn test_default_options() {
+        let options = TableSqlOptions::new();
+        assert!(!options.is_strict());
+        assert!(!options.is_unique_orders());
+        assert!(!options.is_if_not_exists());
+        assert!(options.get_type_info().is_none());
+        assert!(options.get_type_registry().is_none());
+        assert_eq!(options.get_field_attribute::<Collate>("name"), None);
+    }
+
+    #[test]
+    fn test_setters() {
+        let mut options = TableSqlOptions::new();
+        options.use_strict(true);
+        assert!(options.is_strict());
+        options.use_unique_orders(true);
+        assert!(options.is_unique_orders());
+        options.use_if_not_exists(true);
+        assert!(options.is_if_not_exists());
+    }
+
+    #[test]
+    fn test_for_type() {
+        let options = TableSqlOptions::for_type::<Player>();
+        assert_eq!(
+            options.get_field_attribute::<Collate>("name"),
+            Some(&Collate::NoCase)
+        );
+    }
+}
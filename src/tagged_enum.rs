@@ -0,0 +1,191 @@
+use crate::attributes::TaggedEnum;
+use bevy::prelude::*;
+use bevy::reflect::{
+    DynamicEnum, DynamicTuple, DynamicVariant, EnumInfo, ReflectMut, ReflectRef, Type, TypeInfo,
+    VariantInfo,
+};
+use bevy_erm::prelude::{ColumnDefinition, TableDefinition};
+use rusqlite::types::Value;
+use rusqlite::Row;
+
+/// The suffix of the column storing the value of the variant of a `TaggedEnum`.
+pub const TAGGED_VALUE_SUFFIX: &str = "_value";
+
+/// The info of the given type and the type of the values of its variants, if it is an enum
+/// marked as `TaggedEnum` whose variants all hold a single value of the same type.
+pub(crate) fn get_tagged_enum_info(type_info: &TypeInfo) -> Option<(&EnumInfo, Type)> {
+    let TypeInfo::Enum(info) = type_info else {
+        return None;
+    };
+
+    if !info.has_attribute::<TaggedEnum>() {
+        return None;
+    }
+
+    let mut value_type: Option<Type> = None;
+    for variant in info.iter() {
+        let VariantInfo::Tuple(variant) = variant else {
+            return None;
+        };
+
+        let (1, Some(field)) = (variant.field_len(), variant.field_at(0)) else {
+            return None;
+        };
+
+        match value_type {
+            Some(ty) if ty != *field.ty() => return None,
+            _ => value_type = Some(*field.ty()),
+        }
+    }
+
+    Some((info, value_type?))
+}
+
+/// The type info of the `TaggedEnum` stored in the given column, if the type of the struct is
+/// known.
+pub(crate) fn get_field_tagged_enum(
+    type_info: Option<&TypeInfo>,
+    col: &ColumnDefinition,
+) -> Option<&'static TypeInfo> {
+    let Some(TypeInfo::Struct(info)) = type_info else {
+        return None;
+    };
+
+    let enum_type = info.field(&col.rust_name)?.type_info()?;
+    get_tagged_enum_info(enum_type)?;
+
+    Some(enum_type)
+}
+
+/// The names of the columns storing the variant and its value, e.g. `damage`, `damage_value`.
+pub(crate) fn get_tagged_column_names(col: &ColumnDefinition) -> [String; 2] {
+    [
+        col.sql_name.clone(),
+        format!("{}{}", col.sql_name, TAGGED_VALUE_SUFFIX),
+    ]
+}
+
+macro_rules! tagged_values {
+    ($($t:ty => $sql:literal),* $(,)?) => {
+        /// The column type of the values of the variants.
+        pub(crate) fn get_tagged_value_sql_type(ty: &Type) -> Option<&'static str> {
+            $(
+                if ty.is::<$t>() {
+                    return Some($sql);
+                }
+            )*
+
+            None
+        }
+
+        fn get_tagged_value(value: &dyn PartialReflect) -> Option<Value> {
+            let value = value.try_as_reflect()?;
+            $(
+                if let Some(v) = value.downcast_ref::<$t>() {
+                    return Some(Value::from(v.clone()));
+                }
+            )*
+
+            None
+        }
+
+        fn read_tagged_value(
+            ty: &Type,
+            row: &Row,
+            x: usize,
+        ) -> rusqlite::Result<Option<Box<dyn PartialReflect>>> {
+            $(
+                if ty.is::<$t>() {
+                    return Ok(Some(Box::new(row.get::<usize, $t>(x)?)));
+                }
+            )*
+
+            Ok(None)
+        }
+    };
+}
+
+tagged_values!(
+    i8 => "INTEGER",
+    i16 => "INTEGER",
+    i32 => "INTEGER",
+    i64 => "INTEGER",
+    u8 => "INTEGER",
+    u16 => "INTEGER",
+    u32 => "INTEGER",
+    f32 => "REAL",
+    f64 => "REAL",
+    bool => "INTEGER",
+    String => "TEXT",
+);
+
+/// The name of the variant and its value, if the given value is a `TaggedEnum`.
+pub(crate) fn get_tagged_values(value: &dyn Reflect) -> Option<[Value; 2]> {
+    value
+        .get_represented_type_info()
+        .and_then(get_tagged_enum_info)?;
+
+    let ReflectRef::Enum(value) = value.reflect_ref() else {
+        return None;
+    };
+
+    Some([
+        Value::Text(value.variant_name().to_string()),
+        get_tagged_value(value.field_at(0)?)?,
+    ])
+}
+
+/// Read the variants and values of all tagged enums and assign them to their fields. Enums with
+/// missing columns keep their value.
+pub(crate) fn read_tagged_columns(
+    table_def: &TableDefinition,
+    names: &[String],
+    row: &Row,
+    value: &mut dyn PartialReflect,
+) -> rusqlite::Result<()> {
+    let type_info = value.get_represented_type_info();
+    let ReflectMut::Struct(target) = value.reflect_mut() else {
+        return Ok(());
+    };
+
+    for col in table_def.fields.values() {
+        let Some(enum_type) = get_field_tagged_enum(type_info, col) else {
+            continue;
+        };
+        let Some((info, value_type)) = get_tagged_enum_info(enum_type) else {
+            continue;
+        };
+
+        let [tag, value] = get_tagged_column_names(col);
+        let (Some(tag), Some(value)) = (
+            names.iter().position(|n| *n == tag),
+            names.iter().position(|n| *n == value),
+        ) else {
+            continue;
+        };
+
+        let variant = row.get::<usize, String>(tag)?;
+        let Some(variant) = info.variant(&variant).map(|v| v.name()) else {
+            return Err(rusqlite::Error::FromSqlConversionFailure(
+                tag,
+                rusqlite::types::Type::Text,
+                format!("{variant} is no variant of {}.", info.type_path()).into(),
+            ));
+        };
+
+        let Some(data) = read_tagged_value(&value_type, row, value)? else {
+            continue;
+        };
+
+        let mut tuple = DynamicTuple::default();
+        tuple.insert_boxed(data);
+        let mut dyn_enum = DynamicEnum::new(variant, DynamicVariant::Tuple(tuple));
+        dyn_enum.set_represented_type(Some(enum_type));
+
+        if let Some(field) = target.field_mut(&col.rust_name) {
+            field.apply(&dyn_enum);
+        }
+    }
+
+    Ok(())
+}
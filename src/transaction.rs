@@ -0,0 +1,154 @@
+use crate::prelude::{SqliteDatabase, SqliteDbError};
+use bevy::prelude::*;
+use bevy_erm::prelude::TableDefinition;
+use rusqlite::ToSql;
+use std::panic::{self, AssertUnwindSafe};
+
+/// The writes available inside of `SqliteDatabase::transaction`. All of them are committed
+/// together or not at all.
+pub struct TransactionCtx<'a> {
+    database: &'a mut SqliteDatabase,
+}
+
+impl TransactionCtx<'_> {
+    /// Same as `SqliteDatabase::execute`, but as part of the transaction.
+    pub fn execute(
+        &mut self,
+        query: &str,
+        parameter: &[&dyn ToSql],
+    ) -> Result<usize, SqliteDbError> {
+        self.database.execute(query, parameter)
+    }
+
+    /// Same as `SqliteDatabase::insert`, but as part of the transaction.
+    pub fn insert<T: Reflect + Default + TypePath + Struct>(
+        &mut self,
+        def: &TableDefinition,
+        value: &T,
+        registry: &AppTypeRegistry,
+    ) -> Result<usize, SqliteDbError> {
+        self.database.insert(def, value, registry)
+    }
+}
+
+impl SqliteDatabase {
+    /// Run the closure inside a transaction. The changes are committed if the closure returns
+    /// ok and rolled back if it returns an error or panics. Panics are passed on after the
+    /// rollback. Use `savepoint` to nest transactions.
+    pub fn transaction<F, R>(&mut self, f: F) -> Result<R, SqliteDbError>
+    where
+        F: FnOnce(&mut TransactionCtx) -> Result<R, SqliteDbError>,
+    {
+        self.execute("BEGIN;", &[])?;
+
+        let mut ctx = TransactionCtx { database: self };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&mut ctx)));
+        match result {
+            Ok(Ok(r)) => {
+                self.execute("COMMIT;", &[])?;
+                Ok(r)
+            }
+            Ok(Err(e)) => {
+                self.execute("ROLLBACK;", &[])?;
+                Err(e)
+            }
+            Err(payload) => {
+                let _ = self.execute("ROLLBACK;", &[]);
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{SqliteConnectionSettings, SqliteDatabase};
+    use bevy::prelude::*;
+    use bevy_erm::prelude::{ErmTypesRegistry, Key};
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Runner {
+        #[reflect(@Key)]
+        id: i32,
+        name: String,
+    }
+
+    fn update_database_path(
+        mut settings: ResMut<SqliteConnectionSettings>,
+        app_registry: Res<AppTypeRegistry>,
+        mut registry: ResMut<ErmTypesRegistry>,
+    ) {
+        settings.set_data_source("test_transaction.sqlite");
+        registry.register_type::<Runner>(&app_registry);
+    }
+
+    fn run_test(
+        registry: Res<AppTypeRegistry>,
+        erm_registry: Res<ErmTypesRegistry>,
+        mut database: ResMut<SqliteDatabase>,
+        settings: Res<SqliteConnectionSettings>,
+    ) {
+        database.open(&settings).unwrap();
+
+        let table = erm_registry.get_table_definition("Runner").unwrap();
+        database.create_table(table).unwrap();
+        let count_query = "SELECT COUNT(*) FROM Runner;";
+
+        // The second insert fails, because its key is taken by the first one.
+        let result = database.transaction(|tx| {
+            let runner = Runner {
+                name: "Runna vom Sofa".to_string(),
+                ..Default::default()
+            };
+            tx.insert(table, &runner, &registry)?;
+            tx.execute(
+                "INSERT INTO Runner (id, name) VALUES (1, 'Anne Straße');",
+                &[],
+            )?;
+            tx.insert(table, &runner, &registry)?;
+            Ok(())
+        });
+        assert!(result.is_err());
+        let count = database.query_scalar::<i32>(count_query, &[]).unwrap();
+        assert_eq!(count, Some(0));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            database.transaction::<_, ()>(|tx| {
+                tx.execute("INSERT INTO Runner (name) VALUES ('Runna vom Sofa');", &[])?;
+                panic!("Crashed while inserting");
+            })
+        }));
+        assert!(result.is_err());
+        let count = database.query_scalar::<i32>(count_query, &[]).unwrap();
+        assert_eq!(count, Some(0));
+
+        let inserted = database
+            .transaction(|tx| {
+                tx.execute("INSERT INTO Runner (name) VALUES ('Anne Straße');", &[])?;
+                tx.execute("INSERT INTO Runner (name) VALUES ('Karl Kopf');", &[])
+            })
+            .unwrap();
+        assert_eq!(inserted, 1);
+        let count = database.query_scalar::<i32>(count_query, &[]).unwrap();
+        assert_eq!(count, Some(2));
+
+        // Delete the file, so we can rerun the test
+        std::fs::remove_file(settings.get_data_source()).unwrap();
+
+        database.close().unwrap();
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut app = App::new();
+        app.insert_resource(AppTypeRegistry::default());
+        app.add_plugins(SqliteDatabase::default());
+        app.register_type::<Runner>();
+        app.add_systems(PreStartup, update_database_path);
+        app.add_systems(Startup, run_test);
+
+        app.update();
+    }
+}
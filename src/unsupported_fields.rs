@@ -0,0 +1,106 @@
+use crate::attributes::get_integer_enum_info;
+use crate::child_table::is_stored_in_child_table;
+use crate::date_time::is_date_time_column;
+use crate::inline::{get_inline_column_sql, get_inline_type};
+use crate::plugin::{is_assigned_column, is_zero_sized};
+use crate::prelude::{SqliteDatabase, TableSqlOptions};
+use crate::range::get_range_sql_type;
+use crate::tagged_enum::{get_tagged_enum_info, get_tagged_value_sql_type};
+use bevy::prelude::*;
+use bevy::reflect::{GetTypeRegistration, TypeInfo, Typed};
+use bevy_erm::prelude::{ColumnDefinition, ErmTypesRegistry, SqlType};
+
+impl SqliteDatabase {
+    /// List the fields of `T` which cannot be stored, as (field name, type path). Use this to
+    /// audit a type during development instead of running into a panic on the first insert.
+    /// Only the type is inspected, so no connection is required.
+    pub fn unsupported_fields<T: Struct + Reflect + Typed + GetTypeRegistration>(
+    ) -> Vec<(String, String)> {
+        let TypeInfo::Struct(info) = T::type_info() else {
+            return Vec::new();
+        };
+
+        let app_registry = AppTypeRegistry::default();
+        app_registry.write().register::<T>();
+        let mut registry = ErmTypesRegistry::default();
+        registry.register_type::<T>(&app_registry);
+        let Some(table) = registry.get_table_definition(T::short_type_path()) else {
+            return Vec::new();
+        };
+
+        let options = TableSqlOptions::for_type::<T>();
+        info.iter()
+            .filter(|field| {
+                table
+                    .fields
+                    .values()
+                    .find(|col| col.rust_name == field.name())
+                    .is_some_and(|col| !is_supported(col, &options))
+            })
+            .map(|field| (field.name().to_string(), field.type_path().to_string()))
+            .collect()
+    }
+}
+
+/// True, if the column can be created, written and read.
+fn is_supported(col: &ColumnDefinition, options: &TableSqlOptions) -> bool {
+    if is_stored_in_child_table(col) || is_zero_sized(options.get_type_info(), col) {
+        return true;
+    }
+
+    if let Some(inner) = get_inline_type(options.get_type_info(), col) {
+        return get_inline_column_sql(inner).is_ok();
+    }
+
+    let field_type_info = options.get_field_type_info(&col.rust_name);
+    match col.sql_type {
+        SqlType::None => {
+            is_assigned_column(col)
+                || get_range_sql_type(col).is_some()
+                || field_type_info.and_then(get_integer_enum_info).is_some()
+                || field_type_info
+                    .and_then(get_tagged_enum_info)
+                    .and_then(|(_, ty)| get_tagged_value_sql_type(&ty))
+                    .is_some()
+        }
+        SqlType::Date(_) | SqlType::DateTime(_) => {
+            col.ty.is::<String>() || is_date_time_column(col)
+        }
+        SqlType::Time(_) => col.ty.is::<f32>() || col.ty.is::<f64>(),
+        SqlType::Integer(..)
+        | SqlType::UnsingedInteger(..)
+        | SqlType::Float(..)
+        | SqlType::Text(_)
+        | SqlType::Blob(_)
+        | SqlType::Boolean(_)
+        | SqlType::One2One(..)
+        | SqlType::Many2Many(..) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::SqliteDatabase;
+    use bevy::prelude::*;
+    use bevy_erm::prelude::Key;
+    use std::collections::HashSet;
+
+    #[derive(Default, Reflect)]
+    #[reflect(Default)]
+    struct Inventory {
+        #[reflect(@Key)]
+        id: i32,
+        owner: String,
+        position: Vec3,
+        level: Option<u8>,
+        items: HashSet<u32>,
+    }
+
+    #[test]
+    fn test_unsupported_fields() {
+        let fields = SqliteDatabase::unsupported_fields::<Inventory>();
+        let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["items"]);
+        assert!(fields[0].1.contains("HashSet<u32"));
+    }
+}
@@ -1,12 +1,38 @@
+use crate::attributes::{
+    get_discriminant, get_field_attribute, get_integer_enum_info, BoolAsText, Checksum,
+    RelateByName,
+};
+use crate::converters::Converters;
+use crate::date_time::get_date_time_value;
+use crate::decimal::get_decimal_value;
+use crate::entity_names::EntityNames;
+use crate::error::UnsupportedValue;
+use crate::flatten::EULER_ROT;
+use crate::normalize::get_normalize;
+use crate::range::get_range_values;
+use crate::relation::get_related_key;
+use crate::shared::deref_shared;
+use crate::small_string::get_small_string_value;
+use crate::tagged_enum::get_tagged_values;
+use bevy::math::{Affine2, Affine3A, Isometry2d, Isometry3d, Mat3A, Vec3A};
 use bevy::prelude::*;
-use bevy::reflect::TypeInfo;
+use bevy::reflect::{Enum, ReflectRef, TypeInfo};
+use bevy::time::Stopwatch;
 use bevy_erm::prelude::*;
 use rusqlite::types::*;
 use rusqlite::ToSql;
+use std::borrow::Cow;
+use std::net::{IpAddr, SocketAddr};
 
 pub struct ValueWrapper<'a> {
     reg_type: TypeInfo,
     getter: &'a dyn Reflect,
+    bool_as_text: bool,
+    relate_by_name: bool,
+    checksum: bool,
+    normalize: Option<fn(&str) -> String>,
+    component: Option<usize>,
+    value: Option<Value>,
 }
 
 impl<'a> ValueWrapper<'a> {
@@ -24,16 +50,153 @@ impl<'a> ValueWrapper<'a> {
             .unwrap()
             .type_info();
         let field = value.field(field_name).unwrap().try_as_reflect().unwrap();
+        let field = deref_shared(field).unwrap_or(field);
+        let bool_as_text = get_field_attribute::<BoolAsText>(type_info, field_name).is_some();
+        let relate_by_name = get_field_attribute::<RelateByName>(type_info, field_name).is_some();
+        let checksum = get_field_attribute::<Checksum>(type_info, field_name).is_some();
+        let normalize = get_normalize(type_info, field_name);
 
         ValueWrapper {
             reg_type: type_info.to_owned(),
             getter: field,
+            bool_as_text,
+            relate_by_name,
+            checksum,
+            normalize,
+            component: None,
+            value: None,
         }
     }
+
+    /// Write only the given component of a flattened vector or range.
+    pub(crate) fn with_component(mut self, component: usize) -> Self {
+        self.component = Some(component);
+        self
+    }
+
+    /// Write the given value instead of the field, e.g. a field of an inlined struct.
+    pub(crate) fn with_getter(mut self, getter: &'a dyn Reflect) -> Self {
+        self.getter = getter;
+        self
+    }
+
+    /// True, if the field is marked as `Checksum`.
+    pub(crate) fn is_checksum(&self) -> bool {
+        self.checksum
+    }
+
+    /// Write the given value instead of the value of the field.
+    pub(crate) fn set_value(&mut self, value: Value) {
+        self.value = Some(value);
+    }
+
+    /// Write the value returned by the custom converter of the field type, if there is one.
+    pub(crate) fn convert(&mut self, converters: &Converters) {
+        if self.value.is_some() || self.component.is_some() {
+            return;
+        }
+
+        if let Some(value) = converters.to_sql(self.getter) {
+            self.value = Some(value);
+        }
+    }
+
+    /// Replace a relation marked as `RelateByName` with the name of the related entity.
+    pub(crate) fn resolve_name(&mut self, names: &EntityNames) -> Result<(), String> {
+        if !self.relate_by_name {
+            return Ok(());
+        }
+
+        let Some(entity) = self.getter.downcast_ref::<Option<Entity>>() else {
+            return Err("Only Option<Entity> fields can be related by name.".to_string());
+        };
+
+        self.value = Some(match entity {
+            Some(entity) => match names.get_name(*entity) {
+                Some(name) => Value::Text(name.to_string()),
+                None => return Err(format!("Entity {entity} has no name.")),
+            },
+            None => Value::Null,
+        });
+
+        Ok(())
+    }
+}
+
+impl ValueWrapper<'_> {
+    /// Convert the inner value of an optional field, keeping the attributes of the field.
+    fn inner_to_sql(&self, inner: &dyn Reflect) -> rusqlite::Result<Value> {
+        let wrapper = ValueWrapper {
+            reg_type: self.reg_type.clone(),
+            getter: inner,
+            bool_as_text: self.bool_as_text,
+            relate_by_name: false,
+            checksum: self.checksum,
+            normalize: self.normalize,
+            component: None,
+            value: None,
+        };
+
+        match wrapper.to_sql()? {
+            ToSqlOutput::Owned(v) => Ok(v),
+            ToSqlOutput::Borrowed(v) => Ok(v.into()),
+            _ => Err(rusqlite::Error::ToSqlConversionFailure(
+                "The inner value cannot be stored.".into(),
+            )),
+        }
+    }
+}
+
+/// True, if the enum is an `Option`.
+fn is_option(value: &dyn Enum) -> bool {
+    value
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_path().starts_with("core::option::Option<"))
 }
 
 impl ToSql for ValueWrapper<'_> {
     fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        if let Some(value) = &self.value {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(value.clone()));
+        }
+
+        if self.relate_by_name {
+            return Err(rusqlite::Error::ToSqlConversionFailure(
+                "The name of the related entity was not resolved.".into(),
+            ));
+        }
+
+        if let Some(component) = self.component {
+            let components: Vec<Value> = if let Some(v) = self.getter.downcast_ref::<Vec2>() {
+                v.to_array().map(Value::from).to_vec()
+            } else if let Some(v) = self.getter.downcast_ref::<Vec3>() {
+                v.to_array().map(Value::from).to_vec()
+            } else if let Some(v) = self.getter.downcast_ref::<Vec4>() {
+                v.to_array().map(Value::from).to_vec()
+            } else if let Some(v) = self.getter.downcast_ref::<Quat>() {
+                let (x, y, z) = v.to_euler(EULER_ROT);
+                [x, y, z].map(Value::from).to_vec()
+            } else if let Some(v) = get_range_values(self.getter) {
+                v.to_vec()
+            } else if let Some(v) = get_tagged_values(self.getter) {
+                v.to_vec()
+            } else {
+                Vec::new()
+            };
+
+            return match components.get(component) {
+                Some(v) => rusqlite::Result::Ok(ToSqlOutput::Owned(v.clone())),
+                None => Err(rusqlite::Error::ToSqlConversionFailure(
+                    format!("Component {component} does not exist.").into(),
+                )),
+            };
+        }
+
+        // Text marked as `Normalize` is stored in its normalization form.
+        if let (Some(normalize), Some(v)) = (self.normalize, self.getter.downcast_ref::<String>()) {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(normalize(v))));
+        }
+
         let ty = *self.getter.reflect_type_info().ty();
 
         // Unsigned Integer
@@ -99,6 +262,16 @@ impl ToSql for ValueWrapper<'_> {
             )));
         }
 
+        // Boolean
+        if ty == bevy::reflect::Type::of::<bool>() {
+            let v = *self.getter.downcast_ref::<bool>().unwrap();
+            if self.bool_as_text {
+                return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+            }
+
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Integer(v as i64)));
+        }
+
         // Text
         if ty == bevy::reflect::Type::of::<String>() {
             return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(
@@ -112,6 +285,10 @@ impl ToSql for ValueWrapper<'_> {
             )));
         }
 
+        if let Some(v) = self.getter.downcast_ref::<Cow<'static, str>>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+        }
+
         // Vectors
         if ty == bevy::reflect::Type::of::<Vec2>() {
             return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(
@@ -181,21 +358,209 @@ impl ToSql for ValueWrapper<'_> {
             )));
         }
 
-        panic!("Cannot convert type {:?}", self.reg_type.ty().ident());
+        // Directions are stored as their unit vector.
+        if let Some(v) = self.getter.downcast_ref::<Dir2>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(v.as_vec2().into_blob())));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Option<Dir2>>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(match v {
+                Some(dir) => Value::Blob(dir.as_vec2().into_blob()),
+                None => Value::Null,
+            }));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Dir3>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(v.as_vec3().into_blob())));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Option<Dir3>>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(match v {
+                Some(dir) => Value::Blob(dir.as_vec3().into_blob()),
+                None => Value::Null,
+            }));
+        }
+
+        // Vec3A is stored like a Vec3, without the padding lane, so columns written from either
+        // type can be read into the other.
+        if let Some(v) = self.getter.downcast_ref::<Vec3A>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(
+                Vec3::from(*v).into_blob(),
+            )));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Option<Vec3A>>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(match v {
+                Some(v) => Value::Blob(Vec3::from(*v).into_blob()),
+                None => Value::Null,
+            }));
+        }
+
+        // Isometries are stored as the blob of their rotation followed by their translation.
+        if let Some(v) = self.getter.downcast_ref::<Isometry2d>() {
+            let rotation = Vec2::new(v.rotation.cos, v.rotation.sin);
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(
+                [rotation.into_blob(), v.translation.into_blob()].concat(),
+            )));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Isometry3d>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(
+                [
+                    v.rotation.into_blob(),
+                    Vec3::from(v.translation).into_blob(),
+                ]
+                .concat(),
+            )));
+        }
+
+        // Matrices are stored as the blobs of their columns, so a `Mat3A` is stored like a
+        // `Mat3`. Affine transforms are stored as the blob of their matrix followed by their
+        // translation.
+        if let Some(v) = self.getter.downcast_ref::<Mat3A>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(mat3a_blob(v))));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Affine2>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(
+                [
+                    v.matrix2.x_axis.into_blob(),
+                    v.matrix2.y_axis.into_blob(),
+                    v.translation.into_blob(),
+                ]
+                .concat(),
+            )));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<Affine3A>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Blob(
+                [
+                    mat3a_blob(&v.matrix3),
+                    Vec3::from(v.translation).into_blob(),
+                ]
+                .concat(),
+            )));
+        }
+
+        // Stopwatches are stored as their elapsed seconds.
+        if let Some(v) = self.getter.downcast_ref::<Stopwatch>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Real(v.elapsed_secs_f64())));
+        }
+
+        // Timers are stored as their remaining seconds.
+        if let Some(v) = self.getter.downcast_ref::<Timer>() {
+            let remaining = v.remaining().as_secs_f64();
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Real(remaining)));
+        }
+
+        // Timestamps are stored as RFC 3339 text including their offset.
+        if let Some(v) = get_date_time_value(self.getter) {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(v));
+        }
+
+        // Decimals are stored as their canonical text, so they are not rounded.
+        if let Some(v) = get_decimal_value(self.getter) {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(v));
+        }
+
+        // Small strings are stored as text like `String`.
+        if let Some(v) = get_small_string_value(self.getter) {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(v));
+        }
+
+        // Entities are stored as their bits, which are only valid within the current session.
+        if let Some(v) = self.getter.downcast_ref::<Entity>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Integer(v.to_bits() as i64)));
+        }
+
+        // Optional entity relations are stored as the entity bits or NULL.
+        if let Some(v) = self.getter.downcast_ref::<Option<Entity>>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(match v {
+                Some(entity) => Value::Integer(entity.to_bits() as i64),
+                None => Value::Null,
+            }));
+        }
+
+        // Network addresses are stored in their textual form. They are matched via `Any`,
+        // because not every bevy version implements the reflection traits for them.
+        if let Some(v) = self.getter.as_any().downcast_ref::<IpAddr>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+        }
+
+        if let Some(v) = self.getter.as_any().downcast_ref::<SocketAddr>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+        }
+
+        // Characters are stored as text of a single character. 128 bit integers exceed the
+        // integers of sqlite, so they are stored as decimal text to keep their precision.
+        if let Some(v) = self.getter.downcast_ref::<char>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<u128>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+        }
+
+        if let Some(v) = self.getter.downcast_ref::<i128>() {
+            return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Text(v.to_string())));
+        }
+
+        // Fieldless enums marked as `IntegerEnum` are stored as the discriminant of the variant.
+        if let ReflectRef::Enum(value) = self.getter.reflect_ref() {
+            if let Some(discriminant) = value
+                .get_represented_type_info()
+                .and_then(get_integer_enum_info)
+                .and_then(|info| get_discriminant(info, value.variant_index()))
+            {
+                return rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Integer(discriminant)));
+            }
+
+            // Optional values are stored as NULL or like their inner value.
+            if is_option(value) {
+                return match value.field_at(0).and_then(|x| x.try_as_reflect()) {
+                    Some(inner) => self.inner_to_sql(inner).map(ToSqlOutput::Owned),
+                    None => rusqlite::Result::Ok(ToSqlOutput::Owned(Value::Null)),
+                };
+            }
+        }
+
+        // Structs with a key are stored as their key (one to one relations).
+        if let ReflectRef::Struct(value) = self.getter.reflect_ref() {
+            if let Some(key) = get_related_key(value) {
+                return self.inner_to_sql(key).map(ToSqlOutput::Owned);
+            }
+        }
+
+        Err(rusqlite::Error::ToSqlConversionFailure(Box::new(
+            UnsupportedValue(self.getter.as_any().type_id()),
+        )))
     }
 }
 
+/// The blob of the columns of the matrix, without the padding lanes.
+fn mat3a_blob(v: &Mat3A) -> Vec<u8> {
+    [
+        Vec3::from(v.x_axis).into_blob(),
+        Vec3::from(v.y_axis).into_blob(),
+        Vec3::from(v.z_axis).into_blob(),
+    ]
+    .concat()
+}
+
 #[cfg(test)]
 mod tests {
     use super::ValueWrapper;
+    use crate::prelude::SqliteDbError;
     use bevy::prelude::*;
     use rusqlite::ToSql;
+    use std::any::TypeId;
 
     fn prepare_app() -> App {
         let mut app = App::new();
         let registry = AppTypeRegistry::default();
         app.insert_resource(registry);
         app.register_type::<Player>();
+        app.register_type::<Marker>();
 
         app
     }
@@ -259,4 +624,29 @@ mod tests {
 
         app.update();
     }
+
+    #[derive(Default, Reflect, Clone)]
+    #[reflect(Default)]
+    struct Marker {
+        position: (u8, u8),
+    }
+
+    fn update_unsupported_test(registry: ResMut<AppTypeRegistry>) {
+        let marker = Marker::default();
+        let wrapper = ValueWrapper::build::<Marker>(&marker, "position", &registry);
+        let e = wrapper.to_sql().unwrap_err();
+
+        let SqliteDbError::UnsupportedType(ty) = SqliteDbError::from_execute(e) else {
+            panic!("Expected an unsupported type.");
+        };
+        assert_eq!(ty, TypeId::of::<(u8, u8)>());
+    }
+
+    #[test]
+    fn test_wrapper_unsupported_type() {
+        let mut app = prepare_app();
+        app.add_systems(Update, update_unsupported_test);
+
+        app.update();
+    }
 }